@@ -0,0 +1,286 @@
+use crate::input;
+use crate::solver::SolverKind;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// the command-line usage, printed when the arguments can't be parsed
+pub const USAGE: &str = "\
+usage: guessing_game [options]
+
+options:
+  --difficulty <easy|normal|hard>  pick a preset range and attempt limit
+  --min <n>                        smallest number the secret can be (default 1)
+  --max <n>                        largest number the secret can be (default 100)
+  --attempts <n>                   number of guesses before you lose (default unlimited)
+  --hints                          say how close each guess was and narrow down the range as you go
+  --name <name>                    name to put on the high-score table (default $USER)
+  --scores                         print the high-score table instead of playing
+  --auto <binary|random>           let a solver play instead of you and print statistics
+  --games <n>                      number of games to play with --auto (default 1000)
+  --reverse                        you think of a number and the computer guesses it
+  --players <a,b,...>              take turns with other players in this terminal
+  --serve <addr:port>              host a race that other players can --join, e.g. 127.0.0.1:7878
+  --join <addr:port>               join a race hosted with --serve, using --name as your name
+  --seed <n>                       seed for --auto, so the statistics are reproducible (default 0)
+  --scores-file <path>             where to keep high scores (default $XDG_DATA_HOME/guessing_game/scores.json)
+
+--min, --max and --attempts override whatever the difficulty preset sets";
+
+// named presets that set both the range and the number of attempts
+// the attempt limits leave a little slack over what a perfect binary search needs for each range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // (min, max, attempts) for the preset
+    fn preset(self) -> (u32, u32, u32) {
+        match self {
+            Difficulty::Easy => (1, 50, 10),
+            Difficulty::Normal => (1, 100, 8),
+            Difficulty::Hard => (1, 1000, 10),
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Difficulty {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Difficulty, ConfigError> {
+        match s.to_ascii_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(ConfigError::UnknownDifficulty(s.to_string())),
+        }
+    }
+}
+
+// what the program should do when it starts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    Play,
+    Scores,
+    Auto(SolverKind),
+    Reverse,
+    // several players taking turns in one terminal
+    Local(Vec<String>),
+    // host a networked race on this address
+    Serve(String),
+    // join a networked race hosted at this address
+    Join(String),
+}
+
+// everything that decides how a round is played
+// the default is the original game: 1 to 100 with as many guesses as you like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Config {
+    pub mode: Mode,
+    pub min: u32,
+    pub max: u32,
+    pub max_attempts: Option<u32>,
+    pub difficulty: Option<Difficulty>,
+    pub hints: bool,
+    pub player: Option<String>,
+    pub scores_file: Option<PathBuf>,
+    pub games: u32,
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            mode: Mode::Play,
+            min: 1,
+            max: 100,
+            max_attempts: None,
+            difficulty: None,
+            hints: false,
+            player: None,
+            scores_file: None,
+            games: 1000,
+            seed: 0,
+        }
+    }
+}
+
+// everything that can go wrong turning the command line into a Config
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidNumber { option: String, value: String },
+    UnknownDifficulty(String),
+    UnknownSolver(String),
+    TooFewPlayers,
+    EmptyRange { min: u32, max: u32 },
+    ZeroAttempts,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::UnknownOption(option) => write!(f, "unknown option '{option}'"),
+            ConfigError::MissingValue(option) => write!(f, "'{option}' needs a value"),
+            ConfigError::InvalidNumber { option, value } => {
+                write!(f, "'{value}' is not a valid number for '{option}'")
+            }
+            ConfigError::UnknownDifficulty(name) => {
+                write!(
+                    f,
+                    "unknown difficulty '{name}', expected easy, normal or hard"
+                )
+            }
+            ConfigError::UnknownSolver(name) => {
+                write!(f, "unknown solver '{name}', expected binary or random")
+            }
+            ConfigError::TooFewPlayers => {
+                write!(f, "--players needs at least two comma-separated names")
+            }
+            ConfigError::EmptyRange { min, max } => {
+                write!(
+                    f,
+                    "the minimum ({min}) can't be larger than the maximum ({max})"
+                )
+            }
+            ConfigError::ZeroAttempts => write!(f, "the number of attempts must be at least 1"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Config {
+    // the Config for a difficulty preset, before any explicit options are applied on top
+    pub fn preset(difficulty: Difficulty) -> Config {
+        let (min, max, attempts) = difficulty.preset();
+        Config {
+            min,
+            max,
+            max_attempts: Some(attempts),
+            difficulty: Some(difficulty),
+            ..Config::default()
+        }
+    }
+
+    // build a Config from command-line arguments, not including the program name
+    // the preset is applied first no matter where --difficulty appears, so --max 500 --difficulty hard still ends up with a max of 500
+    pub fn from_args<I, S>(args: I) -> Result<Config, ConfigError>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut difficulty = None;
+        let mut min = None;
+        let mut max = None;
+        let mut attempts = None;
+        let mut mode = Mode::Play;
+        let mut player = None;
+        let mut hints = false;
+        let mut scores_file = None;
+        let mut games = None;
+        let mut seed = None;
+
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(option) = args.next() {
+            match option.as_str() {
+                "--difficulty" => difficulty = Some(value(&option, args.next())?.parse()?),
+                "--min" => min = Some(number(&option, args.next())?),
+                "--max" => max = Some(number(&option, args.next())?),
+                "--attempts" => attempts = Some(number(&option, args.next())?),
+                "--hints" => hints = true,
+                "--name" => player = Some(value(&option, args.next())?),
+                "--scores" => mode = Mode::Scores,
+                "--reverse" => mode = Mode::Reverse,
+                "--players" => {
+                    let players: Vec<String> = value(&option, args.next())?
+                        .split(',')
+                        .map(|name| name.trim().to_string())
+                        .filter(|name| !name.is_empty())
+                        .collect();
+                    if players.len() < 2 {
+                        return Err(ConfigError::TooFewPlayers);
+                    }
+                    mode = Mode::Local(players);
+                }
+                "--serve" => mode = Mode::Serve(value(&option, args.next())?),
+                "--join" => mode = Mode::Join(value(&option, args.next())?),
+                "--auto" => {
+                    let name = value(&option, args.next())?;
+                    let kind =
+                        SolverKind::from_name(&name).ok_or(ConfigError::UnknownSolver(name))?;
+                    mode = Mode::Auto(kind);
+                }
+                "--games" => games = Some(number(&option, args.next())?),
+                "--seed" => seed = Some(number(&option, args.next())?),
+                "--scores-file" => scores_file = Some(value(&option, args.next())?.into()),
+                _ => return Err(ConfigError::UnknownOption(option)),
+            }
+        }
+
+        let mut config = match difficulty {
+            Some(difficulty) => Config::preset(difficulty),
+            None => Config::default(),
+        };
+        // tweaking a preset by hand means the round no longer matches it
+        if min.is_some() || max.is_some() || attempts.is_some() {
+            config.difficulty = None;
+        }
+        config.min = min.unwrap_or(config.min);
+        config.max = max.unwrap_or(config.max);
+        config.max_attempts = attempts.or(config.max_attempts);
+        config.mode = mode;
+        config.player = player;
+        config.hints = hints;
+        config.scores_file = scores_file;
+        config.games = games.unwrap_or(config.games);
+        config.seed = seed.unwrap_or(config.seed);
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    // reject combinations the game can't be played with
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.min > self.max {
+            return Err(ConfigError::EmptyRange {
+                min: self.min,
+                max: self.max,
+            });
+        }
+        if self.max_attempts == Some(0) {
+            return Err(ConfigError::ZeroAttempts);
+        }
+        Ok(())
+    }
+}
+
+fn value(option: &str, value: Option<String>) -> Result<String, ConfigError> {
+    value.ok_or_else(|| ConfigError::MissingValue(option.to_string()))
+}
+
+fn number<T: FromStr>(option: &str, raw: Option<String>) -> Result<T, ConfigError> {
+    let raw = value(option, raw)?;
+    input::parse(&raw).map_err(|_| ConfigError::InvalidNumber {
+        option: option.to_string(),
+        value: raw,
+    })
+}
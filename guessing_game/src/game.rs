@@ -0,0 +1,381 @@
+use crate::config::Config;
+use crate::input;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+// a single round of the guessing game
+// the game only knows the rules, the secret and the guesses made so far; reading input and printing output is left to play()
+#[derive(Debug)]
+pub struct Game {
+    secret: u32,
+    min: u32,
+    max: u32,
+    max_attempts: Option<u32>,
+    hints: bool,
+    history: Vec<(u32, Ordering)>,
+}
+
+// how a call to play() ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Won { guesses: u32 },
+    Lost { secret: u32 },
+    Quit { secret: u32 },
+    // input ran out before the game was over, e.g. piped input that didn't contain the answer
+    InputClosed,
+}
+
+// how close a guess is to the secret, as a share of the whole range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Proximity {
+    // within 5% of the range
+    Burning,
+    // within 15% of the range
+    Warm,
+    Cold,
+}
+
+impl fmt::Display for Proximity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Proximity::Burning => "burning",
+            Proximity::Warm => "warm",
+            Proximity::Cold => "cold",
+        })
+    }
+}
+
+// whether the latest guess got closer to the secret than the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Closer,
+    Farther,
+    Same,
+}
+
+// why a guess wasn't accepted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuessError {
+    OutOfRange { guess: u32, min: u32, max: u32 },
+    Repeated(u32),
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GuessError::OutOfRange { guess, min, max } => {
+                write!(f, "{guess} is not between {min} and {max}.")
+            }
+            GuessError::Repeated(guess) => write!(f, "You already guessed {guess}."),
+        }
+    }
+}
+
+impl Game {
+    // pick the secret with whatever rng the caller hands us, using the original 1 to 100 rules
+    // main passes rand::thread_rng(), anything that wants a repeatable game can pass a seeded rng instead
+    pub fn new<R: Rng + ?Sized>(rng: &mut R) -> Game {
+        Game::with_config(rng, &Config::default())
+    }
+
+    // same as new, but with the range and attempt limit taken from a Config
+    // the Config is expected to have been validated already, so an empty range panics just like gen_range would
+    pub fn with_config<R: Rng + ?Sized>(rng: &mut R, config: &Config) -> Game {
+        Game {
+            secret: rng.gen_range(config.min..=config.max),
+            min: config.min,
+            max: config.max,
+            max_attempts: config.max_attempts,
+            hints: config.hints,
+            history: Vec::new(),
+        }
+    }
+
+    // shorthand for a game whose secret is fully determined by the seed
+    pub fn from_seed(seed: u64) -> Game {
+        Game::new(&mut StdRng::seed_from_u64(seed))
+    }
+
+    // compare a guess against the secret, the same way the original loop did with guess.cmp(&secret_number)
+    // Less means the guess was too small, Greater means it was too big
+    // this doesn't validate anything, use check() first to reject guesses a player shouldn't be charged for
+    pub fn guess(&mut self, guess: u32) -> Ordering {
+        let result = guess.cmp(&self.secret);
+        self.history.push((guess, result));
+        result
+    }
+
+    // whether a guess is worth making: inside the range and not made before
+    pub fn check(&self, guess: u32) -> Result<(), GuessError> {
+        if guess < self.min || guess > self.max {
+            return Err(GuessError::OutOfRange {
+                guess,
+                min: self.min,
+                max: self.max,
+            });
+        }
+        if self.history.iter().any(|&(previous, _)| previous == guess) {
+            return Err(GuessError::Repeated(guess));
+        }
+        Ok(())
+    }
+
+    pub fn guesses(&self) -> u32 {
+        self.history.len() as u32
+    }
+
+    // every guess made so far, oldest first, with how it compared to the secret
+    pub fn history(&self) -> &[(u32, Ordering)] {
+        &self.history
+    }
+
+    // the smallest and largest numbers the secret can still be, given the guesses so far
+    pub fn narrowed(&self) -> (u32, u32) {
+        self.history.iter().fold(
+            (self.min, self.max),
+            |(low, high), &(guess, result)| match result {
+                Ordering::Less => (low.max(guess + 1), high),
+                Ordering::Greater => (low, high.min(guess - 1)),
+                Ordering::Equal => (guess, guess),
+            },
+        )
+    }
+
+    // how close a guess is to the secret
+    // the bands are relative to the size of the range, so "warm" means the same thing for 1 to 50 as for 1 to 1000
+    pub fn proximity(&self, guess: u32) -> Proximity {
+        let distance = u64::from(guess.abs_diff(self.secret));
+        let span = u64::from(self.max - self.min) + 1;
+        if distance * 100 <= span * 5 {
+            Proximity::Burning
+        } else if distance * 100 <= span * 15 {
+            Proximity::Warm
+        } else {
+            Proximity::Cold
+        }
+    }
+
+    // compare the latest guess with the one before it, None until there have been two guesses
+    pub fn trend(&self) -> Option<Trend> {
+        let [.., (previous, _), (latest, _)] = self.history[..] else {
+            return None;
+        };
+        let previous = previous.abs_diff(self.secret);
+        let latest = latest.abs_diff(self.secret);
+        Some(match latest.cmp(&previous) {
+            Ordering::Less => Trend::Closer,
+            Ordering::Greater => Trend::Farther,
+            Ordering::Equal => Trend::Same,
+        })
+    }
+
+    // whether play() should give proximity hints after every guess
+    pub fn hints(&self) -> bool {
+        self.hints
+    }
+
+    pub fn secret(&self) -> u32 {
+        self.secret
+    }
+
+    pub fn min(&self) -> u32 {
+        self.min
+    }
+
+    pub fn max(&self) -> u32 {
+        self.max
+    }
+
+    // None when the game has no attempt limit
+    pub fn max_attempts(&self) -> Option<u32> {
+        self.max_attempts
+    }
+
+    // None when the game has no attempt limit
+    pub fn attempts_left(&self) -> Option<u32> {
+        self.max_attempts
+            .map(|max| max.saturating_sub(self.guesses()))
+    }
+
+    // true once every allowed attempt has been used up
+    pub fn out_of_attempts(&self) -> bool {
+        self.attempts_left() == Some(0)
+    }
+}
+
+// a line the player typed: either a guess or one of the commands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entry {
+    Guess(u32),
+    Quit,
+    Hint,
+    History,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidEntry(pub String);
+
+// what every mode says about a line it couldn't make sense of
+impl fmt::Display for InvalidEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let entry = &self.0;
+        let digits = entry.strip_prefix('-').unwrap_or(entry);
+        if entry.is_empty() {
+            f.write_str("Please type a number.")
+        } else if !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) {
+            // a negative number, or one too big for a u32, is still a number
+            write!(f, "{entry} is out of range.")
+        } else {
+            write!(f, "'{entry}' is not a number.")
+        }
+    }
+}
+
+impl FromStr for Entry {
+    type Err = InvalidEntry;
+
+    fn from_str(s: &str) -> Result<Entry, InvalidEntry> {
+        match s.to_ascii_lowercase().as_str() {
+            "quit" | "q" | "exit" => Ok(Entry::Quit),
+            "hint" => Ok(Entry::Hint),
+            "history" => Ok(Entry::History),
+            _ => s
+                .parse()
+                .map(Entry::Guess)
+                .map_err(|_| InvalidEntry(s.to_string())),
+        }
+    }
+}
+
+// run the interactive loop for a game, reading guesses from input and writing everything the player sees to output
+// main hands this stdin and stdout, but any BufRead and Write will do, e.g. a byte slice and a Vec<u8>
+pub fn play<R: BufRead, W: Write>(
+    game: &mut Game,
+    mut input: R,
+    mut output: W,
+) -> io::Result<Outcome> {
+    writeln!(
+        output,
+        "Guess the number between {} and {}!",
+        game.min(),
+        game.max()
+    )?;
+    if let Some(attempts) = game.attempts_left() {
+        writeln!(output, "You have {attempts} guesses.")?;
+    }
+    writeln!(
+        output,
+        "Type quit to give up, hint for a hint or history to see your guesses."
+    )?;
+
+    loop {
+        writeln!(output, "Please input your guess.")?;
+
+        let Some(line) = input::read_line(&mut input)? else {
+            return Ok(Outcome::InputClosed);
+        };
+
+        let guess = match input::parse(&line) {
+            Ok(Entry::Guess(num)) => num,
+            Ok(Entry::Quit) => {
+                writeln!(output, "Giving up? The number was {}.", game.secret())?;
+                write_history(game, &mut output)?;
+                return Ok(Outcome::Quit {
+                    secret: game.secret(),
+                });
+            }
+            Ok(Entry::Hint) => {
+                let (low, high) = game.narrowed();
+                writeln!(output, "The number is between {low} and {high}.")?;
+                continue;
+            }
+            Ok(Entry::History) => {
+                write_history(game, &mut output)?;
+                continue;
+            }
+            Err(err) => {
+                writeln!(output, "{err}")?;
+                continue;
+            }
+        };
+
+        // out of range and repeated guesses don't cost an attempt
+        if let Err(err) = game.check(guess) {
+            writeln!(output, "{err}")?;
+            continue;
+        }
+
+        writeln!(output, "You guessed: {guess}")?;
+
+        match game.guess(guess) {
+            Ordering::Less => writeln!(output, "Too small!")?,
+            Ordering::Greater => writeln!(output, "Too big!")?,
+            Ordering::Equal => {
+                writeln!(output, "You win!")?;
+                write_history(game, &mut output)?;
+                return Ok(Outcome::Won {
+                    guesses: game.guesses(),
+                });
+            }
+        }
+
+        if game.hints() {
+            write_hint(game, guess, &mut output)?;
+        }
+
+        match game.attempts_left() {
+            Some(0) => {
+                writeln!(output, "You lose, it was {}.", game.secret())?;
+                write_history(game, &mut output)?;
+                return Ok(Outcome::Lost {
+                    secret: game.secret(),
+                });
+            }
+            Some(1) => writeln!(output, "Last guess!")?,
+            Some(attempts) => writeln!(output, "{attempts} guesses left.")?,
+            None => {}
+        }
+    }
+}
+
+pub(crate) fn write_history<W: Write>(game: &Game, output: &mut W) -> io::Result<()> {
+    if game.history().is_empty() {
+        return writeln!(output, "No guesses yet.");
+    }
+    writeln!(output, "Your guesses:")?;
+    for (turn, &(guess, result)) in game.history().iter().enumerate() {
+        let described = match result {
+            Ordering::Less => "too small",
+            Ordering::Greater => "too big",
+            Ordering::Equal => "correct",
+        };
+        if game.hints() && result != Ordering::Equal {
+            let proximity = game.proximity(guess);
+            writeln!(
+                output,
+                "{:>3}. {guess} ({described}, {proximity})",
+                turn + 1
+            )?;
+        } else {
+            writeln!(output, "{:>3}. {guess} ({described})", turn + 1)?;
+        }
+    }
+    Ok(())
+}
+
+// the extra feedback hint mode gives after a wrong guess
+pub(crate) fn write_hint<W: Write>(game: &Game, guess: u32, output: &mut W) -> io::Result<()> {
+    let proximity = game.proximity(guess);
+    match game.trend() {
+        Some(Trend::Closer) => writeln!(output, "You're {proximity}, and getting closer.")?,
+        Some(Trend::Farther) => writeln!(output, "You're {proximity}, and getting farther away.")?,
+        Some(Trend::Same) => writeln!(output, "You're {proximity}, no closer than last time.")?,
+        None => writeln!(output, "You're {proximity}.")?,
+    }
+    let (low, high) = game.narrowed();
+    writeln!(output, "The number is between {low} and {high}.")
+}
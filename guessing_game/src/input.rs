@@ -0,0 +1,17 @@
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+// the one place that turns a line the player typed into a value
+// the guessing loop uses it for numbers, the reverse mode for higher/lower/correct answers
+pub fn parse<T: FromStr>(line: &str) -> Result<T, T::Err> {
+    line.trim().parse()
+}
+
+// read the next line from input, or None once input has been closed
+pub fn read_line<R: BufRead>(input: &mut R) -> io::Result<Option<String>> {
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
@@ -0,0 +1,18 @@
+// the library half of the guessing game
+// main.rs only wires stdin, stdout and a real random number generator into this, so everything here can be driven
+// from code with a fixed seed and scripted input
+pub mod config;
+pub mod game;
+pub mod input;
+pub mod multiplayer;
+pub mod net;
+pub mod reverse;
+pub mod scores;
+pub mod solver;
+
+pub use config::{Config, ConfigError, Difficulty};
+pub use game::{play, Entry, Game, GuessError, Outcome, Proximity, Trend};
+pub use multiplayer::{play_local, MultiOutcome};
+pub use reverse::{play_reverse, Answer, ReverseOutcome};
+pub use scores::{Score, ScoreTable};
+pub use solver::{BinarySearchSolver, RandomSolver, Solver};
@@ -1,35 +1,206 @@
-use rand::Rng;
-use std::cmp::Ordering;
-use std::io;
+use guessing_game::config::{Config, Mode, USAGE};
+use guessing_game::net::{self, JoinOutcome};
+use guessing_game::scores::{self, Score, ScoreTable, CUSTOM_DIFFICULTY};
+use guessing_game::solver::{self, SolverKind};
+use guessing_game::{play, play_local, play_reverse, Game, MultiOutcome, Outcome, ReverseOutcome};
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::time::Instant;
+use std::{env, io, process};
 
 fn main() {
-    println!("Guess the number!");
+    let config = match Config::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("error: {err}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+    match &config.mode {
+        Mode::Play => play_round(&config),
+        Mode::Scores => show_scores(&config),
+        Mode::Auto(kind) => auto_play(&config, *kind),
+        Mode::Reverse => reverse_round(&config),
+        Mode::Local(players) => local_round(&config, players),
+        Mode::Serve(addr) => host_race(&config, addr),
+        Mode::Join(addr) => join_race(&config, addr),
+    }
+}
 
-    loop {
-        println!("Please input your guess.");
+fn play_round(config: &Config) {
+    let mut game = Game::with_config(&mut rand::thread_rng(), config);
 
-        let mut guess = String::new();
+    let started = Instant::now();
+    let outcome = or_closed(
+        play(&mut game, io::stdin().lock(), io::stdout()),
+        Outcome::InputClosed,
+    );
 
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
+    if outcome == Outcome::InputClosed {
+        eprintln!("error: input closed before the game was over");
+        process::exit(1);
+    }
 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
+    if let Outcome::Won { guesses } = outcome {
+        let score = Score {
+            name: player_name(config),
+            guesses,
+            min: config.min,
+            max: config.max,
+            difficulty: config
+                .difficulty
+                .map_or(CUSTOM_DIFFICULTY, |difficulty| difficulty.name())
+                .to_string(),
+            elapsed_secs: started.elapsed().as_secs_f64(),
         };
+        record_score(config, score);
+    }
+}
+
+// a broken scores file shouldn't spoil a win, so any problem here is only a warning
+// the file is left alone when it can't be read, so whatever is in it can still be recovered by hand
+fn record_score(config: &Config, score: Score) {
+    let Some(path) = scores_path(config) else {
+        eprintln!("warning: nowhere to save high scores, set XDG_DATA_HOME or use --scores-file");
+        return;
+    };
+
+    let mut table = match ScoreTable::load(&path) {
+        Ok(table) => table,
+        Err(err) => {
+            eprintln!("warning: not saving this score: {err} ({})", path.display());
+            return;
+        }
+    };
+    table.record(score);
+    if let Err(err) = table.save(&path) {
+        eprintln!("warning: not saving this score: {err} ({})", path.display());
+    }
+}
+
+fn show_scores(config: &Config) {
+    let Some(path) = scores_path(config) else {
+        eprintln!("error: no scores file, set XDG_DATA_HOME or use --scores-file");
+        process::exit(1);
+    };
+
+    let table = match ScoreTable::load(&path) {
+        Ok(table) => table,
+        Err(err) => {
+            eprintln!("error: {err} ({})", path.display());
+            process::exit(1);
+        }
+    };
+    table
+        .write_leaderboard(io::stdout().lock())
+        .expect("Failed to print scores");
+}
 
-        println!("You guessed: {guess}");
+fn auto_play(config: &Config, kind: SolverKind) {
+    let stats = solver::simulate(config, kind, config.games, config.seed);
+    println!(
+        "{kind} solver, {} games between {} and {} (seed {}):",
+        stats.games, config.min, config.max, config.seed
+    );
+    println!("  mean guesses: {:.2}", stats.mean);
+    println!("  min guesses:  {}", stats.min);
+    println!("  max guesses:  {}", stats.max);
+    println!(
+        "  optimal worst case: {}",
+        solver::optimal_bound(config.min, config.max)
+    );
+}
+
+fn reverse_round(config: &Config) {
+    let outcome = or_closed(
+        play_reverse(config.min, config.max, io::stdin().lock(), io::stdout()),
+        ReverseOutcome::InputClosed,
+    );
 
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            }
+    match outcome {
+        ReverseOutcome::Guessed { .. } => {}
+        ReverseOutcome::Contradiction { .. } => process::exit(1),
+        ReverseOutcome::InputClosed => {
+            eprintln!("error: input closed before the number was found");
+            process::exit(1);
         }
     }
 }
+
+fn local_round(config: &Config, players: &[String]) {
+    let mut game = Game::with_config(&mut rand::thread_rng(), config);
+
+    let outcome = or_closed(
+        play_local(&mut game, players, io::stdin().lock(), io::stdout()),
+        MultiOutcome::InputClosed,
+    );
+
+    if outcome == MultiOutcome::InputClosed {
+        eprintln!("error: input closed before the game was over");
+        process::exit(1);
+    }
+}
+
+fn host_race(config: &Config, addr: &str) {
+    let listener = match TcpListener::bind(addr) {
+        Ok(listener) => listener,
+        Err(err) => {
+            eprintln!("error: couldn't listen on {addr}: {err}");
+            process::exit(1);
+        }
+    };
+    let game = Game::with_config(&mut rand::thread_rng(), config);
+
+    println!(
+        "Hosting a race between {} and {} on {}, waiting for players to --join...",
+        game.min(),
+        game.max(),
+        listener
+            .local_addr()
+            .map_or(addr.to_string(), |addr| addr.to_string())
+    );
+    if let Err(err) = net::serve(listener, game, io::stdout()) {
+        eprintln!("error: {err}");
+        process::exit(1);
+    }
+}
+
+fn join_race(config: &Config, addr: &str) {
+    let outcome = net::join(addr, &player_name(config), io::stdin().lock(), io::stdout());
+
+    match outcome {
+        Ok(JoinOutcome::InputClosed) => {
+            eprintln!("error: input closed before the race was over");
+            process::exit(1);
+        }
+        Ok(_) => {}
+        Err(err) => {
+            eprintln!("error: {err}");
+            process::exit(1);
+        }
+    }
+}
+
+// a terminal that can't be read from any more, e.g. because someone piped in something that isn't UTF-8, is no
+// different from one that was closed, so the round ends the same way instead of panicking
+fn or_closed<T>(result: io::Result<T>, closed: T) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        closed
+    })
+}
+
+fn scores_path(config: &Config) -> Option<PathBuf> {
+    config.scores_file.clone().or_else(scores::default_path)
+}
+
+fn player_name(config: &Config) -> String {
+    config
+        .player
+        .clone()
+        .or_else(|| env::var("USER").ok())
+        .or_else(|| env::var("USERNAME").ok())
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| String::from("anonymous"))
+}
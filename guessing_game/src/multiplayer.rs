@@ -0,0 +1,100 @@
+use crate::game::{self, Entry, Game};
+use crate::input;
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+
+// how a local multiplayer round ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiOutcome {
+    Won { player: String, guesses: u32 },
+    Lost { secret: u32 },
+    Quit { player: String, secret: u32 },
+    InputClosed,
+}
+
+// two or more players take turns guessing the same secret from one terminal
+// everyone sees everyone else's feedback, so the game is about making good use of the other players' guesses
+// the attempt limit applies per player, so with --attempts 5 every player gets 5 turns
+pub fn play_local<R: BufRead, W: Write>(
+    game: &mut Game,
+    players: &[String],
+    mut input: R,
+    mut output: W,
+) -> io::Result<MultiOutcome> {
+    writeln!(
+        output,
+        "Guess the number between {} and {}! Players take turns: {}.",
+        game.min(),
+        game.max(),
+        players.join(", ")
+    )?;
+    if let Some(attempts) = game.max_attempts() {
+        writeln!(output, "Everyone gets {attempts} guesses.")?;
+    }
+
+    let mut round = 0;
+    loop {
+        round += 1;
+        for player in players {
+            let guess = loop {
+                writeln!(output, "{player}, please input your guess.")?;
+
+                let Some(line) = input::read_line(&mut input)? else {
+                    return Ok(MultiOutcome::InputClosed);
+                };
+
+                match input::parse(&line) {
+                    Ok(Entry::Guess(guess)) => match game.check(guess) {
+                        Ok(()) => break guess,
+                        Err(err) => writeln!(output, "{err}")?,
+                    },
+                    Ok(Entry::Quit) => {
+                        writeln!(
+                            output,
+                            "{player} gives up. The number was {}.",
+                            game.secret()
+                        )?;
+                        game::write_history(game, &mut output)?;
+                        return Ok(MultiOutcome::Quit {
+                            player: player.clone(),
+                            secret: game.secret(),
+                        });
+                    }
+                    Ok(Entry::Hint) => {
+                        let (low, high) = game.narrowed();
+                        writeln!(output, "The number is between {low} and {high}.")?;
+                    }
+                    Ok(Entry::History) => game::write_history(game, &mut output)?,
+                    Err(err) => writeln!(output, "{err}")?,
+                }
+            };
+
+            writeln!(output, "{player} guessed: {guess}")?;
+
+            match game.guess(guess) {
+                Ordering::Less => writeln!(output, "Too small!")?,
+                Ordering::Greater => writeln!(output, "Too big!")?,
+                Ordering::Equal => {
+                    writeln!(output, "{player} wins in round {round}!")?;
+                    game::write_history(game, &mut output)?;
+                    return Ok(MultiOutcome::Won {
+                        player: player.clone(),
+                        guesses: round,
+                    });
+                }
+            }
+
+            if game.hints() {
+                game::write_hint(game, guess, &mut output)?;
+            }
+        }
+
+        if game.max_attempts() == Some(round) {
+            writeln!(output, "Nobody got it, it was {}.", game.secret())?;
+            game::write_history(game, &mut output)?;
+            return Ok(MultiOutcome::Lost {
+                secret: game.secret(),
+            });
+        }
+    }
+}
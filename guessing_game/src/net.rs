@@ -0,0 +1,574 @@
+use crate::game::{Entry, Game, GuessError};
+use crate::input;
+use std::cmp::Ordering;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream, ToSocketAddrs};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+// the networked race uses a small line-based protocol: every message is one line of text, with fields separated by spaces
+// the client speaks first, and the server answers every client message with exactly one line:
+//
+//   client                      server
+//   HELLO <name>           ->   WELCOME <min> <max> <attempts or ->
+//   GUESS <n>              ->   SMALL <left or -> | BIG <left or -> | INVALID <reason>
+//   QUIT                   ->   BYE
+//
+// once someone wins (or everyone is out of guesses) every connected client is sent WINNER <guesses> <secret> <name>
+// (or OVER <secret>) and the connection is closed; a client that ran out of guesses just waits for that line
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClientMessage {
+    Hello(String),
+    Guess(u32),
+    Quit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    Welcome {
+        min: u32,
+        max: u32,
+        attempts: Option<u32>,
+    },
+    // the guess was too small; left is how many guesses the player has left, None without an attempt limit
+    TooSmall {
+        left: Option<u32>,
+    },
+    TooBig {
+        left: Option<u32>,
+    },
+    Invalid(String),
+    Winner {
+        name: String,
+        guesses: u32,
+        secret: u32,
+    },
+    // everyone ran out of guesses or left without anyone winning
+    Over {
+        secret: u32,
+    },
+    Bye,
+}
+
+// a line that isn't a valid protocol message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolError(pub String);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unexpected message '{}'", self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+impl fmt::Display for ClientMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClientMessage::Hello(name) => write!(f, "HELLO {name}"),
+            ClientMessage::Guess(guess) => write!(f, "GUESS {guess}"),
+            ClientMessage::Quit => write!(f, "QUIT"),
+        }
+    }
+}
+
+impl FromStr for ClientMessage {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<ClientMessage, ProtocolError> {
+        let err = || ProtocolError(s.to_string());
+        let (command, rest) = s.split_once(' ').unwrap_or((s, ""));
+        match command {
+            "HELLO" if !rest.trim().is_empty() => Ok(ClientMessage::Hello(rest.trim().to_string())),
+            "GUESS" => rest
+                .trim()
+                .parse()
+                .map(ClientMessage::Guess)
+                .map_err(|_| err()),
+            "QUIT" => Ok(ClientMessage::Quit),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl fmt::Display for ServerMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerMessage::Welcome { min, max, attempts } => {
+                write!(f, "WELCOME {min} {max} {}", Limit(*attempts))
+            }
+            ServerMessage::TooSmall { left } => write!(f, "SMALL {}", Limit(*left)),
+            ServerMessage::TooBig { left } => write!(f, "BIG {}", Limit(*left)),
+            ServerMessage::Invalid(reason) => write!(f, "INVALID {reason}"),
+            ServerMessage::Winner {
+                name,
+                guesses,
+                secret,
+            } => write!(f, "WINNER {guesses} {secret} {name}"),
+            ServerMessage::Over { secret } => write!(f, "OVER {secret}"),
+            ServerMessage::Bye => write!(f, "BYE"),
+        }
+    }
+}
+
+impl FromStr for ServerMessage {
+    type Err = ProtocolError;
+
+    fn from_str(s: &str) -> Result<ServerMessage, ProtocolError> {
+        let err = || ProtocolError(s.to_string());
+        let number = |field: Option<&str>| -> Result<u32, ProtocolError> {
+            field.and_then(|field| field.parse().ok()).ok_or_else(err)
+        };
+        let limit = |field: Option<&str>| match field {
+            Some("-") => Ok(None),
+            field => number(field).map(Some),
+        };
+
+        let (command, rest) = s.split_once(' ').unwrap_or((s, ""));
+        match command {
+            "WELCOME" => {
+                let mut fields = rest.split(' ');
+                Ok(ServerMessage::Welcome {
+                    min: number(fields.next())?,
+                    max: number(fields.next())?,
+                    attempts: limit(fields.next())?,
+                })
+            }
+            "SMALL" => Ok(ServerMessage::TooSmall {
+                left: limit(Some(rest))?,
+            }),
+            "BIG" => Ok(ServerMessage::TooBig {
+                left: limit(Some(rest))?,
+            }),
+            "INVALID" => Ok(ServerMessage::Invalid(rest.to_string())),
+            // the name goes last since it's the only field that can contain spaces
+            "WINNER" => {
+                let mut fields = rest.splitn(3, ' ');
+                Ok(ServerMessage::Winner {
+                    guesses: number(fields.next())?,
+                    secret: number(fields.next())?,
+                    name: fields
+                        .next()
+                        .filter(|name| !name.is_empty())
+                        .ok_or_else(err)?
+                        .to_string(),
+                })
+            }
+            "OVER" => Ok(ServerMessage::Over {
+                secret: number(Some(rest))?,
+            }),
+            "BYE" => Ok(ServerMessage::Bye),
+            _ => Err(err()),
+        }
+    }
+}
+
+// an attempt limit on the wire: the number, or - for no limit
+struct Limit(Option<u32>);
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Some(limit) => write!(f, "{limit}"),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+fn send<M: fmt::Display>(stream: &mut TcpStream, message: &M) -> io::Result<()> {
+    writeln!(stream, "{message}")
+}
+
+// read and parse the next message, None once the other end has closed the connection
+fn receive<M: FromStr<Err = ProtocolError>, R: BufRead>(
+    reader: &mut R,
+) -> io::Result<Option<Result<M, ProtocolError>>> {
+    Ok(input::read_line(reader)?.map(|line| input::parse(&line)))
+}
+
+// how a hosted race ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RaceResult {
+    // the winner's name and how many guesses they needed, None if nobody found the secret
+    pub winner: Option<(String, u32)>,
+    pub secret: u32,
+}
+
+struct Player {
+    name: String,
+    stream: TcpStream,
+    guesses: u32,
+    done: bool,
+}
+
+// everything the host keeps track of, shared between the threads talking to each player
+struct Race<W> {
+    game: Game,
+    players: Vec<Player>,
+    result: Option<RaceResult>,
+    log: W,
+}
+
+impl<W: Write> Race<W> {
+    // the message that tells a player the race is over
+    fn final_message(&self) -> Option<ServerMessage> {
+        let result = self.result.as_ref()?;
+        Some(match &result.winner {
+            Some((name, guesses)) => ServerMessage::Winner {
+                name: name.clone(),
+                guesses: *guesses,
+                secret: result.secret,
+            },
+            None => ServerMessage::Over {
+                secret: result.secret,
+            },
+        })
+    }
+
+    fn guess(&mut self, index: usize, guess: u32) -> ServerMessage {
+        if let Some(message) = self.final_message() {
+            return message;
+        }
+        if self.players[index].done {
+            return ServerMessage::Invalid(String::from("you are out of guesses"));
+        }
+        // everyone has their own set of guesses in a race, so only the range matters here
+        if let Err(err @ GuessError::OutOfRange { .. }) = self.game.check(guess) {
+            return ServerMessage::Invalid(err.to_string());
+        }
+
+        let result = self.game.guess(guess);
+        let player = &mut self.players[index];
+        player.guesses += 1;
+        let left = self
+            .game
+            .max_attempts()
+            .map(|max| max.saturating_sub(player.guesses));
+        if left == Some(0) {
+            player.done = true;
+        }
+        let _ = writeln!(
+            self.log,
+            "{} guessed {guess}: {}",
+            player.name,
+            match result {
+                Ordering::Less => "too small",
+                Ordering::Greater => "too big",
+                Ordering::Equal => "correct",
+            }
+        );
+
+        match result {
+            Ordering::Less => {
+                self.finish_if_everyone_is_done();
+                ServerMessage::TooSmall { left }
+            }
+            Ordering::Greater => {
+                self.finish_if_everyone_is_done();
+                ServerMessage::TooBig { left }
+            }
+            Ordering::Equal => {
+                let winner = (player.name.clone(), player.guesses);
+                self.finish(Some(winner));
+                self.final_message().expect("the race was just finished")
+            }
+        }
+    }
+
+    fn leave(&mut self, index: usize) {
+        let player = &mut self.players[index];
+        if !player.done {
+            player.done = true;
+            let _ = writeln!(self.log, "{} left", player.name);
+        }
+        self.finish_if_everyone_is_done();
+    }
+
+    fn finish_if_everyone_is_done(&mut self) {
+        if self.result.is_none() && self.players.iter().all(|player| player.done) {
+            self.finish(None);
+        }
+    }
+
+    // record the result and tell everyone still connected, then hang up on them
+    fn finish(&mut self, winner: Option<(String, u32)>) {
+        let _ = match &winner {
+            Some((name, guesses)) => writeln!(
+                self.log,
+                "{name} wins with {guesses} guesses, the number was {}",
+                self.game.secret()
+            ),
+            None => writeln!(
+                self.log,
+                "nobody won, the number was {}",
+                self.game.secret()
+            ),
+        };
+        self.result = Some(RaceResult {
+            winner,
+            secret: self.game.secret(),
+        });
+        let message = self.final_message().expect("the race was just finished");
+        for player in &mut self.players {
+            let _ = send(&mut player.stream, &message);
+            let _ = player.stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+// host a race on an already bound listener and return once it's over
+// binding is left to the caller so it can pick port 0 and find out which port it got, which keeps tests offline and parallel
+// the race starts as soon as the first player joins and ends when someone finds the secret or every player is out or gone
+pub fn serve<W: Write + Send + 'static>(
+    listener: TcpListener,
+    game: Game,
+    log: W,
+) -> io::Result<RaceResult> {
+    let race = Arc::new(Mutex::new(Race {
+        game,
+        players: Vec::new(),
+        result: None,
+        log,
+    }));
+
+    // accept() is polled so the loop notices when the race ends without needing one more connection to wake it up
+    listener.set_nonblocking(true)?;
+    loop {
+        if let Some(result) = race.lock().unwrap().result.clone() {
+            return Ok(result);
+        }
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                let race = Arc::clone(&race);
+                thread::spawn(move || {
+                    // a player's connection failing only affects that player
+                    let _ = handle_player(stream, race);
+                });
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(20));
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// talk to one player for as long as they're connected
+fn handle_player<W: Write>(mut stream: TcpStream, race: Arc<Mutex<Race<W>>>) -> io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    // checking the name and joining happen under one lock, so two players saying HELLO with the same name at the same
+    // time can't both get in
+    let index = loop {
+        match receive(&mut reader)? {
+            Some(Ok(ClientMessage::Hello(name))) => {
+                let mut race = race.lock().unwrap();
+                if let Some(message) = race.final_message() {
+                    return send(&mut stream, &message);
+                }
+                // names have to be unique, otherwise a player can't tell whether the winner was them
+                if race.players.iter().any(|player| player.name == name) {
+                    send(
+                        &mut stream,
+                        &ServerMessage::Invalid(format!("someone called {name} already joined")),
+                    )?;
+                    continue;
+                }
+                race.players.push(Player {
+                    name: name.clone(),
+                    stream: stream.try_clone()?,
+                    guesses: 0,
+                    done: false,
+                });
+                let _ = writeln!(race.log, "{name} joined");
+                send(
+                    &mut stream,
+                    &ServerMessage::Welcome {
+                        min: race.game.min(),
+                        max: race.game.max(),
+                        attempts: race.game.max_attempts(),
+                    },
+                )?;
+                break race.players.len() - 1;
+            }
+            Some(Ok(_)) => send(
+                &mut stream,
+                &ServerMessage::Invalid(String::from("say HELLO first")),
+            )?,
+            Some(Err(err)) => send(&mut stream, &ServerMessage::Invalid(err.to_string()))?,
+            None => return Ok(()),
+        }
+    };
+
+    loop {
+        let message = receive(&mut reader);
+        let mut race = race.lock().unwrap();
+        let reply = match message {
+            Ok(Some(Ok(ClientMessage::Guess(guess)))) => race.guess(index, guess),
+            Ok(Some(Ok(ClientMessage::Quit))) => {
+                race.leave(index);
+                return send(&mut stream, &ServerMessage::Bye);
+            }
+            Ok(Some(Ok(ClientMessage::Hello(_)))) => {
+                ServerMessage::Invalid(String::from("you already said hello"))
+            }
+            Ok(Some(Err(err))) => ServerMessage::Invalid(err.to_string()),
+            // the connection closed or broke, either way the player is gone
+            Ok(None) | Err(_) => {
+                race.leave(index);
+                return Ok(());
+            }
+        };
+        // the final message has already been sent to everyone when the race ended
+        if race.result.is_some() {
+            return Ok(());
+        }
+        send(&mut stream, &reply)?;
+    }
+}
+
+// how a race ended for someone who joined it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinOutcome {
+    Won { guesses: u32 },
+    Lost { winner: Option<String>, secret: u32 },
+    Quit,
+    InputClosed,
+}
+
+// join a race hosted with serve(), reading guesses from input and writing what happens to output
+pub fn join<A: ToSocketAddrs, R: BufRead, W: Write>(
+    addr: A,
+    name: &str,
+    mut input: R,
+    mut output: W,
+) -> io::Result<JoinOutcome> {
+    let name = name.trim();
+    let mut stream = TcpStream::connect(addr)?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    send(&mut stream, &ClientMessage::Hello(name.to_string()))?;
+    match next_message(&mut reader)? {
+        ServerMessage::Welcome { min, max, attempts } => {
+            writeln!(
+                output,
+                "Joined the race! Guess the number between {min} and {max}."
+            )?;
+            if let Some(attempts) = attempts {
+                writeln!(output, "You have {attempts} guesses.")?;
+            }
+        }
+        ServerMessage::Invalid(reason) => {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, reason));
+        }
+        message => return finished(name, message, &mut output),
+    }
+
+    loop {
+        writeln!(output, "Please input your guess.")?;
+
+        let Some(line) = input::read_line(&mut input)? else {
+            send(&mut stream, &ClientMessage::Quit)?;
+            return Ok(JoinOutcome::InputClosed);
+        };
+
+        let guess = match input::parse(&line) {
+            Ok(Entry::Guess(guess)) => guess,
+            Ok(Entry::Quit) => {
+                send(&mut stream, &ClientMessage::Quit)?;
+                return match next_message(&mut reader)? {
+                    ServerMessage::Bye => Ok(JoinOutcome::Quit),
+                    message => finished(name, message, &mut output),
+                };
+            }
+            Ok(Entry::Hint | Entry::History) => {
+                writeln!(output, "That's not available in a race.")?;
+                continue;
+            }
+            Err(err) => {
+                writeln!(output, "{err}")?;
+                continue;
+            }
+        };
+
+        send(&mut stream, &ClientMessage::Guess(guess))?;
+        let left = match next_message(&mut reader)? {
+            ServerMessage::TooSmall { left } => {
+                writeln!(output, "Too small!")?;
+                left
+            }
+            ServerMessage::TooBig { left } => {
+                writeln!(output, "Too big!")?;
+                left
+            }
+            ServerMessage::Invalid(reason) => {
+                writeln!(output, "{reason}")?;
+                continue;
+            }
+            message => return finished(name, message, &mut output),
+        };
+
+        if left == Some(0) {
+            writeln!(output, "You're out of guesses, waiting for the others...")?;
+            let message = next_message(&mut reader)?;
+            return finished(name, message, &mut output);
+        }
+    }
+}
+
+// the next message from the server, treating a closed connection or garbage as an error
+fn next_message<R: BufRead>(reader: &mut R) -> io::Result<ServerMessage> {
+    match receive(reader)? {
+        Some(Ok(message)) => Ok(message),
+        Some(Err(err)) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        None => Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "the host closed the connection",
+        )),
+    }
+}
+
+// report the end of the race to the player
+fn finished<W: Write>(
+    name: &str,
+    message: ServerMessage,
+    output: &mut W,
+) -> io::Result<JoinOutcome> {
+    match message {
+        ServerMessage::Winner {
+            name: winner,
+            guesses,
+            secret,
+        } => {
+            if winner == name {
+                writeln!(output, "You win with {guesses} guesses!")?;
+                Ok(JoinOutcome::Won { guesses })
+            } else {
+                writeln!(
+                    output,
+                    "{winner} won with {guesses} guesses, the number was {secret}."
+                )?;
+                Ok(JoinOutcome::Lost {
+                    winner: Some(winner),
+                    secret,
+                })
+            }
+        }
+        ServerMessage::Over { secret } => {
+            writeln!(output, "Nobody got it, the number was {secret}.")?;
+            Ok(JoinOutcome::Lost {
+                winner: None,
+                secret,
+            })
+        }
+        message => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            ProtocolError(message.to_string()),
+        )),
+    }
+}
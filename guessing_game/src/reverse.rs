@@ -0,0 +1,130 @@
+use crate::input;
+use crate::solver::{BinarySearchSolver, Solver};
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::str::FromStr;
+
+// what the player can tell the computer about its guess
+// Higher means the player's number is higher than the guess, so the guess was too small
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Higher,
+    Lower,
+    Correct,
+}
+
+impl Answer {
+    // the answer as the Ordering Game::guess would have returned for the same guess
+    pub fn ordering(self) -> Ordering {
+        match self {
+            Answer::Higher => Ordering::Less,
+            Answer::Lower => Ordering::Greater,
+            Answer::Correct => Ordering::Equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidAnswer(pub String);
+
+impl FromStr for Answer {
+    type Err = InvalidAnswer;
+
+    fn from_str(s: &str) -> Result<Answer, InvalidAnswer> {
+        match s.to_ascii_lowercase().as_str() {
+            "higher" | "h" | "+" => Ok(Answer::Higher),
+            "lower" | "l" | "-" => Ok(Answer::Lower),
+            "correct" | "c" | "yes" | "y" | "=" => Ok(Answer::Correct),
+            _ => Err(InvalidAnswer(s.to_string())),
+        }
+    }
+}
+
+// how a round of the reverse game ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReverseOutcome {
+    Guessed { number: u32, guesses: u32 },
+    // the answer given for guess can't be true given the answers before it
+    Contradiction { guess: u32, answer: Answer },
+    InputClosed,
+}
+
+// the reverse game: the player thinks of a number between min and max and the computer guesses it with a binary search
+pub fn play_reverse<R: BufRead, W: Write>(
+    min: u32,
+    max: u32,
+    mut input: R,
+    mut output: W,
+) -> io::Result<ReverseOutcome> {
+    writeln!(
+        output,
+        "Think of a number between {min} and {max} and I'll guess it."
+    )?;
+    writeln!(output, "Answer each guess with higher, lower or correct.")?;
+
+    let mut solver = BinarySearchSolver::new(min, max);
+    let mut guesses = 0;
+
+    loop {
+        let guess = solver.next_guess();
+        guesses += 1;
+        writeln!(output, "Is it {guess}?")?;
+
+        let answer = loop {
+            let Some(line) = input::read_line(&mut input)? else {
+                return Ok(ReverseOutcome::InputClosed);
+            };
+            match input::parse::<Answer>(&line) {
+                Ok(answer) => break answer,
+                Err(InvalidAnswer(answer)) => writeln!(
+                    output,
+                    "I don't understand '{answer}', please answer higher, lower or correct."
+                )?,
+            }
+        };
+
+        let (low, high) = solver.remaining();
+        let contradiction = match answer {
+            Answer::Higher => guess >= high,
+            Answer::Lower => guess <= low,
+            Answer::Correct => false,
+        };
+        if contradiction {
+            let direction = match answer {
+                Answer::Higher => "higher",
+                _ => "lower",
+            };
+            // only claim to know the number once there's a single one left, and only blame earlier answers if there were any
+            if low == high && guesses == 1 {
+                writeln!(
+                    output,
+                    "That can't be right: {guess} is the only number between {min} and {max}, so it can't be {direction}."
+                )?;
+            } else if low == high {
+                writeln!(
+                    output,
+                    "That can't be right: your earlier answers mean the number is {guess}, so it can't be {direction}."
+                )?;
+            } else {
+                writeln!(
+                    output,
+                    "That can't be right: the number has to be between {low} and {high}, so it can't be {direction} than {guess}."
+                )?;
+            }
+            return Ok(ReverseOutcome::Contradiction { guess, answer });
+        }
+
+        if answer == Answer::Correct {
+            writeln!(
+                output,
+                "Got it! Your number is {guess}, I needed {guesses} guesses."
+            )?;
+            return Ok(ReverseOutcome::Guessed {
+                number: guess,
+                guesses,
+            });
+        }
+
+        solver.feedback(guess, answer.ordering());
+    }
+}
@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// bump this whenever the layout of Score changes, and teach migrate() how to bring the previous version forward
+// that way a scores file written by an older build keeps working after an upgrade
+pub const FORMAT_VERSION: u64 = 1;
+
+// the name used on the leaderboard for rounds that didn't use one of the presets
+pub const CUSTOM_DIFFICULTY: &str = "custom";
+
+// one winning round
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Score {
+    pub name: String,
+    pub guesses: u32,
+    pub min: u32,
+    pub max: u32,
+    pub difficulty: String,
+    pub elapsed_secs: f64,
+}
+
+// what actually gets written to disk: the scores plus the version of the format they were written in
+#[derive(Serialize, Deserialize)]
+struct ScoreFile {
+    version: u64,
+    scores: Vec<Score>,
+}
+
+#[derive(Debug)]
+pub enum ScoreError {
+    Io(io::Error),
+    Corrupt(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for ScoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ScoreError::Io(err) => write!(f, "couldn't access the scores file: {err}"),
+            ScoreError::Corrupt(err) => write!(f, "the scores file is corrupt: {err}"),
+            ScoreError::MissingVersion => write!(f, "the scores file has no format version"),
+            ScoreError::UnsupportedVersion(version) if *version > FORMAT_VERSION => write!(
+                f,
+                "the scores file uses format version {version}, but this build only understands up to {FORMAT_VERSION}"
+            ),
+            // anything older than the first format was never written by any build
+            ScoreError::UnsupportedVersion(version) => write!(
+                f,
+                "the scores file uses format version {version}, which no version of the game ever wrote"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ScoreError {}
+
+impl From<io::Error> for ScoreError {
+    fn from(err: io::Error) -> ScoreError {
+        ScoreError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ScoreError {
+    fn from(err: serde_json::Error) -> ScoreError {
+        ScoreError::Corrupt(err)
+    }
+}
+
+// where scores live when --scores-file isn't given: $XDG_DATA_HOME/guessing_game/scores.json,
+// falling back to ~/.local/share like the XDG spec says
+// None if neither variable is set, in which case there's nowhere sensible to keep them
+pub fn default_path() -> Option<PathBuf> {
+    let data_home = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .filter(|dir| !dir.is_empty())
+                .map(|home| PathBuf::from(home).join(".local").join("share"))
+        })?;
+    Some(data_home.join("guessing_game").join("scores.json"))
+}
+
+// every recorded win, in the order they were recorded
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreTable {
+    scores: Vec<Score>,
+}
+
+impl ScoreTable {
+    // read the table from disk
+    // a missing file just means nobody has won yet, so that's an empty table rather than an error
+    pub fn load(path: &Path) -> Result<ScoreTable, ScoreError> {
+        match fs::read_to_string(path) {
+            Ok(contents) => ScoreTable::from_json(&contents),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ScoreTable::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    pub fn from_json(contents: &str) -> Result<ScoreTable, ScoreError> {
+        let value: Value = serde_json::from_str(contents)?;
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or(ScoreError::MissingVersion)?;
+        let file: ScoreFile = serde_json::from_value(migrate(value, version)?)?;
+        Ok(ScoreTable {
+            scores: file.scores,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = ScoreFile {
+            version: FORMAT_VERSION,
+            scores: self.scores.clone(),
+        };
+        serde_json::to_string_pretty(&file).expect("scores are always serializable")
+    }
+
+    // write the table to disk, creating the data directory if it doesn't exist yet
+    // the new contents go to a temporary file first so a crash halfway through can't leave a half-written table behind
+    pub fn save(&self, path: &Path) -> Result<(), ScoreError> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, self.to_json())?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, score: Score) {
+        self.scores.push(score);
+    }
+
+    pub fn scores(&self) -> &[Score] {
+        &self.scores
+    }
+
+    // the scores grouped by difficulty, best first: fewest guesses, then fastest
+    // the presets come first from easiest to hardest, then everything else in alphabetical order
+    pub fn leaderboard(&self) -> Vec<(&str, Vec<&Score>)> {
+        let mut groups: Vec<(&str, Vec<&Score>)> = Vec::new();
+        for score in &self.scores {
+            match groups
+                .iter_mut()
+                .find(|(difficulty, _)| *difficulty == score.difficulty)
+            {
+                Some((_, scores)) => scores.push(score),
+                None => groups.push((&score.difficulty, vec![score])),
+            }
+        }
+
+        groups.sort_by(|(a, _), (b, _)| (rank(a), *a).cmp(&(rank(b), *b)));
+        for (_, scores) in &mut groups {
+            scores.sort_by(|a, b| {
+                a.guesses
+                    .cmp(&b.guesses)
+                    .then(a.elapsed_secs.total_cmp(&b.elapsed_secs))
+            });
+        }
+        groups
+    }
+
+    pub fn write_leaderboard<W: Write>(&self, mut output: W) -> io::Result<()> {
+        let leaderboard = self.leaderboard();
+        if leaderboard.is_empty() {
+            return writeln!(output, "No high scores yet. Go win a game!");
+        }
+
+        for (difficulty, scores) in leaderboard {
+            writeln!(output, "== {difficulty} ==")?;
+            for (place, score) in scores.iter().enumerate() {
+                writeln!(
+                    output,
+                    "{:>3}. {:<16} {:>4} guesses  {}-{}  {:.1}s",
+                    place + 1,
+                    score.name,
+                    score.guesses,
+                    score.min,
+                    score.max,
+                    score.elapsed_secs
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// where a difficulty sorts on the leaderboard
+fn rank(difficulty: &str) -> u8 {
+    match difficulty {
+        "easy" => 0,
+        "normal" => 1,
+        "hard" => 2,
+        _ => 3,
+    }
+}
+
+// bring a file written in an older format up to FORMAT_VERSION, one version at a time
+// version 1 is the first format, so there's nothing to migrate yet; the next format gets a `1 => ...` arm here
+fn migrate(value: Value, version: u64) -> Result<Value, ScoreError> {
+    match version {
+        FORMAT_VERSION => Ok(value),
+        _ => Err(ScoreError::UnsupportedVersion(version)),
+    }
+}
@@ -0,0 +1,190 @@
+use crate::config::Config;
+use crate::game::Game;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Ordering;
+use std::fmt;
+
+// something that can play the guessing game on its own
+// it proposes a guess, gets told how that guess compared to the secret (the same Ordering Game::guess returns), and goes again
+pub trait Solver {
+    fn next_guess(&mut self) -> u32;
+
+    // Less means the guess was too small, Greater means it was too big
+    fn feedback(&mut self, guess: u32, result: Ordering);
+}
+
+// always guesses the middle of whatever range the secret can still be in
+// this is the optimal strategy: it never needs more than optimal_bound(min, max) guesses
+#[derive(Debug, Clone)]
+pub struct BinarySearchSolver {
+    low: u32,
+    high: u32,
+}
+
+impl BinarySearchSolver {
+    pub fn new(min: u32, max: u32) -> BinarySearchSolver {
+        BinarySearchSolver {
+            low: min,
+            high: max,
+        }
+    }
+
+    // the smallest and largest numbers the secret can still be, given the feedback so far
+    pub fn remaining(&self) -> (u32, u32) {
+        (self.low, self.high)
+    }
+}
+
+impl Solver for BinarySearchSolver {
+    fn next_guess(&mut self) -> u32 {
+        // written this way instead of (low + high) / 2 so it can't overflow near u32::MAX
+        self.low + (self.high - self.low) / 2
+    }
+
+    fn feedback(&mut self, guess: u32, result: Ordering) {
+        narrow(&mut self.low, &mut self.high, guess, result);
+    }
+}
+
+// guesses anywhere in the range the secret can still be in
+// it still listens to the feedback, so it always finishes, just not as quickly as the binary search
+#[derive(Debug, Clone)]
+pub struct RandomSolver<R: Rng> {
+    low: u32,
+    high: u32,
+    rng: R,
+}
+
+impl<R: Rng> RandomSolver<R> {
+    pub fn new(min: u32, max: u32, rng: R) -> RandomSolver<R> {
+        RandomSolver {
+            low: min,
+            high: max,
+            rng,
+        }
+    }
+}
+
+impl<R: Rng> Solver for RandomSolver<R> {
+    fn next_guess(&mut self) -> u32 {
+        self.rng.gen_range(self.low..=self.high)
+    }
+
+    fn feedback(&mut self, guess: u32, result: Ordering) {
+        narrow(&mut self.low, &mut self.high, guess, result);
+    }
+}
+
+// shrink [low, high] to the part that's still consistent with the feedback for guess
+fn narrow(low: &mut u32, high: &mut u32, guess: u32, result: Ordering) {
+    match result {
+        Ordering::Less => *low = (*low).max(guess.saturating_add(1)),
+        Ordering::Greater => *high = (*high).min(guess.saturating_sub(1)),
+        Ordering::Equal => {
+            *low = guess;
+            *high = guess;
+        }
+    }
+}
+
+// which Solver --auto should use
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolverKind {
+    BinarySearch,
+    Random,
+}
+
+impl SolverKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SolverKind::BinarySearch => "binary",
+            SolverKind::Random => "random",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<SolverKind> {
+        match name.to_ascii_lowercase().as_str() {
+            "binary" => Some(SolverKind::BinarySearch),
+            "random" => Some(SolverKind::Random),
+            _ => None,
+        }
+    }
+
+    // build a fresh solver of this kind for the range
+    // the rng is only used by solvers that need randomness
+    pub fn build<R: Rng>(self, min: u32, max: u32, rng: &mut R) -> Box<dyn Solver> {
+        match self {
+            SolverKind::BinarySearch => Box::new(BinarySearchSolver::new(min, max)),
+            SolverKind::Random => Box::new(RandomSolver::new(
+                min,
+                max,
+                StdRng::seed_from_u64(rng.gen()),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for SolverKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// let a solver play a game to the end and return how many guesses it took
+// the game's attempt limit is ignored here, since the point is to measure how many guesses the solver needs
+pub fn solve<S: Solver + ?Sized>(game: &mut Game, solver: &mut S) -> u32 {
+    loop {
+        let guess = solver.next_guess();
+        match game.guess(guess) {
+            Ordering::Equal => return game.guesses(),
+            result => solver.feedback(guess, result),
+        }
+    }
+}
+
+// the worst case number of guesses for a perfect binary search over min..=max
+// each guess splits the n remaining numbers into two halves plus the guess itself, so k guesses can cover 2^k - 1 numbers
+// and the bound is ceil(log2(n + 1)); that's the same as ceil(log2(n)) except when n is an exact power of two
+pub fn optimal_bound(min: u32, max: u32) -> u32 {
+    let size = u64::from(max - min) + 1;
+    u64::BITS - size.leading_zeros()
+}
+
+// how a solver did over a batch of games
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub games: u32,
+    pub mean: f64,
+    pub min: u32,
+    pub max: u32,
+}
+
+// play `games` games with the given solver, with secrets drawn from an rng seeded with `seed`
+// the same seed always produces the same secrets and the same random guesses, so the numbers are reproducible
+pub fn simulate(config: &Config, kind: SolverKind, games: u32, seed: u64) -> Stats {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut total = 0u64;
+    let mut min = u32::MAX;
+    let mut max = 0;
+
+    for _ in 0..games {
+        let mut game = Game::with_config(&mut rng, config);
+        let mut solver = kind.build(config.min, config.max, &mut rng);
+        let guesses = solve(&mut game, solver.as_mut());
+        total += u64::from(guesses);
+        min = min.min(guesses);
+        max = max.max(guesses);
+    }
+
+    Stats {
+        games,
+        mean: if games == 0 {
+            0.0
+        } else {
+            total as f64 / f64::from(games)
+        },
+        min: if games == 0 { 0 } else { min },
+        max,
+    }
+}
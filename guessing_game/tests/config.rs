@@ -0,0 +1,104 @@
+// turning command lines into a Config, and the combinations that are turned away
+use guessing_game::config::Mode;
+use guessing_game::{Config, ConfigError, Difficulty};
+
+fn parse(args: &[&str]) -> Result<Config, ConfigError> {
+    Config::from_args(args.iter().copied())
+}
+
+#[test]
+fn no_options_is_the_original_game() {
+    assert_eq!(parse(&[]), Ok(Config::default()));
+    let config = Config::default();
+    assert_eq!(
+        (config.min, config.max, config.max_attempts),
+        (1, 100, None)
+    );
+    assert_eq!(config.mode, Mode::Play);
+}
+
+#[test]
+fn each_preset_sets_the_range_and_attempts() {
+    for (name, difficulty, expected) in [
+        ("easy", Difficulty::Easy, (1, 50, Some(10))),
+        ("normal", Difficulty::Normal, (1, 100, Some(8))),
+        ("HARD", Difficulty::Hard, (1, 1000, Some(10))),
+    ] {
+        let config = parse(&["--difficulty", name]).expect("it's a preset");
+        assert_eq!((config.min, config.max, config.max_attempts), expected);
+        assert_eq!(config.difficulty, Some(difficulty));
+        assert_eq!(config, Config::preset(difficulty));
+    }
+    assert_eq!(
+        parse(&["--difficulty", "impossible"]),
+        Err(ConfigError::UnknownDifficulty(String::from("impossible")))
+    );
+}
+
+#[test]
+fn overriding_a_preset_means_it_isnt_that_preset_any_more() {
+    for overrides in [["--min", "5"], ["--max", "500"], ["--attempts", "3"]] {
+        // wherever --difficulty comes, the preset goes first and the override wins
+        let before = parse(&[overrides[0], overrides[1], "--difficulty", "hard"]).unwrap();
+        let after = parse(&["--difficulty", "hard", overrides[0], overrides[1]]).unwrap();
+        assert_eq!(before, after);
+        assert_eq!(after.difficulty, None);
+    }
+    let config = parse(&["--max", "500", "--difficulty", "hard"]).unwrap();
+    assert_eq!(
+        (config.min, config.max, config.max_attempts),
+        (1, 500, Some(10))
+    );
+
+    // options that don't touch the range or attempts leave the preset alone
+    let config = parse(&["--difficulty", "easy", "--hints", "--name", "Ferris"]).unwrap();
+    assert_eq!(config.difficulty, Some(Difficulty::Easy));
+}
+
+#[test]
+fn a_range_with_nothing_in_it() {
+    assert_eq!(
+        parse(&["--min", "10", "--max", "9"]),
+        Err(ConfigError::EmptyRange { min: 10, max: 9 })
+    );
+    // a preset's range can be emptied by an override too
+    assert_eq!(
+        parse(&["--difficulty", "easy", "--min", "51"]),
+        Err(ConfigError::EmptyRange { min: 51, max: 50 })
+    );
+    // a single number is a range, if not a very exciting one
+    assert!(parse(&["--min", "7", "--max", "7"]).is_ok());
+}
+
+#[test]
+fn no_attempts_at_all() {
+    assert_eq!(parse(&["--attempts", "0"]), Err(ConfigError::ZeroAttempts));
+    let config = Config {
+        max_attempts: Some(0),
+        ..Config::default()
+    };
+    assert_eq!(config.validate(), Err(ConfigError::ZeroAttempts));
+}
+
+#[test]
+fn options_that_dont_parse() {
+    assert_eq!(
+        parse(&["--max"]),
+        Err(ConfigError::MissingValue(String::from("--max")))
+    );
+    assert_eq!(
+        parse(&["--max", "lots"]),
+        Err(ConfigError::InvalidNumber {
+            option: String::from("--max"),
+            value: String::from("lots")
+        })
+    );
+    assert_eq!(
+        parse(&["--colour"]),
+        Err(ConfigError::UnknownOption(String::from("--colour")))
+    );
+    assert_eq!(
+        parse(&["--players", "solo"]),
+        Err(ConfigError::TooFewPlayers)
+    );
+}
@@ -0,0 +1,143 @@
+// races over loopback: the host listens on port 0 and scripted clients play against it
+use guessing_game::net::{self, JoinOutcome, RaceResult};
+use guessing_game::{Config, Game};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread::{self, JoinHandle};
+
+// the secret for seed 42 with the standard 1 to 100 rules
+const SEED: u64 = 42;
+const SECRET: u32 = 14;
+
+fn host(game: Game) -> (SocketAddr, JoinHandle<io::Result<RaceResult>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("loopback is always there");
+    let addr = listener.local_addr().expect("the listener is bound");
+    (
+        addr,
+        thread::spawn(move || net::serve(listener, game, io::sink())),
+    )
+}
+
+// a client that speaks the protocol directly, one line at a time
+struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+}
+
+impl Client {
+    fn connect(addr: SocketAddr) -> Client {
+        let stream = TcpStream::connect(addr).expect("the host is listening");
+        let reader = BufReader::new(stream.try_clone().expect("streams can be cloned"));
+        Client { stream, reader }
+    }
+
+    // send one line and return the line the host answers with
+    fn say(&mut self, line: &str) -> String {
+        writeln!(self.stream, "{line}").expect("the host is connected");
+        self.hear()
+    }
+
+    fn hear(&mut self) -> String {
+        let mut line = String::new();
+        self.reader
+            .read_line(&mut line)
+            .expect("the host is connected");
+        line.trim_end().to_string()
+    }
+}
+
+#[test]
+fn joining_player_wins() {
+    let (addr, host) = host(Game::from_seed(SEED));
+    let mut output = Vec::new();
+    let outcome = net::join(addr, "Ann", "50\n25\n12\n14\n".as_bytes(), &mut output)
+        .expect("the race runs to the end");
+    assert_eq!(outcome, JoinOutcome::Won { guesses: 4 });
+    let transcript = String::from_utf8(output).expect("join only writes text");
+    assert!(transcript.contains("Too big!\n"));
+    assert!(transcript.ends_with("You win with 4 guesses!\n"));
+
+    let result = host
+        .join()
+        .expect("the host didn't panic")
+        .expect("the race ran");
+    assert_eq!(
+        result,
+        RaceResult {
+            winner: Some((String::from("Ann"), 4)),
+            secret: SECRET,
+        }
+    );
+}
+
+#[test]
+fn names_have_to_be_unique() {
+    let (addr, host) = host(Game::from_seed(SEED));
+    let mut ann = Client::connect(addr);
+    assert_eq!(ann.say("HELLO Ann"), "WELCOME 1 100 -");
+    let mut other = Client::connect(addr);
+    assert_eq!(
+        other.say("HELLO Ann"),
+        "INVALID someone called Ann already joined"
+    );
+    assert_eq!(other.say("HELLO Bob"), "WELCOME 1 100 -");
+
+    assert_eq!(other.say("GUESS 50"), "BIG -");
+    assert_eq!(ann.say("GUESS 14"), "WINNER 1 14 Ann");
+    // everyone still connected hears who won
+    assert_eq!(other.hear(), "WINNER 1 14 Ann");
+
+    let result = host
+        .join()
+        .expect("the host didn't panic")
+        .expect("the race ran");
+    assert_eq!(result.winner, Some((String::from("Ann"), 1)));
+}
+
+#[test]
+fn everyone_out_of_guesses() {
+    let config = Config {
+        max_attempts: Some(1),
+        ..Config::default()
+    };
+    let game = Game::with_config(&mut StdRng::seed_from_u64(SEED), &config);
+    let (addr, host) = host(game);
+    let mut ann = Client::connect(addr);
+    assert_eq!(ann.say("GUESS 1"), "INVALID say HELLO first");
+    assert_eq!(ann.say("HELLO Ann"), "WELCOME 1 100 1");
+    assert_eq!(ann.say("GUESS 0"), "INVALID 0 is not between 1 and 100.");
+    assert_eq!(ann.say("GUESS 1"), "OVER 14");
+
+    let result = host
+        .join()
+        .expect("the host didn't panic")
+        .expect("the race ran");
+    assert_eq!(
+        result,
+        RaceResult {
+            winner: None,
+            secret: SECRET,
+        }
+    );
+}
+
+#[test]
+fn last_player_quitting_ends_the_race() {
+    let (addr, host) = host(Game::from_seed(SEED));
+    let mut bob = Client::connect(addr);
+    assert_eq!(bob.say("HELLO Bob"), "WELCOME 1 100 -");
+    let mut output = Vec::new();
+    let outcome = net::join(addr, "Ann", "50\nquit\n".as_bytes(), &mut output)
+        .expect("the host says goodbye");
+    assert_eq!(outcome, JoinOutcome::Quit);
+
+    // with Ann gone, Bob leaving is the end of it, so he hears the secret instead of a goodbye
+    assert_eq!(bob.say("QUIT"), "OVER 14");
+    let result = host
+        .join()
+        .expect("the host didn't panic")
+        .expect("the race ran");
+    assert_eq!(result.winner, None);
+}
@@ -0,0 +1,239 @@
+// replay scripted games against fixed seeds and check everything the player would have seen
+use guessing_game::{play, play_local, Config, Game, MultiOutcome, Outcome};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+// the secret for seed 42 with the standard 1 to 100 rules
+const SEED: u64 = 42;
+const SECRET: u32 = 14;
+
+fn replay(game: &mut Game, input: &str) -> (Outcome, String) {
+    let mut output = Vec::new();
+    let outcome = play(game, input.as_bytes(), &mut output).expect("writing to a Vec can't fail");
+    (
+        outcome,
+        String::from_utf8(output).expect("play only writes text"),
+    )
+}
+
+#[test]
+fn seed_picks_the_same_secret() {
+    assert_eq!(Game::from_seed(SEED).secret(), SECRET);
+    assert_eq!(
+        Game::from_seed(SEED).secret(),
+        Game::from_seed(SEED).secret()
+    );
+}
+
+#[test]
+fn winning_game() {
+    let mut game = Game::from_seed(SEED);
+    let (outcome, transcript) = replay(&mut game, "50\n25\n12\n14\n");
+    assert_eq!(outcome, Outcome::Won { guesses: 4 });
+    assert_eq!(
+        transcript,
+        "Guess the number between 1 and 100!
+Type quit to give up, hint for a hint or history to see your guesses.
+Please input your guess.
+You guessed: 50
+Too big!
+Please input your guess.
+You guessed: 25
+Too big!
+Please input your guess.
+You guessed: 12
+Too small!
+Please input your guess.
+You guessed: 14
+You win!
+Your guesses:
+  1. 50 (too big)
+  2. 25 (too big)
+  3. 12 (too small)
+  4. 14 (correct)
+"
+    );
+}
+
+#[test]
+fn bad_entries_cost_nothing() {
+    let mut game = Game::from_seed(SEED);
+    let (outcome, transcript) = replay(&mut game, "\nabc\n0\n50\n50\nhint\n14\n");
+    assert_eq!(outcome, Outcome::Won { guesses: 2 });
+    assert_eq!(
+        transcript,
+        "Guess the number between 1 and 100!
+Type quit to give up, hint for a hint or history to see your guesses.
+Please input your guess.
+Please type a number.
+Please input your guess.
+'abc' is not a number.
+Please input your guess.
+0 is not between 1 and 100.
+Please input your guess.
+You guessed: 50
+Too big!
+Please input your guess.
+You already guessed 50.
+Please input your guess.
+The number is between 1 and 49.
+Please input your guess.
+You guessed: 14
+You win!
+Your guesses:
+  1. 50 (too big)
+  2. 14 (correct)
+"
+    );
+}
+
+#[test]
+fn quitting_reveals_the_secret() {
+    let mut game = Game::from_seed(SEED);
+    let (outcome, transcript) = replay(&mut game, "90\nquit\n");
+    assert_eq!(outcome, Outcome::Quit { secret: SECRET });
+    assert!(transcript.ends_with(
+        "Giving up? The number was 14.
+Your guesses:
+  1. 90 (too big)
+"
+    ));
+}
+
+#[test]
+fn running_out_of_input() {
+    let mut game = Game::from_seed(SEED);
+    let (outcome, transcript) = replay(&mut game, "1\n");
+    assert_eq!(outcome, Outcome::InputClosed);
+    assert!(transcript.ends_with("Too small!\nPlease input your guess.\n"));
+}
+
+#[test]
+fn losing_with_an_attempt_limit() {
+    let config = Config {
+        max_attempts: Some(2),
+        ..Config::default()
+    };
+    let mut game = Game::with_config(&mut StdRng::seed_from_u64(SEED), &config);
+    let (outcome, transcript) = replay(&mut game, "99\n98\n14\n");
+    assert_eq!(outcome, Outcome::Lost { secret: SECRET });
+    assert_eq!(
+        transcript,
+        "Guess the number between 1 and 100!
+You have 2 guesses.
+Type quit to give up, hint for a hint or history to see your guesses.
+Please input your guess.
+You guessed: 99
+Too big!
+Last guess!
+Please input your guess.
+You guessed: 98
+Too big!
+You lose, it was 14.
+Your guesses:
+  1. 99 (too big)
+  2. 98 (too big)
+"
+    );
+}
+
+#[test]
+fn numbers_outside_u32_are_out_of_range() {
+    let mut game = Game::from_seed(SEED);
+    let (outcome, transcript) = replay(&mut game, "-5\n4294967296\n14\n");
+    assert_eq!(outcome, Outcome::Won { guesses: 1 });
+    assert!(transcript.contains("-5 is out of range.\n"));
+    assert!(transcript.contains("4294967296 is out of range.\n"));
+}
+
+#[test]
+fn local_multiplayer_asks_for_a_number_the_same_way() {
+    let mut game = Game::from_seed(SEED);
+    let players = [String::from("Ann"), String::from("Bob")];
+    let mut output = Vec::new();
+    let outcome = play_local(
+        &mut game,
+        &players,
+        "\n-1\n20\n14\n".as_bytes(),
+        &mut output,
+    )
+    .expect("writing to a Vec can't fail");
+    assert_eq!(
+        outcome,
+        MultiOutcome::Won {
+            player: String::from("Bob"),
+            guesses: 1
+        }
+    );
+    let transcript = String::from_utf8(output).expect("play_local only writes text");
+    assert!(transcript.contains(
+        "Ann, please input your guess.
+Please type a number.
+Ann, please input your guess.
+-1 is out of range.
+Ann, please input your guess.
+Ann guessed: 20
+"
+    ));
+}
+
+#[test]
+fn hints_narrow_the_range_and_say_if_you_are_getting_closer() {
+    let config = Config {
+        hints: true,
+        ..Config::default()
+    };
+    let mut game = Game::with_config(&mut StdRng::seed_from_u64(SEED), &config);
+    assert_eq!(game.secret(), SECRET);
+    let (outcome, transcript) = replay(&mut game, "50\n30\n40\n20\n8\n13\nhint\n14\n");
+    assert_eq!(outcome, Outcome::Won { guesses: 7 });
+    assert_eq!(
+        transcript,
+        "Guess the number between 1 and 100!
+Type quit to give up, hint for a hint or history to see your guesses.
+Please input your guess.
+You guessed: 50
+Too big!
+You're cold.
+The number is between 1 and 49.
+Please input your guess.
+You guessed: 30
+Too big!
+You're cold, and getting closer.
+The number is between 1 and 29.
+Please input your guess.
+You guessed: 40
+Too big!
+You're cold, and getting farther away.
+The number is between 1 and 29.
+Please input your guess.
+You guessed: 20
+Too big!
+You're warm, and getting closer.
+The number is between 1 and 19.
+Please input your guess.
+You guessed: 8
+Too small!
+You're warm, no closer than last time.
+The number is between 9 and 19.
+Please input your guess.
+You guessed: 13
+Too small!
+You're burning, and getting closer.
+The number is between 14 and 19.
+Please input your guess.
+The number is between 14 and 19.
+Please input your guess.
+You guessed: 14
+You win!
+Your guesses:
+  1. 50 (too big, cold)
+  2. 30 (too big, cold)
+  3. 40 (too big, cold)
+  4. 20 (too big, warm)
+  5. 8 (too small, warm)
+  6. 13 (too small, burning)
+  7. 14 (correct)
+"
+    );
+}
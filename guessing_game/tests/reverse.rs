@@ -0,0 +1,82 @@
+// the reverse game against scripted answers
+use guessing_game::{play_reverse, Answer, ReverseOutcome};
+
+fn replay(min: u32, max: u32, input: &str) -> (ReverseOutcome, String) {
+    let mut output = Vec::new();
+    let outcome =
+        play_reverse(min, max, input.as_bytes(), &mut output).expect("writing to a Vec can't fail");
+    (
+        outcome,
+        String::from_utf8(output).expect("play_reverse only writes text"),
+    )
+}
+
+#[test]
+fn guesses_the_number() {
+    let (outcome, transcript) = replay(1, 100, "higher\nlower\ncorrect\n");
+    assert_eq!(
+        outcome,
+        ReverseOutcome::Guessed {
+            number: 62,
+            guesses: 3
+        }
+    );
+    assert!(transcript.contains("Is it 50?\nIs it 75?\nIs it 62?\n"));
+    assert!(transcript.ends_with("Got it! Your number is 62, I needed 3 guesses.\n"));
+}
+
+#[test]
+fn contradiction_with_a_range_left_reports_the_range() {
+    let (outcome, transcript) = replay(5, 6, "lower\n");
+    assert_eq!(
+        outcome,
+        ReverseOutcome::Contradiction {
+            guess: 5,
+            answer: Answer::Lower
+        }
+    );
+    assert!(transcript.ends_with(
+        "That can't be right: the number has to be between 5 and 6, so it can't be lower than 5.\n"
+    ));
+}
+
+#[test]
+fn contradiction_with_one_number_left_names_it() {
+    let (outcome, transcript) = replay(1, 3, "higher\nhigher\n");
+    assert_eq!(
+        outcome,
+        ReverseOutcome::Contradiction {
+            guess: 3,
+            answer: Answer::Higher
+        }
+    );
+    assert!(transcript.ends_with(
+        "That can't be right: your earlier answers mean the number is 3, so it can't be higher.\n"
+    ));
+}
+
+#[test]
+fn contradiction_before_any_answers_doesnt_blame_them() {
+    let (outcome, transcript) = replay(7, 7, "lower\n");
+    assert_eq!(
+        outcome,
+        ReverseOutcome::Contradiction {
+            guess: 7,
+            answer: Answer::Lower
+        }
+    );
+    assert!(transcript.ends_with(
+        "That can't be right: 7 is the only number between 7 and 7, so it can't be lower.\n"
+    ));
+    assert!(!transcript.contains("earlier answers"));
+
+    // and a number on its own can still be guessed
+    let (outcome, _) = replay(7, 7, "correct\n");
+    assert_eq!(
+        outcome,
+        ReverseOutcome::Guessed {
+            number: 7,
+            guesses: 1
+        }
+    );
+}
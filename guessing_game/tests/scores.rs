@@ -0,0 +1,156 @@
+// the high-score table: reading it back, the files it refuses, and the order of the leaderboard
+use guessing_game::scores::{ScoreError, FORMAT_VERSION};
+use guessing_game::{Score, ScoreTable};
+use std::fs;
+use std::path::PathBuf;
+
+fn score(name: &str, guesses: u32, difficulty: &str, elapsed_secs: f64) -> Score {
+    Score {
+        name: name.to_string(),
+        guesses,
+        min: 1,
+        max: 100,
+        difficulty: difficulty.to_string(),
+        elapsed_secs,
+    }
+}
+
+// a file of its own for each test, so they can run at the same time
+fn scores_file(name: &str) -> PathBuf {
+    let path =
+        std::env::temp_dir().join(format!("guessing_game-{}-{name}.json", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn a_table_survives_being_written_out() {
+    let mut table = ScoreTable::default();
+    table.record(score("Ferris", 7, "normal", 12.5));
+    table.record(score("Corro", 4, "custom", 3.0));
+    assert_eq!(ScoreTable::from_json(&table.to_json()).unwrap(), table);
+
+    let path = scores_file("round-trip");
+    table.save(&path).expect("the temp dir is writable");
+    assert_eq!(ScoreTable::load(&path).unwrap(), table);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_file_written_by_hand() {
+    let json = r#"{
+        "version": 1,
+        "scores": [
+            {"name": "Ferris", "guesses": 5, "min": 1, "max": 50, "difficulty": "easy", "elapsed_secs": 9.5}
+        ]
+    }"#;
+    let table = ScoreTable::from_json(json).expect("it's a version 1 file");
+    assert_eq!(
+        table.scores(),
+        &[Score {
+            max: 50,
+            ..score("Ferris", 5, "easy", 9.5)
+        }]
+    );
+}
+
+#[test]
+fn nobody_has_won_yet() {
+    let path = scores_file("missing");
+    assert_eq!(ScoreTable::load(&path).unwrap(), ScoreTable::default());
+    let mut output = Vec::new();
+    ScoreTable::default()
+        .write_leaderboard(&mut output)
+        .unwrap();
+    assert_eq!(output, b"No high scores yet. Go win a game!\n");
+}
+
+#[test]
+fn corrupt_files() {
+    for json in [
+        "",
+        "{",
+        "not json at all",
+        // the right version, but the scores aren't scores
+        r#"{"version": 1, "scores": [{"name": "Ferris"}]}"#,
+        r#"{"version": 1}"#,
+    ] {
+        assert!(
+            matches!(ScoreTable::from_json(json), Err(ScoreError::Corrupt(_))),
+            "{json:?} was read"
+        );
+    }
+
+    let path = scores_file("corrupt");
+    fs::write(&path, "{\"version\": 1, \"scores\": [").unwrap();
+    assert!(matches!(
+        ScoreTable::load(&path),
+        Err(ScoreError::Corrupt(_))
+    ));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn versions_it_doesnt_know() {
+    for json in [
+        r#"{"scores": []}"#,
+        r#"{"version": "1", "scores": []}"#,
+        "[]",
+    ] {
+        assert!(
+            matches!(ScoreTable::from_json(json), Err(ScoreError::MissingVersion)),
+            "{json:?} was read"
+        );
+    }
+
+    let newer = FORMAT_VERSION + 1;
+    let err = ScoreTable::from_json(&format!(r#"{{"version": {newer}, "scores": []}}"#))
+        .expect_err("it's from a newer build");
+    assert!(matches!(err, ScoreError::UnsupportedVersion(version) if version == newer));
+    assert_eq!(
+        err.to_string(),
+        format!("the scores file uses format version {newer}, but this build only understands up to {FORMAT_VERSION}")
+    );
+
+    let err = ScoreTable::from_json(r#"{"version": 0, "scores": []}"#)
+        .expect_err("there never was a version 0");
+    assert!(matches!(err, ScoreError::UnsupportedVersion(0)));
+    assert_eq!(
+        err.to_string(),
+        "the scores file uses format version 0, which no version of the game ever wrote"
+    );
+}
+
+#[test]
+fn the_leaderboard_puts_the_presets_first_and_the_best_at_the_top() {
+    let mut table = ScoreTable::default();
+    table.record(score("slow", 6, "hard", 40.0));
+    table.record(score("zed", 3, "zany", 1.0));
+    table.record(score("fast", 6, "hard", 20.0));
+    table.record(score("few", 5, "hard", 90.0));
+    table.record(score("mid", 4, "normal", 8.0));
+    table.record(score("own", 2, "custom", 5.0));
+    table.record(score("easy", 9, "easy", 30.0));
+
+    let leaderboard: Vec<(&str, Vec<&str>)> = table
+        .leaderboard()
+        .into_iter()
+        .map(|(difficulty, scores)| {
+            (
+                difficulty,
+                scores.iter().map(|score| score.name.as_str()).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        leaderboard,
+        vec![
+            ("easy", vec!["easy"]),
+            ("normal", vec!["mid"]),
+            // fewest guesses first, then the quickest of those
+            ("hard", vec!["few", "fast", "slow"]),
+            ("custom", vec!["own"]),
+            ("zany", vec!["zed"]),
+        ]
+    );
+}
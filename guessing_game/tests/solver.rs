@@ -0,0 +1,90 @@
+// the solvers: the binary search never needs more than optimal_bound guesses, and a seed always plays out the same
+use guessing_game::config::Config;
+use guessing_game::solver::{optimal_bound, simulate, SolverKind};
+use guessing_game::{BinarySearchSolver, Solver};
+
+// how many guesses the binary search takes to find secret in min..=max
+fn binary_search_for(secret: u32, min: u32, max: u32) -> u32 {
+    let mut solver = BinarySearchSolver::new(min, max);
+    let mut guesses = 0;
+    loop {
+        let guess = solver.next_guess();
+        guesses += 1;
+        let result = guess.cmp(&secret);
+        if result.is_eq() {
+            return guesses;
+        }
+        solver.feedback(guess, result);
+        let (low, high) = solver.remaining();
+        assert!(
+            low <= secret && secret <= high,
+            "{secret} fell outside {low}..={high}"
+        );
+    }
+}
+
+#[test]
+fn the_bound_holds_for_every_secret_and_is_reached() {
+    let ranges = [
+        (1, 1),
+        (1, 2),
+        (1, 3),
+        (1, 50),
+        (1, 64),
+        (1, 100),
+        (1, 1000),
+        (1, 1024),
+        (37, 4100),
+        // near the top, where low + high would overflow
+        (u32::MAX - 2000, u32::MAX),
+        (0, 0),
+    ];
+    for (min, max) in ranges {
+        let bound = optimal_bound(min, max);
+        let worst = (min..=max)
+            .map(|secret| binary_search_for(secret, min, max))
+            .max()
+            .unwrap();
+        // it's a bound on the worst case, and the worst case really is that bad
+        assert_eq!(worst, bound, "{min}..={max}");
+    }
+}
+
+#[test]
+fn bounds_for_the_presets() {
+    assert_eq!(optimal_bound(1, 50), 6);
+    assert_eq!(optimal_bound(1, 100), 7);
+    assert_eq!(optimal_bound(1, 1000), 10);
+    // 2^k - 1 numbers is exactly what k guesses can cover
+    assert_eq!(optimal_bound(1, 127), 7);
+    assert_eq!(optimal_bound(1, 128), 8);
+    assert_eq!(optimal_bound(0, u32::MAX), 33);
+}
+
+#[test]
+fn simulations_stay_within_the_bound() {
+    let config = Config::default();
+    let stats = simulate(&config, SolverKind::BinarySearch, 500, 7);
+    assert_eq!(stats.games, 500);
+    assert!(stats.max <= optimal_bound(config.min, config.max));
+    assert!(stats.min >= 1);
+    assert!(f64::from(stats.min) <= stats.mean && stats.mean <= f64::from(stats.max));
+
+    // the random solver listens to the feedback, so it always finishes, just not within the bound
+    let random = simulate(&config, SolverKind::Random, 500, 7);
+    assert!(random.max <= config.max - config.min + 1);
+    assert!(random.mean >= stats.mean);
+}
+
+#[test]
+fn the_same_seed_gives_the_same_statistics() {
+    let config = Config::default();
+    for kind in [SolverKind::BinarySearch, SolverKind::Random] {
+        assert_eq!(
+            simulate(&config, kind, 200, 42),
+            simulate(&config, kind, 200, 42)
+        );
+    }
+    let none = simulate(&config, SolverKind::Random, 0, 42);
+    assert_eq!((none.games, none.mean, none.min, none.max), (0, 0.0, 0, 0));
+}
@@ -0,0 +1,167 @@
+// compares the grid index against checking every rectangle, for the queries layout code runs most
+// run with `cargo bench`
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rectangles::{GridIndex, Rectangle};
+
+// a small linear congruential generator, so the benchmark gets the same rectangles every run without needing rand
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % u64::from(bound)) as u32
+    }
+}
+
+// n rectangles between 10 and 60 pixels on a side, scattered over a square canvas that grows with n
+fn layout(n: usize) -> Vec<Rectangle> {
+    let mut rng = Lcg(n as u64);
+    let canvas = (n as f64).sqrt() as u32 * 40;
+    (0..n)
+        .map(|_| {
+            Rectangle::at(
+                rng.next(canvas),
+                rng.next(canvas),
+                10 + rng.next(50),
+                10 + rng.next(50),
+            )
+        })
+        .collect()
+}
+
+fn index(rectangles: &[Rectangle]) -> GridIndex {
+    let mut index = GridIndex::new(64);
+    for rectangle in rectangles {
+        index.insert(*rectangle);
+    }
+    index
+}
+
+fn window_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("window query");
+    for n in [1_000, 10_000, 50_000] {
+        let rectangles = layout(n);
+        let index = index(&rectangles);
+        let window = Rectangle::at(100, 100, 200, 200);
+
+        group.bench_with_input(
+            BenchmarkId::new("naive", n),
+            &rectangles,
+            |b, rectangles| {
+                b.iter(|| {
+                    rectangles
+                        .iter()
+                        .filter(|rectangle| rectangle.intersects(black_box(&window)))
+                        .count()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("grid", n), &index, |b, index| {
+            b.iter(|| index.query_window(black_box(&window)).len())
+        });
+    }
+    group.finish();
+}
+
+// for every rectangle, find every other rectangle that contains it: the O(n²) loop this index was added to replace
+fn all_containing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("containing, every rectangle");
+    group.sample_size(10);
+    for n in [1_000, 10_000] {
+        let rectangles = layout(n);
+        let index = index(&rectangles);
+
+        group.bench_with_input(
+            BenchmarkId::new("naive", n),
+            &rectangles,
+            |b, rectangles| {
+                b.iter(|| {
+                    rectangles
+                        .iter()
+                        .map(|target| {
+                            rectangles
+                                .iter()
+                                .filter(|rectangle| rectangle.contains(target))
+                                .count()
+                        })
+                        .sum::<usize>()
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("grid", n), &rectangles, |b, rectangles| {
+            b.iter(|| {
+                rectangles
+                    .iter()
+                    .map(|target| index.containing(target).len())
+                    .sum::<usize>()
+            })
+        });
+    }
+    group.finish();
+}
+
+// for every rectangle, find every rectangle big enough to hold it, wherever it is
+// both sides collect the ids, since when most rectangles can hold the target most of the time goes into listing them;
+// the large targets are the ones only a few rectangles can hold, where the index gets to skip most of them
+fn all_can_hold(c: &mut Criterion) {
+    let mut group = c.benchmark_group("can_hold");
+    group.sample_size(10);
+    for n in [1_000, 10_000] {
+        let rectangles = layout(n);
+        let index = index(&rectangles);
+        let large: Vec<Rectangle> = rectangles
+            .iter()
+            .filter(|rectangle| rectangle.width.min(rectangle.height) >= 45)
+            .copied()
+            .collect();
+
+        for (targets, label) in [(&rectangles, "every rectangle"), (&large, "large targets")] {
+            group.bench_with_input(
+                BenchmarkId::new(format!("naive, {label}"), n),
+                targets,
+                |b, targets| {
+                    b.iter(|| {
+                        targets
+                            .iter()
+                            .map(|target| {
+                                rectangles
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, rectangle)| rectangle.can_hold(target))
+                                    .map(|(id, _)| id)
+                                    .collect::<Vec<_>>()
+                                    .len()
+                            })
+                            .sum::<usize>()
+                    })
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("sizes, {label}"), n),
+                targets,
+                |b, targets| {
+                    b.iter(|| {
+                        targets
+                            .iter()
+                            .map(|target| index.can_hold(target).len())
+                            .sum::<usize>()
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+fn build(c: &mut Criterion) {
+    let rectangles = layout(50_000);
+    c.bench_function("build grid, 50000 rectangles", |b| {
+        b.iter(|| index(black_box(&rectangles)).len())
+    });
+}
+
+criterion_group!(benches, window_queries, all_containing, all_can_hold, build);
+criterion_main!(benches);
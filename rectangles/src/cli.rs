@@ -0,0 +1,266 @@
+// the command-line side of the rectangles binary: read a list of rectangles, report on them
+// running the binary without arguments still shows the walkthrough in main.rs
+use rectangles::parse::{self, ParseError};
+use rectangles::render::{self, RenderOptions};
+use rectangles::Rectangle;
+use serde::Serialize;
+use std::fs;
+use std::io::{self, Read, Write};
+
+pub const USAGE: &str = "\
+usage: rectangles [--input csv|json] [--output table|csv|json|svg|ascii] [--highlight-overlaps] <file or - for stdin>
+
+reads a list of rectangles and prints the area, perimeter and which other rectangles each one can hold
+the svg and ascii outputs draw the rectangles instead, labelled with their index, and --highlight-overlaps marks where they overlap
+the input format is guessed from the file extension or the contents when --input isn't given";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum InputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Table,
+    Csv,
+    Json,
+    Svg,
+    Ascii,
+}
+
+struct Options {
+    input: Option<InputFormat>,
+    output: OutputFormat,
+    highlight_overlaps: bool,
+    path: String,
+}
+
+// one row of the report
+#[derive(Serialize)]
+struct Row {
+    index: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+    area: u64,
+    perimeter: u64,
+    // indices of the other rectangles this one can hold
+    can_hold: Vec<usize>,
+}
+
+// run the command line with the arguments after the program name, returning the exit code
+pub fn run<I: Iterator<Item = String>>(args: I) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}\n\n{USAGE}");
+            return 2;
+        }
+    };
+
+    let text = match read_input(&options.path) {
+        Ok(text) => text,
+        Err(err) => {
+            eprintln!("error: couldn't read {}: {err}", options.path);
+            return 1;
+        }
+    };
+
+    let input = options
+        .input
+        .unwrap_or_else(|| guess_format(&options.path, &text));
+    let parsed: Result<Vec<Rectangle>, ParseError> = match input {
+        InputFormat::Csv => parse::parse_csv(&text),
+        InputFormat::Json => parse::parse_json(&text),
+    };
+    let rectangles = match parsed {
+        Ok(rectangles) => rectangles,
+        Err(err) => {
+            eprintln!("error: {}: {err}", options.path);
+            return 1;
+        }
+    };
+
+    let rows = report(&rectangles);
+    let render_options = RenderOptions {
+        highlight_overlaps: options.highlight_overlaps,
+        ..RenderOptions::default()
+    };
+    let labelled: Vec<(String, Rectangle)> = rectangles
+        .iter()
+        .enumerate()
+        .map(|(index, rectangle)| (index.to_string(), *rectangle))
+        .collect();
+    let mut stdout = io::stdout().lock();
+    let written = match options.output {
+        OutputFormat::Svg => {
+            stdout.write_all(render::render_svg(&labelled, &render_options).as_bytes())
+        }
+        OutputFormat::Ascii => {
+            stdout.write_all(render::render_ascii(&labelled, &render_options).as_bytes())
+        }
+        OutputFormat::Table => write_table(&rows, &mut stdout),
+        OutputFormat::Csv => write_csv(&rows, &mut stdout),
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut stdout, &rows)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(stdout)),
+    };
+    match written {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error: couldn't write the report: {err}");
+            1
+        }
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut input = None;
+    let mut output = OutputFormat::Table;
+    let mut highlight_overlaps = false;
+    let mut path = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => {
+                input = Some(match args.next().as_deref() {
+                    Some("csv") => InputFormat::Csv,
+                    Some("json") => InputFormat::Json,
+                    _ => return Err(String::from("--input needs csv or json")),
+                })
+            }
+            "--output" => {
+                output = match args.next().as_deref() {
+                    Some("table") => OutputFormat::Table,
+                    Some("csv") => OutputFormat::Csv,
+                    Some("json") => OutputFormat::Json,
+                    Some("svg") => OutputFormat::Svg,
+                    Some("ascii") => OutputFormat::Ascii,
+                    _ => {
+                        return Err(String::from(
+                            "--output needs table, csv, json, svg or ascii",
+                        ))
+                    }
+                }
+            }
+            "--highlight-overlaps" => highlight_overlaps = true,
+            _ if arg.starts_with("--") => return Err(format!("unknown option '{arg}'")),
+            _ if path.is_none() => path = Some(arg),
+            _ => return Err(format!("unexpected argument '{arg}'")),
+        }
+    }
+
+    Ok(Options {
+        input,
+        output,
+        highlight_overlaps,
+        path: path.ok_or_else(|| String::from("no input file given"))?,
+    })
+}
+
+fn read_input(path: &str) -> io::Result<String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+// go by the extension if there is one, otherwise a JSON list has to start with [
+fn guess_format(path: &str, text: &str) -> InputFormat {
+    if path.ends_with(".json") {
+        InputFormat::Json
+    } else if path.ends_with(".csv") {
+        InputFormat::Csv
+    } else if text.trim_start().starts_with('[') {
+        InputFormat::Json
+    } else {
+        InputFormat::Csv
+    }
+}
+
+fn report(rectangles: &[Rectangle]) -> Vec<Row> {
+    rectangles
+        .iter()
+        .enumerate()
+        .map(|(index, rectangle)| Row {
+            index,
+            x: rectangle.x,
+            y: rectangle.y,
+            width: rectangle.width,
+            height: rectangle.height,
+            area: rectangle.area_method(),
+            perimeter: rectangle.perimeter(),
+            can_hold: rectangles
+                .iter()
+                .enumerate()
+                .filter(|&(other, candidate)| other != index && rectangle.can_hold(candidate))
+                .map(|(other, _)| other)
+                .collect(),
+        })
+        .collect()
+}
+
+fn join(indices: &[usize], separator: &str) -> String {
+    indices
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn write_table<W: Write>(rows: &[Row], output: &mut W) -> io::Result<()> {
+    let rectangles: Vec<String> = rows
+        .iter()
+        .map(|row| Rectangle::at(row.x, row.y, row.width, row.height).to_string())
+        .collect();
+    let width = rectangles
+        .iter()
+        .map(String::len)
+        .chain(["rectangle".len()])
+        .max()
+        .unwrap_or(0);
+
+    writeln!(
+        output,
+        "{:>5}  {:<width$}  {:>20}  {:>11}  can hold",
+        "#", "rectangle", "area", "perimeter"
+    )?;
+    for (row, rectangle) in rows.iter().zip(&rectangles) {
+        let can_hold = if row.can_hold.is_empty() {
+            String::from("-")
+        } else {
+            join(&row.can_hold, ", ")
+        };
+        writeln!(
+            output,
+            "{:>5}  {:<width$}  {:>20}  {:>11}  {}",
+            row.index, rectangle, row.area, row.perimeter, can_hold
+        )?;
+    }
+    Ok(())
+}
+
+// the can_hold column is a space-separated list so it stays a single CSV field
+fn write_csv<W: Write>(rows: &[Row], output: &mut W) -> io::Result<()> {
+    writeln!(output, "index,x,y,width,height,area,perimeter,can_hold")?;
+    for row in rows {
+        writeln!(
+            output,
+            "{},{},{},{},{},{},{},{}",
+            row.index,
+            row.x,
+            row.y,
+            row.width,
+            row.height,
+            row.area,
+            row.perimeter,
+            join(&row.can_hold, " ")
+        )?;
+    }
+    Ok(())
+}
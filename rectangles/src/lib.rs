@@ -0,0 +1,354 @@
+// the Rectangle type from main.rs, pulled out into a library so other crates can depend on it
+// rectangles now have a position as well as a size, which is what layout code needs
+// coordinates are in pixels with the origin at the top left, so x grows to the right and y grows downwards
+// a rectangle covers the half-open area x..x + width by y..y + height, so two rectangles that only share an edge don't overlap
+// Rectangle on its own still means u32 pixels, but the coordinates can be any Coordinate type, including lengths
+// in real-world units from the units module
+
+use serde::Serialize;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+pub mod packing;
+pub mod parse;
+pub mod render;
+pub mod shapes;
+pub mod spatial;
+pub mod units;
+
+pub use packing::{pack, Packing, Placement, Strategy};
+pub use parse::ParseError;
+pub use render::{render_ascii, render_svg, RenderOptions};
+pub use shapes::{Circle, Shape, Square, Triangle};
+pub use spatial::GridIndex;
+pub use units::{Dpi, Inches, Length, Millimetres, Pixels, Unit};
+
+// Deserialize is implemented by hand in parse.rs, so a rectangle can also be read from a "WxH" string
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize)] // the debug annotation allows us to print the struct using the debug trait
+pub struct Rectangle<T = u32> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+// the number types a Rectangle can be made of
+// copying, ordering, adding and subtracting is all the geometry needs; the far edges and the perimeter are worked out
+// in a wider type, so x + width can't overflow, and so is the area for types where that wider type can multiply
+pub trait Coordinate:
+    Copy + PartialOrd + Add<Output = Self> + Sub<Output = Self> + fmt::Debug
+{
+    const ZERO: Self;
+    // what union() caps a size at when the real one doesn't fit
+    const MAX: Self;
+
+    // big enough for the sum of any two coordinates, and for integers their product too
+    type Wide: Copy + PartialOrd + Add<Output = Self::Wide> + Sub<Output = Self::Wide> + fmt::Debug;
+
+    fn widen(self) -> Self::Wide;
+
+    // back from the wider type, None if it doesn't fit
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+}
+
+macro_rules! coordinate {
+    ($($number:ty => $wide:ty),*) => {
+        $(
+            impl Coordinate for $number {
+                const ZERO: Self = 0;
+                const MAX: Self = <$number>::MAX;
+                type Wide = $wide;
+
+                fn widen(self) -> $wide {
+                    <$wide>::from(self)
+                }
+
+                fn narrow(wide: $wide) -> Option<$number> {
+                    <$number>::try_from(wide).ok()
+                }
+            }
+        )*
+    };
+}
+
+coordinate!(u32 => u64, u64 => u128, i32 => i64, i64 => i128);
+
+impl Coordinate for f32 {
+    const ZERO: Self = 0.0;
+    const MAX: Self = f32::MAX;
+    type Wide = f64;
+
+    fn widen(self) -> f64 {
+        f64::from(self)
+    }
+
+    // anything past f32::MAX would turn into infinity
+    fn narrow(wide: f64) -> Option<f32> {
+        let narrow = wide as f32;
+        (narrow.is_finite() || !wide.is_finite()).then_some(narrow)
+    }
+}
+
+// f64 is already as wide as floats go, so its edges are rounded like any other f64 sum
+impl Coordinate for f64 {
+    const ZERO: Self = 0.0;
+    const MAX: Self = f64::MAX;
+    type Wide = f64;
+
+    fn widen(self) -> f64 {
+        self
+    }
+
+    fn narrow(wide: f64) -> Option<f64> {
+        Some(wide)
+    }
+}
+
+// PartialOrd has no max or min, so these stand in for them
+fn larger<C: PartialOrd>(a: C, b: C) -> C {
+    if b > a {
+        b
+    } else {
+        a
+    }
+}
+
+fn smaller<C: PartialOrd>(a: C, b: C) -> C {
+    if b < a {
+        b
+    } else {
+        a
+    }
+}
+
+// returned by the checked_ methods when the answer doesn't fit in the integer type it has to go in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OverflowError {
+    // which operation overflowed, e.g. "scale"
+    pub operation: &'static str,
+}
+
+impl fmt::Display for OverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} overflowed", self.operation)
+    }
+}
+
+impl std::error::Error for OverflowError {}
+
+impl<T: Coordinate> Rectangle<T> {
+    // we can have multiple impl blocks for the same struct, this one works for every coordinate type
+    // begin with the impl keyword, which is short for implementation, meaning we are implementing the rectangle struct
+    // any function in this block is called an "associated function" of the Rectangle struct
+    // associated functions that don't take self as a parameter are not methods, but can still be useful, such as a constructor function
+
+    // a rectangle of the given size sitting at the origin
+    pub fn new(width: T, height: T) -> Rectangle<T> {
+        Rectangle::at(T::ZERO, T::ZERO, width, height)
+    }
+
+    // a rectangle of the given size with its top left corner at (x, y)
+    // the position can be anything the coordinate type allows, but a negative (or NaN) width or height panics
+    pub fn at(x: T, y: T, width: T, height: T) -> Rectangle<T> {
+        assert!(
+            width >= T::ZERO && height >= T::ZERO,
+            "a rectangle can't be {width:?} by {height:?}, its sides can't be negative"
+        );
+        Rectangle {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    pub fn square(size: T) -> Rectangle<T> {
+        // this is an associated function that is not a method because it does not take self as a parameter
+        // this is a constructor function that returns a square Rectangle with the given size
+        Rectangle::new(size, size)
+    }
+
+    // this function will take self as a parameter, but will also take in another rectangle to compare to
+    // the rule: self can hold other if other's size fits inside self's size, either as it is or turned by 90 degrees
+    // fitting exactly counts, so a rectangle can always hold one of the same size
+    // positions are ignored, this only answers whether other could be moved inside self; use contains() to check where they actually are
+    // other has to have the same coordinate type, so asking whether millimetres can hold inches doesn't compile
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        let fits = self.width >= other.width && self.height >= other.height;
+        let fits_rotated = self.width >= other.height && self.height >= other.width;
+        fits || fits_rotated
+    }
+
+    // the same rectangle with every coordinate passed through f
+    // this is how a rectangle changes coordinate type, e.g. .map(f64::from) or .map(Length::<f64, Millimetres>::new)
+    pub fn map<S: Coordinate, F: Fn(T) -> S>(&self, f: F) -> Rectangle<S> {
+        Rectangle::at(f(self.x), f(self.y), f(self.width), f(self.height))
+    }
+
+    pub fn perimeter(&self) -> T::Wide {
+        let half = self.width.widen() + self.height.widen();
+        half + half
+    }
+
+    pub fn width(&self) -> bool {
+        // this method returns true if the rectangle's width is greater than 0
+        // the main use for methods with the same name as fields is as getters, so you can have private fields with public getter methods
+        self.width > T::ZERO
+    }
+
+    // the x coordinate just past the right edge
+    // this is in the wider type because x + width doesn't always fit in T, e.g. a u64 for a u32 rectangle
+    pub fn right(&self) -> T::Wide {
+        self.x.widen() + self.width.widen()
+    }
+
+    // the y coordinate just past the bottom edge
+    pub fn bottom(&self) -> T::Wide {
+        self.y.widen() + self.height.widen()
+    }
+
+    // whether other lies completely inside self where they are now
+    // touching the edges is fine, and an empty rectangle on self's boundary counts as inside
+    pub fn contains(&self, other: &Rectangle<T>) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    // whether the point is covered by the rectangle
+    // the left and top edges are part of it, the right and bottom edges belong to whatever is next to it
+    pub fn contains_point(&self, x: T, y: T) -> bool {
+        x >= self.x && y >= self.y && x.widen() < self.right() && y.widen() < self.bottom()
+    }
+
+    // whether the two rectangles share some area; sharing just an edge or a corner doesn't count
+    pub fn intersects(&self, other: &Rectangle<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // the area the two rectangles have in common, None if they don't overlap
+    pub fn intersection(&self, other: &Rectangle<T>) -> Option<Rectangle<T>> {
+        let left = larger(self.x, other.x);
+        let top = larger(self.y, other.y);
+        let right = smaller(self.right(), other.right());
+        let bottom = smaller(self.bottom(), other.bottom());
+        if right <= left.widen() || bottom <= top.widen() {
+            return None;
+        }
+        // the overlap is never bigger than either rectangle, so its size always fits back into T
+        Some(Rectangle::at(
+            left,
+            top,
+            T::narrow(right - left.widen())?,
+            T::narrow(bottom - top.widen())?,
+        ))
+    }
+
+    // the smallest rectangle that covers both, also known as their bounding box
+    // two rectangles far apart near the edge of T's range can have a bounding box wider than T can hold,
+    // in which case the size is capped at T::MAX; use checked_union to find out when that happens
+    pub fn union(&self, other: &Rectangle<T>) -> Rectangle<T> {
+        let (left, top, width, height) = self.union_size(other);
+        Rectangle::at(
+            left,
+            top,
+            T::narrow(width).unwrap_or(T::MAX),
+            T::narrow(height).unwrap_or(T::MAX),
+        )
+    }
+
+    // the same as union, but an error instead of a capped size when the bounding box is too big for T
+    pub fn checked_union(&self, other: &Rectangle<T>) -> Result<Rectangle<T>, OverflowError> {
+        let (left, top, width, height) = self.union_size(other);
+        let overflow = OverflowError { operation: "union" };
+        Ok(Rectangle::at(
+            left,
+            top,
+            T::narrow(width).ok_or(overflow)?,
+            T::narrow(height).ok_or(overflow)?,
+        ))
+    }
+
+    // the top left corner and the full size of the bounding box of both rectangles
+    fn union_size(&self, other: &Rectangle<T>) -> (T, T, T::Wide, T::Wide) {
+        let left = smaller(self.x, other.x);
+        let top = smaller(self.y, other.y);
+        let right = larger(self.right(), other.right());
+        let bottom = larger(self.bottom(), other.bottom());
+        (left, top, right - left.widen(), bottom - top.widen())
+    }
+
+    // the bounding box of any number of rectangles, None for an empty list
+    pub fn bounding_box<'a, I: IntoIterator<Item = &'a Rectangle<T>>>(
+        rectangles: I,
+    ) -> Option<Rectangle<T>>
+    where
+        T: 'a,
+    {
+        rectangles
+            .into_iter()
+            .copied()
+            .reduce(|bounds, rectangle| bounds.union(&rectangle))
+    }
+}
+
+// the area needs multiplying, which lengths can't do: millimetres times millimetres are square millimetres, not millimetres
+impl<T: Coordinate> Rectangle<T>
+where
+    T::Wide: Mul<Output = T::Wide>,
+{
+    // this function is a method that is defined on the Rectangle struct
+    // since area formulas for different shapes are different,
+    // it is best to define the area method on the Rectangle struct specifically to avoid it being called on, for example, a circle struct
+    // the area is in the wider type, e.g. a u64 for a u32 rectangle, because the product of two u32s can need up to 64 bits
+    pub fn area_method(&self) -> T::Wide {
+        // &self in this case is short for self: &Self. the Self type is an alias for whatever type we are implementing the methods on
+        // we can take ownership of self, borrow self immutably (like we did here), or borrow self mutably, just like any other parameter
+        self.width.widen() * self.height.widen()
+    }
+}
+
+impl Rectangle {
+    // everything from here on is for u32 rectangles, where the arithmetic can be done without losing anything
+
+    // the combined area of several rectangles, None if the total doesn't fit in a u64
+    // a single area always fits, but two very large ones added together might not
+    pub fn checked_total_area<'a, I: IntoIterator<Item = &'a Rectangle>>(
+        rectangles: I,
+    ) -> Option<u64> {
+        rectangles.into_iter().try_fold(0u64, |total, rectangle| {
+            total.checked_add(rectangle.area_method())
+        })
+    }
+
+    // the same rectangle moved by (dx, dy)
+    // None if that would move it to a negative position or past u32::MAX
+    pub fn translate(&self, dx: i64, dy: i64) -> Option<Rectangle> {
+        // i64 has room for any u32 plus or minus any i32, but not for any i64, so the additions are checked too
+        let x = u32::try_from(i64::from(self.x).checked_add(dx)?).ok()?;
+        let y = u32::try_from(i64::from(self.y).checked_add(dy)?).ok()?;
+        Some(Rectangle { x, y, ..*self })
+    }
+
+    // the same rectangle with its width and height multiplied by factor
+    // the position stays where it is, so the rectangle grows to the right and downwards
+    // this used to be a plain multiplication, which panics in debug builds and silently wraps around in release builds
+    pub fn checked_scale(&self, factor: u32) -> Result<Rectangle, OverflowError> {
+        let overflow = OverflowError { operation: "scale" };
+        Ok(Rectangle {
+            width: self.width.checked_mul(factor).ok_or(overflow)?,
+            height: self.height.checked_mul(factor).ok_or(overflow)?,
+            ..*self
+        })
+    }
+
+    // the same as checked_scale, but any side that would overflow is capped at u32::MAX instead
+    pub fn saturating_scale(&self, factor: u32) -> Rectangle {
+        Rectangle {
+            width: self.width.saturating_mul(factor),
+            height: self.height.saturating_mul(factor),
+            ..*self
+        }
+    }
+}
@@ -1,10 +1,18 @@
-#[derive(Debug)] // this annotation allows us to print the struct using the debug trait
-struct Rectangle {
-    width: u32,
-    height: u32,
-}
+// the Rectangle struct and its methods live in lib.rs so other crates can use them too
+mod cli;
+
+use rectangles::packing::{self, Strategy};
+use rectangles::{
+    Circle, Dpi, GridIndex, Inches, Length, Millimetres, Pixels, Rectangle, Shape, Square, Triangle,
+};
+use std::{env, process};
 
 fn main() {
+    // with arguments, this is a tool that reads rectangles from a file; without, it walks through what rectangles can do
+    if env::args().len() > 1 {
+        process::exit(cli::run(env::args().skip(1)));
+    }
+
     // define the width and height of a rectangle in pixels
     let width1 = 30;
     let height1 = 50;
@@ -21,6 +29,8 @@ fn main() {
     );
 
     let rect2 = Rectangle {
+        x: 0,
+        y: 0,
         width: 30,
         height: 50,
     };
@@ -40,17 +50,13 @@ fn main() {
     // where the macro is called, along with the value of the expression it is fed
     // this goes to stderr instead of stdout
     let scale = 2;
-    let rect3 = Rectangle {
-        width: dbg!(width1 * scale),
-        height: dbg!(height1 * scale),
-    };
+    // the scaling goes through checked_scale so a rectangle that is too big to scale is an error instead of an overflow
+    let rect3 = dbg!(Rectangle::new(width1, height1).checked_scale(scale))
+        .expect("a 30x50 rectangle can be doubled without overflowing");
     dbg!(rect3);
 
     // create a new rectangle since we destroyed rect3 with dbg!
-    let rect4 = Rectangle {
-        width: 30,
-        height: 50,
-    };
+    let rect4 = Rectangle::new(30, 50);
     // we can call the area method on the new rectangle
     println!(
         "The area of the rectangle is {} square pixels.",
@@ -67,18 +73,9 @@ fn main() {
     }
 
     // create 3 new rectangles to compare
-    let rect1 = Rectangle {
-        width: 30,
-        height: 50,
-    };
-    let rect2 = Rectangle {
-        width: 10,
-        height: 40,
-    };
-    let rect3 = Rectangle {
-        width: 60,
-        height: 45,
-    };
+    let rect1 = Rectangle::new(30, 50);
+    let rect2 = Rectangle::new(10, 40);
+    let rect3 = Rectangle::new(60, 45);
 
     // compare rect1 to rect2 and rect3 to see if rect1 can hold them
     println!(
@@ -101,8 +98,8 @@ fn main() {
     // create a square
     let square1 = Rectangle::square(10); // this is creates a new instance of the Rectangle struct using the square associated function
     println!("area of square1: {}", square1.area_method());
-    println!( // since square is still a rectangle, we can use the can_hold method to see if rect1 can hold square1
-              // ideally, I would like to use a square struct that is a child of the rectangle struct, but I don't know how to do that yet
+    println!(
+        // since square is still a rectangle, we can use the can_hold method to see if rect1 can hold square1
         "Can rect1 ({}x{}) hold square1 ({}x{})? {}",
         rect1.width,
         rect1.height,
@@ -110,12 +107,152 @@ fn main() {
         square1.height,
         rect1.can_hold(&square1)
     );
+
+    // rust has no inheritance, so there's no way to make a square struct a child of the rectangle struct
+    // instead, Square is its own type, and it shares behaviour with Rectangle through the Shape trait
+    // trait objects let us keep different shapes in the same vec and treat them all the same way
+    let shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(rect1),
+        Box::new(Square::at(0, 0, 10)),
+        Box::new(Circle::at(20, 20, 20)),
+        Box::new(Triangle::new((0, 0), (30, 0), (0, 40))),
+    ];
+    for shape in &shapes {
+        println!(
+            "{:?}: area {:.1}, perimeter {:.1}, bounding box {:?}",
+            shape,
+            shape.area(),
+            shape.perimeter(),
+            shape.bounding_box()
+        );
+    }
+    for holder in &shapes {
+        for other in &shapes {
+            println!(
+                "can {:?} hold {:?}? {}",
+                holder,
+                other,
+                holder.can_hold(other.as_ref())
+            );
+        }
+    }
+
+    // rectangles also have a position now, which lets us ask where they are and how they overlap
+    let window = Rectangle::at(10, 10, 100, 80);
+    let button = Rectangle::at(90, 70, 40, 20);
+    println!("window: {window:?}, button: {button:?}");
+    println!("perimeter of the window: {}", window.perimeter());
+    println!(
+        "does the window contain (50, 50)? {}",
+        window.contains_point(50, 50)
+    );
+    println!(
+        "does the window contain the button? {}",
+        window.contains(&button)
+    );
+    println!("do they overlap? {}", window.intersects(&button));
+    println!("where they overlap: {:?}", window.intersection(&button));
+    println!("bounding box of both: {:?}", window.union(&button));
+    println!(
+        "button moved 20 pixels left: {:?}",
+        button.translate(-20, 0)
+    );
+    println!("window at twice the size: {:?}", window.checked_scale(2));
+
+    // sizes near the limits of u32 don't overflow any more
+    let huge = Rectangle::new(u32::MAX, u32::MAX);
+    println!(
+        "area of the largest possible rectangle: {}",
+        huge.area_method()
+    );
+    println!("scaling it up: {:?}", huge.checked_scale(2));
+    println!("scaling it up, capped: {:?}", huge.saturating_scale(2));
+
+    // can_hold only tells us whether one rectangle fits in another, but the packer can fit a whole list of them into a container
+    let sheet = Rectangle::new(100, 60);
+    let sprites = [
+        Rectangle::new(40, 30),
+        Rectangle::new(30, 40),
+        Rectangle::new(60, 20),
+        Rectangle::new(20, 20),
+        Rectangle::new(50, 10),
+        Rectangle::new(10, 50),
+    ];
+    for strategy in [Strategy::Shelf, Strategy::Guillotine, Strategy::MaxRects] {
+        let packed = packing::pack(&sheet, &sprites, strategy, true)
+            .expect("the sheet is at the origin, so every position in it fits in a u32");
+        println!(
+            "{:?}: placed {} of {} sprites, {} square pixels unused",
+            strategy,
+            packed.placements.len(),
+            sprites.len(),
+            packed.unused_area
+        );
+        for placement in &packed.placements {
+            println!(
+                "  sprite {} at ({}, {}){}",
+                placement.item,
+                placement.rect.x,
+                placement.rect.y,
+                if placement.rotated { ", rotated" } else { "" }
+            );
+        }
+    }
+
+    // with lots of rectangles, checking every pair gets slow, so a spatial index only looks at the ones nearby
+    let mut index = GridIndex::new(64);
+    let window_id = index.insert(window);
+    index.insert(button);
+    println!("rectangles at (95, 75): {:?}", index.query_point(95, 75));
+    println!(
+        "rectangles overlapping 0x0 to 50x50: {:?}",
+        index.query_window(&Rectangle::new(50, 50))
+    );
+    println!(
+        "rectangles that contain a 10x10 square at (20, 20): {:?}",
+        index.containing(&Rectangle::at(20, 20, 10, 10))
+    );
+    index.remove(window_id);
+    println!("after removing the window: {:?}", index.query_point(95, 75));
+
+    // Rectangle is generic, so the coordinates don't have to be u32s
+    // plain Rectangle still means Rectangle<u32>, everything above was using that
+    let precise: Rectangle<f64> = Rectangle::new(2.5, 4.0);
+    let offscreen: Rectangle<i64> = Rectangle::at(-20, -10, 30, 30);
+    println!(
+        "can {precise:?} hold a 3.9 square? {}",
+        precise.can_hold(&Rectangle::square(3.9))
+    );
+    println!("{offscreen:?} as f64: {:?}", offscreen.map(|n| n as f64));
+    println!(
+        "the part of it on a 100x100 screen: {:?}",
+        offscreen.intersection(&Rectangle::new(100, 100))
+    );
+
+    // the coordinates can also carry a unit, which stops millimetres from being compared with inches by mistake
+    // a4.can_hold(&letter) wouldn't compile here, the letter sheet has to be converted to millimetres first
+    let a4: Rectangle<Length<f64, Millimetres>> = Rectangle::new(210.0, 297.0).map(Length::new);
+    let letter: Rectangle<Length<f64, Inches>> = Rectangle::new(8.5, 11.0).map(Length::new);
+    let letter_in_mm = letter.convert::<Millimetres>(Dpi::PRINT);
+    println!(
+        "letter paper is {:.1} by {:.1}",
+        letter_in_mm.width, letter_in_mm.height
+    );
+    println!("can A4 hold letter? {}", a4.can_hold(&letter_in_mm));
+    let a4_in_pixels = a4.convert::<Pixels>(Dpi::PRINT);
+    println!(
+        "A4 at {} dpi is {:.0} by {:.0}",
+        Dpi::PRINT.0,
+        a4_in_pixels.width,
+        a4_in_pixels.height
+    );
 }
 
 // this function takes the width and height of a rectangle and returns the area
 // this is not ideal because it is not clear that the variables are connected outside the context of this function
-fn area(width: u32, height: u32) -> u32 {
-    width * height
+// the result is a u64 because multiplying two u32s can overflow a u32, which panics in debug builds and wraps around in release builds
+fn area(width: u32, height: u32) -> u64 {
+    u64::from(width) * u64::from(height)
 }
 
 // this function does the same thing as the one above but uses a tuple instead
@@ -123,50 +260,14 @@ fn area(width: u32, height: u32) -> u32 {
 // but it is still not ideal because it is not clear what the values in the tuple represent
 // it doesn't matter in the case of multiplying width and height,
 // but it could be confusing in other cases
-fn area_tuple(dimensions: (u32, u32)) -> u32 {
-    dimensions.0 * dimensions.1
+fn area_tuple(dimensions: (u32, u32)) -> u64 {
+    u64::from(dimensions.0) * u64::from(dimensions.1)
 }
 
 // this function also does the same thing as the one above but uses a struct instead
 // this is a better option because it is clear that the variables are connected when they are passed into the function
 // and it is clear what the values in the struct represent, both in the context of the function and outside of it
 // this is also the most flexible option because it allows us to add more fields to the struct in the future without having to change the function
-fn area_struct(rectangle: &Rectangle) -> u32 {
-    rectangle.width * rectangle.height // accessing the fields of a borrowed struct does not move them
-}
-
-impl Rectangle { // we can have multiple impl blocks for the same struct, but there's no reason for me to separate these
-    // begin with the impl keyword, which is short for implementation, meaning we are implementing the rectangle struct
-    // any function in this block is called an "associated function" of the Rectangle struct
-    // associated functions that don't take self as a parameter are not methods, but can still be useful, such as a constructor function
-    // this function is a method that is defined on the Rectangle struct
-    // this is the best option because it takes the solution from above and explicitly associates it with the struct
-    // since area formulas for different shapes are different,
-    // it is best to define the area method on the Rectangle struct specifically to avoid it being called on, for example, a circle struct
-    fn area_method(&self) -> u32 {
-        // &self in this case is short for self: &Self. the Self type is an alias for whatever type we are implementing the methods on
-        // we can take ownership of self, borrow self immutably (like we did here), or borrow self mutably, just like any other parameter
-        self.width * self.height
-    }
-
-    fn width(&self) -> bool {
-        // this method returns true if the rectangle's width is greater than 0
-        // the main use for methods with the same name as fields is as getters, so you can have private fields with public getter methods
-        self.width > 0
-    }
-
-    // this function will take self as a parameter, but will also take in another rectangle to compare to
-    fn can_hold(&self, other: &Rectangle) -> bool {
-        // this method returns true if the rectangle can hold another rectangle
-        self.width > other.width && self.height > other.height
-    }
-
-    fn square(size: u32) -> Rectangle {
-        // this is an associated function that is not a method because it does not take self as a parameter
-        // this is a constructor function that returns a square Rectangle with the given size
-        Rectangle {
-            width: size,
-            height: size,
-        }
-    }
+fn area_struct(rectangle: &Rectangle) -> u64 {
+    u64::from(rectangle.width) * u64::from(rectangle.height) // accessing the fields of a borrowed struct does not move them
 }
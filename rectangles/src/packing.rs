@@ -0,0 +1,337 @@
+// 2D bin packing: placing a list of rectangles inside a container without any of them overlapping
+// the item rectangles are only used for their size, pack() decides where they go and reports positions inside the container
+// all three strategies are greedy heuristics, so they're fast but not guaranteed to find a packing whenever one exists
+use crate::{OverflowError, Rectangle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    // rows of items, each row as tall as the tallest item in it; simple and fast, wastes space above shorter items
+    Shelf,
+    // keeps a list of free rectangles and cuts the one an item goes into in two
+    Guillotine,
+    // keeps every maximal free rectangle, even overlapping ones; the slowest and usually the tightest
+    MaxRects,
+}
+
+// where an item ended up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    // the item's index in the slice that was passed to pack()
+    pub item: usize,
+    // the space the item takes up, in the same coordinates as the container
+    // when rotated is true, width and height are swapped compared to the item
+    pub rect: Rectangle,
+    pub rotated: bool,
+}
+
+// the result of packing a container
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Packing {
+    // placements in the order the items were placed, which isn't necessarily the order they were passed in
+    pub placements: Vec<Placement>,
+    // indices of the items that didn't fit
+    pub unplaced: Vec<usize>,
+    pub used_area: u64,
+    pub unused_area: u64,
+}
+
+// pack items into container using the given strategy
+// with allow_rotation, items may be turned by 90 degrees when that makes them fit better
+// fails if an item fits in the container but the container is so far out that the item's position doesn't fit in a u32
+pub fn pack(
+    container: &Rectangle,
+    items: &[Rectangle],
+    strategy: Strategy,
+    allow_rotation: bool,
+) -> Result<Packing, OverflowError> {
+    let mut packer: Box<dyn Packer> = match strategy {
+        Strategy::Shelf => Box::new(ShelfPacker::new(container)),
+        Strategy::Guillotine => Box::new(GuillotinePacker::new(container)),
+        Strategy::MaxRects => Box::new(MaxRectsPacker::new(container)),
+    };
+
+    // big items first, since they're the hardest to fit once the container starts filling up
+    // shelves are sorted by height instead, since that's what decides how much space a shelf wastes
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by_key(|&index| {
+        let item = &items[index];
+        let key = match strategy {
+            Strategy::Shelf if allow_rotation => u64::from(item.width.min(item.height)),
+            Strategy::Shelf => u64::from(item.height),
+            _ => item.area_method(),
+        };
+        std::cmp::Reverse(key)
+    });
+
+    let mut packing = Packing::default();
+    for index in order {
+        let item = &items[index];
+        // an empty item takes up no space, so it can go in the corner as long as it isn't longer than the container
+        let found = if item.width == 0 || item.height == 0 {
+            orientations(item.width, item.height, allow_rotation)
+                .into_iter()
+                .find(|&(width, height, _)| width <= container.width && height <= container.height)
+                .map(|(width, height, rotated)| (Rectangle::new(width, height), rotated))
+        } else {
+            packer.place(item.width, item.height, allow_rotation)
+        };
+
+        // placements are worked out relative to the container, then moved to where the container is
+        // an item that doesn't fit isn't an error, but one that fits somewhere no u32 can point to is
+        match found {
+            Some((rect, rotated)) => {
+                let rect = rect
+                    .translate(i64::from(container.x), i64::from(container.y))
+                    .ok_or(OverflowError { operation: "pack" })?;
+                packing.used_area += rect.area_method();
+                packing.placements.push(Placement {
+                    item: index,
+                    rect,
+                    rotated,
+                });
+            }
+            None => packing.unplaced.push(index),
+        }
+    }
+    packing.unused_area = container.area_method() - packing.used_area;
+    Ok(packing)
+}
+
+// one packing strategy
+// place() finds room for an item of the given size and returns where it went, relative to the container's top left corner
+trait Packer {
+    fn place(&mut self, width: u32, height: u32, allow_rotation: bool)
+        -> Option<(Rectangle, bool)>;
+}
+
+// the ways an item can be oriented: as it is, and turned by 90 degrees if that's allowed and makes a difference
+fn orientations(width: u32, height: u32, allow_rotation: bool) -> Vec<(u32, u32, bool)> {
+    let mut orientations = vec![(width, height, false)];
+    if allow_rotation && width != height {
+        orientations.push((height, width, true));
+    }
+    orientations
+}
+
+struct Shelf {
+    y: u32,
+    height: u32,
+    used_width: u32,
+}
+
+struct ShelfPacker {
+    width: u32,
+    height: u32,
+    shelves: Vec<Shelf>,
+    // where the next shelf would start
+    next_y: u32,
+}
+
+impl ShelfPacker {
+    fn new(container: &Rectangle) -> ShelfPacker {
+        ShelfPacker {
+            width: container.width,
+            height: container.height,
+            shelves: Vec::new(),
+            next_y: 0,
+        }
+    }
+}
+
+impl Packer for ShelfPacker {
+    fn place(
+        &mut self,
+        width: u32,
+        height: u32,
+        allow_rotation: bool,
+    ) -> Option<(Rectangle, bool)> {
+        // lying items down keeps shelves low, so try the flatter orientation first
+        let mut orientations = orientations(width, height, allow_rotation);
+        orientations.sort_by_key(|&(_, height, _)| height);
+
+        // the first shelf with room wins
+        for &(width, height, rotated) in &orientations {
+            for shelf in &mut self.shelves {
+                if height <= shelf.height && width <= self.width - shelf.used_width {
+                    let rect = Rectangle::at(shelf.used_width, shelf.y, width, height);
+                    shelf.used_width += width;
+                    return Some((rect, rotated));
+                }
+            }
+        }
+
+        // otherwise open a new shelf underneath the others
+        for &(width, height, rotated) in &orientations {
+            if width <= self.width && height <= self.height - self.next_y {
+                let rect = Rectangle::at(0, self.next_y, width, height);
+                self.shelves.push(Shelf {
+                    y: self.next_y,
+                    height,
+                    used_width: width,
+                });
+                self.next_y += height;
+                return Some((rect, rotated));
+            }
+        }
+        None
+    }
+}
+
+struct GuillotinePacker {
+    free: Vec<Rectangle>,
+}
+
+impl GuillotinePacker {
+    fn new(container: &Rectangle) -> GuillotinePacker {
+        GuillotinePacker {
+            free: vec![Rectangle::new(container.width, container.height)],
+        }
+    }
+}
+
+impl Packer for GuillotinePacker {
+    fn place(
+        &mut self,
+        width: u32,
+        height: u32,
+        allow_rotation: bool,
+    ) -> Option<(Rectangle, bool)> {
+        // best area fit: the free rectangle with the least space left over, ties going to the one with the smaller short side left
+        let mut best = None;
+        let mut best_score = (u64::MAX, u32::MAX);
+        for (index, free) in self.free.iter().enumerate() {
+            for (width, height, rotated) in orientations(width, height, allow_rotation) {
+                if width > free.width || height > free.height {
+                    continue;
+                }
+                let waste = free.area_method() - u64::from(width) * u64::from(height);
+                let short_side = (free.width - width).min(free.height - height);
+                let score = (waste, short_side);
+                if score < best_score {
+                    best = Some((index, width, height, rotated));
+                    best_score = score;
+                }
+            }
+        }
+
+        let (index, width, height, rotated) = best?;
+        let free = self.free.swap_remove(index);
+        let placed = Rectangle::at(free.x, free.y, width, height);
+
+        // cut the rest of the free rectangle in two, making the cut along the shorter leftover side
+        // that keeps the bigger of the two pieces as large as possible
+        let left_over_width = free.width - width;
+        let left_over_height = free.height - height;
+        let (right, below) = if left_over_width < left_over_height {
+            (
+                Rectangle::at(free.x + width, free.y, left_over_width, height),
+                Rectangle::at(free.x, free.y + height, free.width, left_over_height),
+            )
+        } else {
+            (
+                Rectangle::at(free.x + width, free.y, left_over_width, free.height),
+                Rectangle::at(free.x, free.y + height, width, left_over_height),
+            )
+        };
+        self.free.extend(
+            [right, below]
+                .into_iter()
+                .filter(|rect| rect.width > 0 && rect.height > 0),
+        );
+        Some((placed, rotated))
+    }
+}
+
+struct MaxRectsPacker {
+    free: Vec<Rectangle>,
+}
+
+impl MaxRectsPacker {
+    fn new(container: &Rectangle) -> MaxRectsPacker {
+        MaxRectsPacker {
+            free: vec![Rectangle::new(container.width, container.height)],
+        }
+    }
+}
+
+impl Packer for MaxRectsPacker {
+    fn place(
+        &mut self,
+        width: u32,
+        height: u32,
+        allow_rotation: bool,
+    ) -> Option<(Rectangle, bool)> {
+        // best short side fit: the free rectangle where the item leaves the smallest gap along one side
+        let mut best: Option<(Rectangle, bool, (u32, u32))> = None;
+        for free in &self.free {
+            for (width, height, rotated) in orientations(width, height, allow_rotation) {
+                if width > free.width || height > free.height {
+                    continue;
+                }
+                let (gap_x, gap_y) = (free.width - width, free.height - height);
+                let score = (gap_x.min(gap_y), gap_x.max(gap_y));
+                if best.is_none_or(|(.., best_score)| score < best_score) {
+                    best = Some((Rectangle::at(free.x, free.y, width, height), rotated, score));
+                }
+            }
+        }
+
+        let (placed, rotated, _) = best?;
+
+        // every free rectangle the item overlaps is replaced by the (up to four) biggest pieces of it the item doesn't cover
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+        for rect in self.free.drain(..) {
+            if !rect.intersects(&placed) {
+                free.push(rect);
+                continue;
+            }
+            // all four differences fit in a u32 since they're measured inside rect
+            let (rect_right, rect_bottom) = (rect.right(), rect.bottom());
+            let (placed_right, placed_bottom) = (placed.right(), placed.bottom());
+            let pieces = [
+                // left of the item
+                Rectangle::at(rect.x, rect.y, placed.x.saturating_sub(rect.x), rect.height),
+                // right of the item
+                Rectangle::at(
+                    placed_right as u32,
+                    rect.y,
+                    rect_right.saturating_sub(placed_right) as u32,
+                    rect.height,
+                ),
+                // above the item
+                Rectangle::at(rect.x, rect.y, rect.width, placed.y.saturating_sub(rect.y)),
+                // below the item
+                Rectangle::at(
+                    rect.x,
+                    placed_bottom as u32,
+                    rect.width,
+                    rect_bottom.saturating_sub(placed_bottom) as u32,
+                ),
+            ];
+            free.extend(
+                pieces
+                    .into_iter()
+                    .filter(|piece| piece.width > 0 && piece.height > 0),
+            );
+        }
+
+        // a free rectangle inside another one adds nothing, so drop it to keep the list short
+        let mut index = 0;
+        while index < free.len() {
+            // of two identical rectangles, only the second one counts as redundant so one of them survives
+            let redundant = free.iter().enumerate().any(|(other, rect)| {
+                other != index
+                    && rect.contains(&free[index])
+                    && (*rect != free[index] || other < index)
+            });
+            if redundant {
+                free.swap_remove(index);
+            } else {
+                index += 1;
+            }
+        }
+        self.free = free;
+
+        Some((placed, rotated))
+    }
+}
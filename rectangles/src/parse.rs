@@ -0,0 +1,349 @@
+// reading rectangles from text, and writing them back out
+// the text form of a rectangle is "WxH", e.g. "30x50", optionally followed by its position as "+X+Y", e.g. "30x50+10+20"
+// that's the same geometry syntax X11 uses, and it has no commas in it so it can sit in a CSV field
+// lists of rectangles can come from CSV or JSON; every error says which line and column of the input it's about
+use crate::Rectangle;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, Visitor};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    // both start at 1, like in a text editor
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<serde_json::Error> for ParseError {
+    fn from(err: serde_json::Error) -> ParseError {
+        // serde_json puts the position at the end of its message as well, so strip that off to avoid saying it twice
+        let message = err.to_string();
+        let message = match message.rfind(" at line ") {
+            Some(end) => message[..end].to_string(),
+            None => message,
+        };
+        ParseError {
+            line: err.line(),
+            column: err.column(),
+            message,
+        }
+    }
+}
+
+// "WxH" for a rectangle at the origin, "WxH+X+Y" otherwise, which is exactly what FromStr accepts
+impl fmt::Display for Rectangle {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if self.x != 0 || self.y != 0 {
+            write!(f, "+{}+{}", self.x, self.y)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Rectangle {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Rectangle, ParseError> {
+        parse_rectangle(s).map_err(|(offset, message)| ParseError {
+            line: 1,
+            column: column(s, offset),
+            message,
+        })
+    }
+}
+
+// the column, counting from 1, of the character at the given byte offset into line
+// columns count characters rather than bytes, so a rectangle labelled "café" doesn't push every later column one too far
+fn column(line: &str, offset: usize) -> usize {
+    line.char_indices()
+        .take_while(|&(start, _)| start < offset)
+        .count()
+        + 1
+}
+
+// the actual "WxH+X+Y" parser
+// errors carry the byte offset into s where the problem is, so callers can turn that into a column in a bigger input
+fn parse_rectangle(s: &str) -> Result<Rectangle, (usize, String)> {
+    let mut cursor = Cursor { text: s, offset: 0 };
+
+    cursor.skip_whitespace();
+    let width = cursor.number("width")?;
+    cursor.skip_whitespace();
+    cursor.expect(&['x', 'X'], "'x' between the width and the height")?;
+    cursor.skip_whitespace();
+    let height = cursor.number("height")?;
+    cursor.skip_whitespace();
+
+    let (mut x, mut y) = (0, 0);
+    if cursor.peek() == Some('+') {
+        cursor.offset += 1;
+        cursor.skip_whitespace();
+        x = cursor.number("x position")?;
+        cursor.skip_whitespace();
+        cursor.expect(&['+'], "'+' between the x and y positions")?;
+        cursor.skip_whitespace();
+        y = cursor.number("y position")?;
+        cursor.skip_whitespace();
+    }
+
+    match cursor.peek() {
+        None => Ok(Rectangle::at(x, y, width, height)),
+        Some(c) => Err((cursor.offset, format!("unexpected '{c}'"))),
+    }
+}
+
+struct Cursor<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl Cursor<'_> {
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.offset += c.len_utf8();
+        }
+    }
+
+    fn expect(&mut self, options: &[char], what: &str) -> Result<(), (usize, String)> {
+        match self.peek() {
+            Some(c) if options.contains(&c) => {
+                self.offset += c.len_utf8();
+                Ok(())
+            }
+            _ => Err((self.offset, format!("expected {what}"))),
+        }
+    }
+
+    fn number(&mut self, what: &str) -> Result<u32, (usize, String)> {
+        let start = self.offset;
+        let digits = self.text[start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Err((start, format!("expected a number for the {what}")));
+        }
+        self.offset += digits;
+        self.text[start..self.offset]
+            .parse()
+            .map_err(|_| (start, format!("the {what} is too large")))
+    }
+}
+
+// one line of CSV split on commas, with each field trimmed and the byte offset where it starts
+fn split_fields(line: &str) -> Vec<(usize, &str)> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    for field in line.split(',') {
+        let trimmed = field.trim_start();
+        fields.push((start + field.len() - trimmed.len(), trimmed.trim_end()));
+        start += field.len() + 1;
+    }
+    fields
+}
+
+// which column of a CSV file holds which field
+// the other columns the CSV report writes (index, area, perimeter and can_hold) are worked out from these, so they're skipped
+struct Columns {
+    x: Option<usize>,
+    y: Option<usize>,
+    width: usize,
+    height: usize,
+}
+
+// read rectangles from CSV, one per line
+// a line is either a single "WxH" or "WxH+X+Y" field, "width,height", or "x,y,width,height"
+// the first line can be a header naming the columns (x, y, width, height in any order, x and y optional) instead
+// a header can also have the columns of `rectangles --output csv`, so a report can be read back in
+// blank lines and lines starting with # are skipped
+pub fn parse_csv(text: &str) -> Result<Vec<Rectangle>, ParseError> {
+    let mut rectangles = Vec::new();
+    let mut columns = None;
+    let mut first = true;
+
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let error = |offset: usize, message: String| ParseError {
+            line: line_number,
+            column: column(line, offset),
+            message,
+        };
+
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let fields = split_fields(line);
+
+        if std::mem::take(&mut first) && fields.iter().any(|(_, field)| is_header(field)) {
+            columns = Some(header(&fields).map_err(|(offset, message)| error(offset, message))?);
+            continue;
+        }
+
+        let number = |(offset, field): (usize, &str), what: &str| {
+            field.parse::<u32>().map_err(|_| {
+                error(
+                    offset,
+                    format!("expected a number for the {what}, found '{field}'"),
+                )
+            })
+        };
+        let field = |column: usize| {
+            fields.get(column).copied().ok_or_else(|| {
+                error(
+                    line.len(),
+                    format!("expected at least {} fields", column + 1),
+                )
+            })
+        };
+
+        let rectangle = match (&columns, fields.len()) {
+            (Some(columns), _) => Rectangle::at(
+                columns
+                    .x
+                    .map_or(Ok(0), |x| number(field(x)?, "x position"))?,
+                columns
+                    .y
+                    .map_or(Ok(0), |y| number(field(y)?, "y position"))?,
+                number(field(columns.width)?, "width")?,
+                number(field(columns.height)?, "height")?,
+            ),
+            (None, 1) => {
+                let (offset, field) = fields[0];
+                parse_rectangle(field).map_err(|(at, message)| error(offset + at, message))?
+            }
+            (None, 2) => Rectangle::new(number(fields[0], "width")?, number(fields[1], "height")?),
+            (None, 4) => Rectangle::at(
+                number(fields[0], "x position")?,
+                number(fields[1], "y position")?,
+                number(fields[2], "width")?,
+                number(fields[3], "height")?,
+            ),
+            (None, count) => {
+                return Err(error(
+                    0,
+                    format!("expected 1, 2 or 4 fields, found {count}"),
+                ))
+            }
+        };
+        rectangles.push(rectangle);
+    }
+    Ok(rectangles)
+}
+
+// columns the CSV report adds on top of the rectangle itself
+const REPORT_COLUMNS: &[&str] = &["index", "area", "perimeter", "can_hold"];
+
+fn is_header(field: &str) -> bool {
+    let field = field.to_ascii_lowercase();
+    matches!(field.as_str(), "x" | "y" | "width" | "height")
+        || REPORT_COLUMNS.contains(&field.as_str())
+}
+
+fn header(fields: &[(usize, &str)]) -> Result<Columns, (usize, String)> {
+    let (mut x, mut y, mut width, mut height) = (None, None, None, None);
+    for (column, &(offset, field)) in fields.iter().enumerate() {
+        let slot = match field.to_ascii_lowercase().as_str() {
+            "x" => &mut x,
+            "y" => &mut y,
+            "width" => &mut width,
+            "height" => &mut height,
+            name if REPORT_COLUMNS.contains(&name) => continue,
+            _ => return Err((offset, format!("unknown column '{field}'"))),
+        };
+        if slot.replace(column).is_some() {
+            return Err((offset, format!("column '{field}' appears twice")));
+        }
+    }
+    let line_end = fields
+        .last()
+        .map_or(0, |(offset, field)| offset + field.len());
+    Ok(Columns {
+        x,
+        y,
+        width: width.ok_or((line_end, String::from("the header has no width column")))?,
+        height: height.ok_or((line_end, String::from("the header has no height column")))?,
+    })
+}
+
+// read rectangles from a JSON array
+// each entry is either a string in the "WxH" or "WxH+X+Y" form, or an object with width, height and optionally x and y
+pub fn parse_json(text: &str) -> Result<Vec<Rectangle>, ParseError> {
+    serde_json::from_str(text).map_err(|err| {
+        // serde_json counts columns in bytes, so count them again in characters
+        let mut err = ParseError::from(err);
+        if let Some(line) = text.lines().nth(err.line.saturating_sub(1)) {
+            err.column = column(line, err.column.saturating_sub(1));
+        }
+        err
+    })
+}
+
+// written by hand instead of derived so a rectangle can be either a "WxH" string or an object
+// serde_json attaches the line and column to any error returned from here
+impl<'de> Deserialize<'de> for Rectangle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Rectangle, D::Error> {
+        deserializer.deserialize_any(RectangleVisitor)
+    }
+}
+
+const FIELDS: &[&str] = &["x", "y", "width", "height"];
+
+struct RectangleVisitor;
+
+impl<'de> Visitor<'de> for RectangleVisitor {
+    type Value = Rectangle;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a \"WxH\" string or an object with a width and a height")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Rectangle, E> {
+        parse_rectangle(s).map_err(|(offset, message)| {
+            E::custom(format!(
+                "{message} at character {} of \"{s}\"",
+                column(s, offset)
+            ))
+        })
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Rectangle, A::Error> {
+        let (mut x, mut y, mut width, mut height) = (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            let (name, slot) = match key.as_str() {
+                "x" => ("x", &mut x),
+                "y" => ("y", &mut y),
+                "width" => ("width", &mut width),
+                "height" => ("height", &mut height),
+                _ => return Err(de::Error::unknown_field(&key, FIELDS)),
+            };
+            if slot.is_some() {
+                return Err(de::Error::duplicate_field(name));
+            }
+            *slot = Some(map.next_value()?);
+        }
+        Ok(Rectangle::at(
+            x.unwrap_or(0),
+            y.unwrap_or(0),
+            width.ok_or_else(|| de::Error::missing_field("width"))?,
+            height.ok_or_else(|| de::Error::missing_field("height"))?,
+        ))
+    }
+}
@@ -0,0 +1,160 @@
+// drawing sets of positioned rectangles, as an SVG document or as ASCII art for the terminal
+// both renderers only depend on their input, with no randomness, hashing or floating point, so the output is stable
+// enough to compare against a saved copy
+use crate::Rectangle;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    // mark the areas where two or more rectangles overlap
+    pub highlight_overlaps: bool,
+    // the widest the ASCII grid may get, and the tallest too, since the characters are square
+    // bigger layouts are drawn with each character standing for several pixels
+    pub max_columns: u32,
+}
+
+impl Default for RenderOptions {
+    fn default() -> RenderOptions {
+        RenderOptions {
+            highlight_overlaps: false,
+            max_columns: 80,
+        }
+    }
+}
+
+// fill colours for the rectangles, used in order and starting over once they run out
+const PALETTE: [&str; 8] = [
+    "#4e79a7", "#f28e2b", "#59a14f", "#e15759", "#76b7b2", "#edc948", "#b07aa1", "#9c755f",
+];
+
+const OVERLAP_COLOUR: &str = "#ff0000";
+
+// the characters the ASCII renderer fills rectangles with, used in order and starting over once they run out
+const SYMBOLS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+// where two rectangles overlap, in the order the pairs come up
+fn overlaps<L>(items: &[(L, Rectangle)]) -> Vec<Rectangle> {
+    let mut overlaps = Vec::new();
+    for (index, (_, first)) in items.iter().enumerate() {
+        for (_, second) in &items[index + 1..] {
+            overlaps.extend(first.intersection(second));
+        }
+    }
+    overlaps
+}
+
+// text that is safe to put inside an SVG element or attribute
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// an SVG document showing every rectangle with its label in the top left corner
+// later rectangles are drawn on top of earlier ones, and the view is cropped to the bounding box of all of them
+pub fn render_svg<L: AsRef<str>>(items: &[(L, Rectangle)], options: &RenderOptions) -> String {
+    let bounds = Rectangle::bounding_box(items.iter().map(|(_, rect)| rect)).unwrap_or_default();
+    let mut svg = String::new();
+
+    // writing to a String can't fail, so the results of writeln! are ignored throughout
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}">"#,
+        bounds.x, bounds.y, bounds.width, bounds.height, bounds.width, bounds.height
+    );
+    for (index, (label, rect)) in items.iter().enumerate() {
+        let colour = PALETTE[index % PALETTE.len()];
+        let _ = writeln!(
+            svg,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{colour}" fill-opacity="0.5" stroke="{colour}"/>"#,
+            rect.x, rect.y, rect.width, rect.height
+        );
+        let _ = writeln!(
+            svg,
+            r#"  <text x="{}" y="{}" font-family="monospace" font-size="12" dominant-baseline="hanging">{}</text>"#,
+            u64::from(rect.x) + 2,
+            u64::from(rect.y) + 2,
+            escape(label.as_ref())
+        );
+    }
+    if options.highlight_overlaps {
+        for overlap in overlaps(items) {
+            let _ = writeln!(
+                svg,
+                r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{OVERLAP_COLOUR}" fill-opacity="0.6" class="overlap"/>"#,
+                overlap.x, overlap.y, overlap.width, overlap.height
+            );
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// an ASCII-art grid with every rectangle filled in with its own letter, followed by a legend saying which letter is which
+// empty space is drawn as '.', and with highlight_overlaps any cell covered by more than one rectangle is drawn as '#'
+// otherwise later rectangles are drawn over earlier ones
+// each character stands for a square of cell x cell pixels, where cell is as small as it can be while keeping both the
+// width and the height within max_columns, so a very tall layout can't produce billions of rows
+pub fn render_ascii<L: AsRef<str>>(items: &[(L, Rectangle)], options: &RenderOptions) -> String {
+    let mut ascii = String::new();
+    let Some(bounds) = Rectangle::bounding_box(items.iter().map(|(_, rect)| rect)) else {
+        return ascii;
+    };
+
+    let longest = bounds.width.max(bounds.height);
+    let cell = longest.div_ceil(options.max_columns.max(1)).max(1);
+    let columns = bounds.width.div_ceil(cell);
+    let rows = bounds.height.div_ceil(cell);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            // a cell belongs to a rectangle if the rectangle covers the pixel in the cell's top left corner
+            // this is done in u64, since cells near the edge of the u32 range can start past u32::MAX
+            let x = u64::from(bounds.x) + u64::from(column) * u64::from(cell);
+            let y = u64::from(bounds.y) + u64::from(row) * u64::from(cell);
+            let mut covering = items
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, rect))| {
+                    x >= u64::from(rect.x)
+                        && y >= u64::from(rect.y)
+                        && x < rect.right()
+                        && y < rect.bottom()
+                })
+                .map(|(index, _)| index);
+            let symbol = match (covering.next(), covering.last()) {
+                (None, _) => '.',
+                (Some(_), Some(_)) if options.highlight_overlaps => '#',
+                (Some(_), Some(last)) => symbol(last),
+                (Some(only), None) => symbol(only),
+            };
+            ascii.push(symbol);
+        }
+        ascii.push('\n');
+    }
+
+    if cell > 1 {
+        let _ = writeln!(ascii, "\neach character is {cell}x{cell} pixels");
+    }
+    ascii.push('\n');
+    for (index, (label, rect)) in items.iter().enumerate() {
+        let _ = writeln!(ascii, "{}  {}  {}", symbol(index), label.as_ref(), rect);
+    }
+    if options.highlight_overlaps {
+        let _ = writeln!(ascii, "#  overlap");
+    }
+    ascii
+}
+
+fn symbol(index: usize) -> char {
+    char::from(SYMBOLS[index % SYMBOLS.len()])
+}
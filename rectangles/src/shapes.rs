@@ -0,0 +1,210 @@
+// rust doesn't have inheritance, so a square can't be a "child" of a rectangle
+// instead, every shape implements the Shape trait, and code that doesn't care which shape it has works with &dyn Shape or Box<dyn Shape>
+use crate::Rectangle;
+use std::fmt;
+
+// what every shape can tell us about itself
+// the Debug bound means a Vec<Box<dyn Shape>> can still be printed with {:?}
+pub trait Shape: fmt::Debug {
+    fn area(&self) -> f64;
+
+    fn perimeter(&self) -> f64;
+
+    // the smallest axis-aligned rectangle that covers the shape
+    fn bounding_box(&self) -> Rectangle;
+
+    // the radius of the smallest circle the shape fits in
+    fn outer_radius(&self) -> f64;
+
+    // whether other fits inside self if it's moved there, like Rectangle::can_hold but across shape kinds
+    // the default compares bounding boxes, which is exact for rectangle-like holders; shapes that aren't rectangles override it
+    // for some pairs the answer is conservative: false can mean "couldn't prove it fits", never "fits when it doesn't"
+    fn can_hold(&self, other: &dyn Shape) -> bool {
+        self.bounding_box().can_hold(&other.bounding_box())
+    }
+}
+
+// Rectangle has its own can_hold and perimeter methods, and those win over the trait's when called with method syntax,
+// so rect.can_hold(&other_rect) still works as before and Shape::can_hold(&rect, &circle) compares across shapes
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        self.area_method() as f64
+    }
+
+    fn perimeter(&self) -> f64 {
+        Rectangle::perimeter(self) as f64
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        *self
+    }
+
+    fn outer_radius(&self) -> f64 {
+        f64::from(self.width).hypot(f64::from(self.height)) / 2.0
+    }
+}
+
+// a square with its top left corner at (x, y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Square {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+}
+
+impl Square {
+    pub fn at(x: u32, y: u32, size: u32) -> Square {
+        Square { x, y, size }
+    }
+}
+
+// every square is also a rectangle, so it can be turned into one whenever a Rectangle is needed
+impl From<Square> for Rectangle {
+    fn from(square: Square) -> Rectangle {
+        Rectangle::at(square.x, square.y, square.size, square.size)
+    }
+}
+
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        Rectangle::from(*self).area()
+    }
+
+    fn perimeter(&self) -> f64 {
+        4.0 * f64::from(self.size)
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        Rectangle::from(*self)
+    }
+
+    fn outer_radius(&self) -> f64 {
+        f64::from(self.size) * std::f64::consts::SQRT_2 / 2.0
+    }
+}
+
+// a circle around the point (x, y)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Circle {
+    pub x: u32,
+    pub y: u32,
+    pub radius: u32,
+}
+
+impl Circle {
+    pub fn at(x: u32, y: u32, radius: u32) -> Circle {
+        Circle { x, y, radius }
+    }
+
+    // the radius of the largest circle that fits inside, which for a circle is the same as the smallest one around it
+    pub fn inner_radius(&self) -> f64 {
+        f64::from(self.radius)
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * f64::from(self.radius).powi(2)
+    }
+
+    fn perimeter(&self) -> f64 {
+        std::f64::consts::TAU * f64::from(self.radius)
+    }
+
+    // coordinates can't go below zero, so the box of a circle that pokes out past the top or left edge starts at zero instead
+    // its size is always the full diameter though, so comparing sizes still works
+    fn bounding_box(&self) -> Rectangle {
+        let diameter = self.radius.saturating_mul(2);
+        Rectangle::at(
+            self.x.saturating_sub(self.radius),
+            self.y.saturating_sub(self.radius),
+            diameter,
+            diameter,
+        )
+    }
+
+    fn outer_radius(&self) -> f64 {
+        f64::from(self.radius)
+    }
+
+    // the same rule as Triangle: other fits if the smallest circle around it fits in the largest circle inside self
+    // for a circle that's exact rather than conservative, because nothing else fits inside it
+    fn can_hold(&self, other: &dyn Shape) -> bool {
+        other.outer_radius() <= self.inner_radius()
+    }
+}
+
+// a triangle given by its three corners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Triangle {
+    pub a: (u32, u32),
+    pub b: (u32, u32),
+    pub c: (u32, u32),
+}
+
+impl Triangle {
+    pub fn new(a: (u32, u32), b: (u32, u32), c: (u32, u32)) -> Triangle {
+        Triangle { a, b, c }
+    }
+
+    // the lengths of the three sides, longest last
+    fn sides(&self) -> [f64; 3] {
+        let distance = |(x1, y1): (u32, u32), (x2, y2): (u32, u32)| {
+            (f64::from(x1) - f64::from(x2)).hypot(f64::from(y1) - f64::from(y2))
+        };
+        let mut sides = [
+            distance(self.a, self.b),
+            distance(self.b, self.c),
+            distance(self.c, self.a),
+        ];
+        sides.sort_by(f64::total_cmp);
+        sides
+    }
+
+    // the radius of the largest circle that fits inside the triangle
+    pub fn inner_radius(&self) -> f64 {
+        let semi_perimeter = self.perimeter() / 2.0;
+        if semi_perimeter == 0.0 {
+            return 0.0;
+        }
+        self.area() / semi_perimeter
+    }
+}
+
+impl Shape for Triangle {
+    // the shoelace formula
+    fn area(&self) -> f64 {
+        let [(x1, y1), (x2, y2), (x3, y3)] =
+            [self.a, self.b, self.c].map(|(x, y)| (f64::from(x), f64::from(y)));
+        ((x1 * (y2 - y3) + x2 * (y3 - y1) + x3 * (y1 - y2)) / 2.0).abs()
+    }
+
+    fn perimeter(&self) -> f64 {
+        self.sides().iter().sum()
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        let xs = [self.a.0, self.b.0, self.c.0];
+        let ys = [self.a.1, self.b.1, self.c.1];
+        let (left, top) = (*xs.iter().min().unwrap(), *ys.iter().min().unwrap());
+        let (right, bottom) = (*xs.iter().max().unwrap(), *ys.iter().max().unwrap());
+        Rectangle::at(left, top, right - left, bottom - top)
+    }
+
+    // for a triangle with an obtuse or right angle the smallest enclosing circle has the longest side as its diameter,
+    // otherwise it's the circle through all three corners
+    fn outer_radius(&self) -> f64 {
+        let [a, b, c] = self.sides();
+        let area = self.area();
+        if a * a + b * b <= c * c || area == 0.0 {
+            c / 2.0
+        } else {
+            a * b * c / (4.0 * area)
+        }
+    }
+
+    // conservative: other fits if the smallest circle around it fits in the largest circle inside the triangle
+    fn can_hold(&self, other: &dyn Shape) -> bool {
+        other.outer_radius() <= self.inner_radius()
+    }
+}
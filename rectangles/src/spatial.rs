@@ -0,0 +1,249 @@
+// a uniform grid over positioned rectangles, for answering "what is here?" without checking every rectangle
+// space is cut into square cells and every rectangle is listed in each cell it covers, so a query only has to look at
+// the rectangles in the cells it touches
+// this works best when the cell size is about the size of a typical rectangle: much smaller and big rectangles are
+// listed in lots of cells, much bigger and every cell holds lots of rectangles
+// a rectangle that would be listed in more than MAX_CELLS cells is kept on a separate list that every query checks
+// instead, and a window covering more cells than there are rectangles is answered by checking every rectangle, so
+// neither inserting nor querying ever walks through billions of cells
+// next to the grid, the rectangles are also kept sorted by size, for can_hold queries that don't care where anything is
+use crate::Rectangle;
+use std::collections::{BTreeMap, HashMap};
+
+// identifies a rectangle in a GridIndex
+// ids are handed out in order by insert() and never reused, even after the rectangle is removed
+pub type Id = usize;
+
+// the most cells a single rectangle gets listed in
+const MAX_CELLS: u64 = 1024;
+
+// the first and last column and row of cells a rectangle covers
+type CellRange = ((u32, u32), (u32, u32));
+
+// a rectangle's shorter side, then its longer side
+// one rectangle can hold another, turned if need be, exactly when both of these are at least as big as the other's
+fn sides(rectangle: &Rectangle) -> (u32, u32) {
+    (
+        rectangle.width.min(rectangle.height),
+        rectangle.width.max(rectangle.height),
+    )
+}
+
+#[derive(Debug, Clone)]
+pub struct GridIndex {
+    cell_size: u32,
+    cells: HashMap<(u32, u32), Vec<Id>>,
+    // the rectangles that cover too many cells to be listed in each of them, in id order
+    large: Vec<Id>,
+    // the rectangles grouped by their shorter side, each group sorted by the longer side and then the id
+    sizes: BTreeMap<u32, Vec<(u32, Id)>>,
+    // indexed by id; removed rectangles leave a None behind so the other ids stay valid
+    rectangles: Vec<Option<Rectangle>>,
+    len: usize,
+}
+
+impl GridIndex {
+    // an empty index with the given cell size in pixels; a cell size of 0 is treated as 1
+    pub fn new(cell_size: u32) -> GridIndex {
+        GridIndex {
+            cell_size: cell_size.max(1),
+            cells: HashMap::new(),
+            large: Vec::new(),
+            sizes: BTreeMap::new(),
+            rectangles: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get(&self, id: Id) -> Option<&Rectangle> {
+        self.rectangles.get(id)?.as_ref()
+    }
+
+    // every rectangle in the index with its id, in id order
+    pub fn iter(&self) -> impl Iterator<Item = (Id, &Rectangle)> {
+        self.rectangles
+            .iter()
+            .enumerate()
+            .filter_map(|(id, rectangle)| Some((id, rectangle.as_ref()?)))
+    }
+
+    pub fn insert(&mut self, rectangle: Rectangle) -> Id {
+        let id = self.rectangles.len();
+        let range = self.cell_range(&rectangle);
+        if cell_count(range) > MAX_CELLS {
+            self.large.push(id);
+        } else {
+            for cell in cells_in(range) {
+                self.cells.entry(cell).or_default().push(id);
+            }
+        }
+        let (short, long) = sides(&rectangle);
+        let group = self.sizes.entry(short).or_default();
+        // ids only go up, so a new one always comes after the others with the same longer side
+        let at = group.partition_point(|&(other_long, _)| other_long <= long);
+        group.insert(at, (long, id));
+        self.rectangles.push(Some(rectangle));
+        self.len += 1;
+        id
+    }
+
+    // take a rectangle out of the index, returning it if it was there
+    pub fn remove(&mut self, id: Id) -> Option<Rectangle> {
+        let rectangle = self.rectangles.get_mut(id)?.take()?;
+        let range = self.cell_range(&rectangle);
+        if cell_count(range) > MAX_CELLS {
+            self.large.retain(|&other| other != id);
+        } else {
+            for cell in cells_in(range) {
+                if let Some(ids) = self.cells.get_mut(&cell) {
+                    ids.retain(|&other| other != id);
+                    if ids.is_empty() {
+                        self.cells.remove(&cell);
+                    }
+                }
+            }
+        }
+        let (short, long) = sides(&rectangle);
+        if let Some(group) = self.sizes.get_mut(&short) {
+            if let Ok(at) = group.binary_search(&(long, id)) {
+                group.remove(at);
+            }
+            if group.is_empty() {
+                self.sizes.remove(&short);
+            }
+        }
+        self.len -= 1;
+        Some(rectangle)
+    }
+
+    // the ids of the rectangles that cover the point, in id order
+    pub fn query_point(&self, x: u32, y: u32) -> Vec<Id> {
+        self.matching([self.cell_of(x, y)].into_iter(), |rectangle| {
+            rectangle.contains_point(x, y)
+        })
+    }
+
+    // the ids of the rectangles that overlap the window, in id order
+    // like Rectangle::intersects, only sharing an edge doesn't count
+    pub fn query_window(&self, window: &Rectangle) -> Vec<Id> {
+        let overlaps = |rectangle: &Rectangle| rectangle.intersects(window);
+        let range = self.cell_range(window);
+        // looking in more cells than there are rectangles is slower than just checking them all
+        if cell_count(range) > self.len as u64 {
+            return self
+                .iter()
+                .filter(|(_, rectangle)| overlaps(rectangle))
+                .map(|(id, _)| id)
+                .collect();
+        }
+        self.matching(cells_in(range), overlaps)
+    }
+
+    // the ids of the rectangles that contain target where they both are now, like Rectangle::contains, in id order
+    // this is about positions: a rectangle somewhere else that is big enough to hold target, the way
+    // Rectangle::can_hold compares sizes, doesn't count; GridIndex::can_hold answers that one
+    pub fn containing(&self, target: &Rectangle) -> Vec<Id> {
+        // anything that contains target covers its top left pixel, or for an empty target at least one of the
+        // pixels touching its top left corner, so those are the only cells worth looking in
+        let (x, y) = (target.x, target.y);
+        let corners = [
+            (x, y),
+            (x.saturating_sub(1), y),
+            (x, y.saturating_sub(1)),
+            (x.saturating_sub(1), y.saturating_sub(1)),
+        ];
+        let cells = corners.map(|(x, y)| self.cell_of(x, y));
+        self.matching(cells.into_iter(), |rectangle| rectangle.contains(target))
+    }
+
+    // the ids of the rectangles big enough to hold target wherever they are, like Rectangle::can_hold, in id order
+    // only the groups with a long enough shorter side are looked at, and in each of those only the part with a long
+    // enough longer side, so rectangles that are too small are skipped without being checked
+    pub fn can_hold(&self, target: &Rectangle) -> Vec<Id> {
+        let (short, long) = sides(target);
+        let mut ids: Vec<Id> = self
+            .sizes
+            .range(short..)
+            .flat_map(|(_, group)| {
+                let start = group.partition_point(|&(other_long, _)| other_long < long);
+                group[start..].iter().map(|&(_, id)| id)
+            })
+            .collect();
+        // sorting a long list of hits takes longer than marking them off by id and reading them back in order
+        if ids.len() > self.rectangles.len() / 16 {
+            let mut hit = vec![false; self.rectangles.len()];
+            for id in ids.drain(..) {
+                hit[id] = true;
+            }
+            ids.extend(
+                hit.iter()
+                    .enumerate()
+                    .filter(|&(_, &hit)| hit)
+                    .map(|(id, _)| id),
+            );
+        } else {
+            ids.sort_unstable();
+        }
+        ids
+    }
+
+    // every id listed in the given cells or on the large list whose rectangle passes the check, without duplicates
+    fn matching<I, F>(&self, cells: I, check: F) -> Vec<Id>
+    where
+        I: Iterator<Item = (u32, u32)>,
+        F: Fn(&Rectangle) -> bool,
+    {
+        let mut ids: Vec<Id> = cells
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .chain(&self.large)
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids.retain(|&id| self.get(id).is_some_and(&check));
+        ids
+    }
+
+    fn cell_of(&self, x: u32, y: u32) -> (u32, u32) {
+        (x / self.cell_size, y / self.cell_size)
+    }
+
+    // the cells the rectangle covers
+    // an empty rectangle covers no pixels, so it's listed in the cell of its top left corner to keep it findable
+    fn cell_range(&self, rectangle: &Rectangle) -> CellRange {
+        let (left, top) = self.cell_of(rectangle.x, rectangle.y);
+        let (right, bottom) = if rectangle.width == 0 || rectangle.height == 0 {
+            (left, top)
+        } else {
+            // a rectangle can reach past u32::MAX, but no point can, so there's no need for cells past the last point
+            let (last_column, last_row) = self.cell_of(u32::MAX, u32::MAX);
+            let cell_of_edge = |edge: u64, last: u32| {
+                u32::try_from((edge - 1) / u64::from(self.cell_size))
+                    .map_or(last, |cell| cell.min(last))
+            };
+            (
+                cell_of_edge(rectangle.right(), last_column),
+                cell_of_edge(rectangle.bottom(), last_row),
+            )
+        };
+        ((left, top), (right, bottom))
+    }
+}
+
+fn cell_count(((left, top), (right, bottom)): CellRange) -> u64 {
+    // 2^32 columns by 2^32 rows is one more than a u64 can hold, and anything that big is far too many anyway
+    (u64::from(right - left) + 1).saturating_mul(u64::from(bottom - top) + 1)
+}
+
+fn cells_in(((left, top), (right, bottom)): CellRange) -> impl Iterator<Item = (u32, u32)> {
+    (top..=bottom).flat_map(move |row| (left..=right).map(move |column| (column, row)))
+}
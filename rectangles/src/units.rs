@@ -0,0 +1,202 @@
+// lengths that know what unit they're in, so a Rectangle can be measured in pixels, millimetres or inches
+// a Length<f64, Millimetres> and a Length<f64, Inches> are different types, so adding them together or asking whether
+// one rectangle can hold the other is a compile error instead of a wrong answer
+// the only way from one unit to another is convert(), which needs the dpi because how big a pixel is depends on the screen
+// or printer it ends up on
+use crate::{Coordinate, Rectangle};
+use std::cmp::Ordering;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Sub};
+
+// a unit of length, used as the second type parameter of Length
+pub trait Unit: Copy + fmt::Debug {
+    // written after a length when it's displayed, e.g. "mm"
+    const SYMBOL: &'static str;
+
+    // how many of this unit make up an inch at the given dpi
+    fn per_inch(dpi: Dpi) -> f64;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Pixels;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Millimetres;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Inches;
+
+impl Unit for Pixels {
+    const SYMBOL: &'static str = "px";
+
+    fn per_inch(dpi: Dpi) -> f64 {
+        dpi.0
+    }
+}
+
+impl Unit for Millimetres {
+    const SYMBOL: &'static str = "mm";
+
+    fn per_inch(_: Dpi) -> f64 {
+        25.4
+    }
+}
+
+impl Unit for Inches {
+    const SYMBOL: &'static str = "in";
+
+    fn per_inch(_: Dpi) -> f64 {
+        1.0
+    }
+}
+
+// dots (pixels) per inch, the exchange rate between pixels and the physical units
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct Dpi(pub f64);
+
+impl Dpi {
+    // what CSS and most desktop toolkits assume a screen has
+    pub const SCREEN: Dpi = Dpi(96.0);
+    // a common resolution for printing photos
+    pub const PRINT: Dpi = Dpi(300.0);
+}
+
+/// a number of some unit, e.g. `Length::<f64, Millimetres>::new(210.0)` is 210mm
+/// the unit only exists in the type, so a Length takes up exactly as much space as the number in it
+///
+/// lengths in different units don't mix, so neither of these compiles:
+///
+/// ```compile_fail,E0308
+/// use rectangles::{Inches, Length, Millimetres};
+///
+/// let a4_width: Length<f64, Millimetres> = Length::new(210.0);
+/// let letter_width: Length<f64, Inches> = Length::new(8.5);
+/// let both = a4_width + letter_width;
+/// ```
+///
+/// ```compile_fail,E0308
+/// use rectangles::{Inches, Length, Millimetres, Rectangle};
+///
+/// let a4: Rectangle<Length<f64, Millimetres>> = Rectangle::new(210.0, 297.0).map(Length::new);
+/// let letter: Rectangle<Length<f64, Inches>> = Rectangle::new(8.5, 11.0).map(Length::new);
+/// a4.can_hold(&letter);
+/// ```
+///
+/// converting one of them first does:
+///
+/// ```
+/// use rectangles::{Dpi, Inches, Length, Millimetres, Rectangle};
+///
+/// let a4: Rectangle<Length<f64, Millimetres>> = Rectangle::new(210.0, 297.0).map(Length::new);
+/// let letter: Rectangle<Length<f64, Inches>> = Rectangle::new(8.5, 11.0).map(Length::new);
+/// assert!(!a4.can_hold(&letter.convert(Dpi::SCREEN)));
+/// ```
+pub struct Length<T, U> {
+    pub value: T,
+    unit: PhantomData<U>,
+}
+
+impl<T, U> Length<T, U> {
+    pub fn new(value: T) -> Length<T, U> {
+        Length {
+            value,
+            unit: PhantomData,
+        }
+    }
+}
+
+impl<U: Unit> Length<f64, U> {
+    // the same length in another unit; the dpi only matters when one of the two units is pixels
+    pub fn convert<V: Unit>(self, dpi: Dpi) -> Length<f64, V> {
+        Length::new(self.value / U::per_inch(dpi) * V::per_inch(dpi))
+    }
+}
+
+impl<U: Unit> Rectangle<Length<f64, U>> {
+    // the same rectangle in another unit, position included
+    // conversions only go through f64; use map to get there from other number types first
+    pub fn convert<V: Unit>(&self, dpi: Dpi) -> Rectangle<Length<f64, V>> {
+        self.map(|length| length.convert(dpi))
+    }
+}
+
+// the standard traits are written out by hand rather than derived, because deriving them would also require the unit
+// marker to implement them, and the unit doesn't take part in any of them anyway
+
+impl<T: fmt::Debug, U: Unit> fmt::Debug for Length<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}{}", self.value, U::SYMBOL)
+    }
+}
+
+impl<T: fmt::Display, U: Unit> fmt::Display for Length<T, U> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.value.fmt(f)?;
+        f.write_str(U::SYMBOL)
+    }
+}
+
+impl<T: Clone, U> Clone for Length<T, U> {
+    fn clone(&self) -> Length<T, U> {
+        Length::new(self.value.clone())
+    }
+}
+
+impl<T: Copy, U> Copy for Length<T, U> {}
+
+impl<T: PartialEq, U> PartialEq for Length<T, U> {
+    fn eq(&self, other: &Length<T, U>) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T: PartialOrd, U> PartialOrd for Length<T, U> {
+    fn partial_cmp(&self, other: &Length<T, U>) -> Option<Ordering> {
+        self.value.partial_cmp(&other.value)
+    }
+}
+
+impl<T: Default, U> Default for Length<T, U> {
+    fn default() -> Length<T, U> {
+        Length::new(T::default())
+    }
+}
+
+// lengths can only be added to and subtracted from lengths in the same unit
+impl<T: Add<Output = T>, U> Add for Length<T, U> {
+    type Output = Length<T, U>;
+
+    fn add(self, other: Length<T, U>) -> Length<T, U> {
+        Length::new(self.value + other.value)
+    }
+}
+
+impl<T: Sub<Output = T>, U> Sub for Length<T, U> {
+    type Output = Length<T, U>;
+
+    fn sub(self, other: Length<T, U>) -> Length<T, U> {
+        Length::new(self.value - other.value)
+    }
+}
+
+// a length's edges are worked out in the wider number type, still in the same unit
+impl<T: Coordinate, U: Unit> Coordinate for Length<T, U> {
+    const ZERO: Self = Length {
+        value: T::ZERO,
+        unit: PhantomData,
+    };
+    const MAX: Self = Length {
+        value: T::MAX,
+        unit: PhantomData,
+    };
+    type Wide = Length<T::Wide, U>;
+
+    fn widen(self) -> Length<T::Wide, U> {
+        Length::new(self.value.widen())
+    }
+
+    fn narrow(wide: Length<T::Wide, U>) -> Option<Length<T, U>> {
+        T::narrow(wide.value).map(Length::new)
+    }
+}
@@ -0,0 +1,38 @@
+// the rectangles binary end to end: its CSV report can be fed back in as input
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn run(args: &[&str], input: &str) -> String {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_rectangles"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary was built for the tests");
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(input.as_bytes())
+        .expect("the binary reads all of stdin");
+    let output = child.wait_with_output().expect("the binary finishes");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout).expect("the output is text")
+}
+
+#[test]
+fn the_csv_report_reads_back_in() {
+    let report = run(
+        &["--input", "csv", "--output", "csv", "-"],
+        "30x50\n10x10+5+5\n60x2+1+1\n",
+    );
+    assert!(report.starts_with("index,x,y,width,height,area,perimeter,can_hold\n"));
+    // reading the report back describes the same rectangles, so the report of it is the same too
+    let again = run(&["--input", "csv", "--output", "csv", "-"], &report);
+    assert_eq!(again, report);
+}
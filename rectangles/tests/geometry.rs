@@ -0,0 +1,127 @@
+// where rectangles are: points on the edges, overlaps, bounding boxes, fitting one inside another and moving them
+use rectangles::Rectangle;
+
+// a u32 rectangle at the origin, for the checks that only look at sizes
+fn size(width: u32, height: u32) -> Rectangle {
+    Rectangle::new(width, height)
+}
+
+#[test]
+fn the_top_and_left_edges_are_inside_and_the_others_arent() {
+    let rect = Rectangle::at(10, 20, 5, 3);
+    assert!(rect.contains_point(10, 20));
+    assert!(rect.contains_point(14, 22));
+    assert!(rect.contains_point(10, 22));
+    assert!(rect.contains_point(14, 20));
+    // the right and bottom edges belong to whatever is next to it
+    assert!(!rect.contains_point(15, 20));
+    assert!(!rect.contains_point(10, 23));
+    assert!(!rect.contains_point(15, 23));
+    assert!(!rect.contains_point(9, 20));
+    assert!(!rect.contains_point(10, 19));
+    // an empty rectangle has no points at all
+    assert!(!Rectangle::at(3, 3, 0, 5).contains_point(3, 3));
+    // right at the end of the u32 range
+    let corner = Rectangle::at(u32::MAX - 1, u32::MAX - 1, 1, 1);
+    assert!(corner.contains_point(u32::MAX - 1, u32::MAX - 1));
+    assert!(!corner.contains_point(u32::MAX, u32::MAX));
+}
+
+#[test]
+fn overlapping_rectangles_intersect() {
+    let a = Rectangle::at(0, 0, 10, 10);
+    let b = Rectangle::at(5, 6, 10, 10);
+    assert_eq!(a.intersection(&b), Some(Rectangle::at(5, 6, 5, 4)));
+    assert_eq!(a.intersection(&b), b.intersection(&a));
+    assert!(a.intersects(&b));
+    // one inside the other overlaps by all of the smaller one
+    let inner = Rectangle::at(2, 3, 4, 4);
+    assert_eq!(a.intersection(&inner), Some(inner));
+    assert_eq!(a.intersection(&a), Some(a));
+}
+
+#[test]
+fn touching_rectangles_dont_intersect() {
+    let a = Rectangle::at(0, 0, 10, 10);
+    // sharing the right edge, the bottom edge, or only the corner
+    for touching in [
+        Rectangle::at(10, 0, 5, 10),
+        Rectangle::at(0, 10, 10, 5),
+        Rectangle::at(10, 10, 5, 5),
+    ] {
+        assert_eq!(a.intersection(&touching), None, "{touching}");
+        assert_eq!(touching.intersection(&a), None, "{touching}");
+        assert!(!a.intersects(&touching));
+    }
+}
+
+#[test]
+fn disjoint_rectangles_dont_intersect() {
+    let a = Rectangle::at(0, 0, 10, 10);
+    for apart in [
+        Rectangle::at(20, 0, 5, 5),
+        Rectangle::at(0, 20, 5, 5),
+        // overlapping columns but not rows
+        Rectangle::at(5, 11, 10, 10),
+    ] {
+        assert_eq!(a.intersection(&apart), None, "{apart}");
+        assert!(!apart.intersects(&a));
+    }
+    // an empty rectangle inside another still has no area in common with it
+    assert_eq!(a.intersection(&Rectangle::at(5, 5, 0, 0)), None);
+}
+
+#[test]
+fn the_union_covers_both() {
+    let a = Rectangle::at(0, 0, 10, 10);
+    let b = Rectangle::at(20, 5, 5, 15);
+    let union = a.union(&b);
+    assert_eq!(union, Rectangle::at(0, 0, 25, 20));
+    assert_eq!(b.union(&a), union);
+    assert!(union.contains(&a) && union.contains(&b));
+    assert_eq!(Rectangle::bounding_box(&[a, b]), Some(union));
+    assert_eq!(Rectangle::<u32>::bounding_box(&[]), None);
+}
+
+#[test]
+fn can_hold_turns_the_other_rectangle_if_it_has_to() {
+    let wide = size(30, 10);
+    let tall = size(10, 30);
+    // only fits on its side
+    assert!(wide.can_hold(&tall));
+    assert!(tall.can_hold(&wide));
+    assert!(wide.can_hold(&size(9, 29)));
+    // too big either way round
+    assert!(!wide.can_hold(&size(11, 11)));
+    assert!(!wide.can_hold(&size(31, 1)));
+    // positions don't matter, only sizes
+    let placed: Rectangle = Rectangle::at(100, 100, 30, 10);
+    assert!(placed.can_hold(&Rectangle::at(0, 0, 10, 30)));
+}
+
+#[test]
+fn can_hold_one_of_the_same_size() {
+    let rect = size(8, 5);
+    assert!(rect.can_hold(&rect));
+    assert!(rect.can_hold(&size(5, 8)));
+    assert!(size(7, 7).can_hold(&size(7, 7)));
+    assert!(!size(7, 7).can_hold(&size(8, 8)));
+    // everything can hold nothing
+    assert!(size(0, 0).can_hold(&size(0, 0)));
+}
+
+#[test]
+fn translating_moves_without_resizing() {
+    let rect = Rectangle::at(10, 20, 3, 4);
+    assert_eq!(rect.translate(5, -20), Some(Rectangle::at(15, 0, 3, 4)));
+    assert_eq!(rect.translate(0, 0), Some(rect));
+    // not past the origin, and not past u32::MAX
+    assert_eq!(rect.translate(-11, 0), None);
+    assert_eq!(rect.translate(0, -21), None);
+    assert_eq!(rect.translate(i64::from(u32::MAX) - 9, 0), None);
+    assert_eq!(
+        rect.translate(i64::from(u32::MAX) - 10, 0),
+        Some(Rectangle::at(u32::MAX, 20, 3, 4))
+    );
+    assert_eq!(rect.translate(i64::MIN, i64::MAX), None);
+}
@@ -0,0 +1,145 @@
+// the arithmetic at the edges of the u32 range: checked methods report overflow, saturating ones cap, and the u64
+// results are exact
+use proptest::prelude::*;
+use rectangles::{OverflowError, Rectangle};
+
+const MAX: u32 = u32::MAX;
+
+#[test]
+fn area_of_the_largest_rectangle_is_exact() {
+    let largest = Rectangle::new(MAX, MAX);
+    assert_eq!(largest.area_method(), 18_446_744_065_119_617_025);
+    assert_eq!(largest.area_method(), u64::from(MAX) * u64::from(MAX));
+    assert_eq!(largest.perimeter(), 4 * u64::from(MAX));
+    assert_eq!(Rectangle::new(0, MAX).area_method(), 0);
+}
+
+#[test]
+fn total_area_overflows_past_u64() {
+    let largest = Rectangle::new(MAX, MAX);
+    assert_eq!(
+        Rectangle::checked_total_area(&[largest]),
+        Some(largest.area_method())
+    );
+    // two of the largest rectangles together need more than 64 bits
+    assert_eq!(Rectangle::checked_total_area(&[largest, largest]), None);
+    assert_eq!(Rectangle::checked_total_area(&[]), Some(0));
+}
+
+#[test]
+fn checked_scale_at_the_boundary() {
+    let overflow = Err(OverflowError { operation: "scale" });
+    let half = MAX / 2;
+    // 2 * (MAX / 2) is MAX - 1, one more step is too far
+    assert_eq!(
+        Rectangle::new(half, 1).checked_scale(2),
+        Ok(Rectangle::new(MAX - 1, 2))
+    );
+    assert_eq!(Rectangle::new(half + 1, 1).checked_scale(2), overflow);
+    assert_eq!(Rectangle::new(1, half + 1).checked_scale(2), overflow);
+    assert_eq!(
+        Rectangle::new(MAX, MAX).checked_scale(1),
+        Ok(Rectangle::new(MAX, MAX))
+    );
+    assert_eq!(
+        Rectangle::new(1, 1).checked_scale(MAX),
+        Ok(Rectangle::new(MAX, MAX))
+    );
+    assert_eq!(Rectangle::new(2, 1).checked_scale(MAX), overflow);
+    assert_eq!(
+        Rectangle::new(MAX, MAX).checked_scale(0),
+        Ok(Rectangle::new(0, 0))
+    );
+    assert_eq!(
+        Rectangle::new(0, 0).checked_scale(MAX),
+        Ok(Rectangle::new(0, 0))
+    );
+}
+
+#[test]
+fn scaling_keeps_the_position() {
+    let placed = Rectangle::at(MAX, MAX, 3, 4);
+    assert_eq!(placed.checked_scale(2), Ok(Rectangle::at(MAX, MAX, 6, 8)));
+}
+
+#[test]
+fn saturating_scale_clamps() {
+    assert_eq!(
+        Rectangle::new(MAX / 2 + 1, 3).saturating_scale(2),
+        Rectangle::new(MAX, 6)
+    );
+    assert_eq!(
+        Rectangle::new(MAX, MAX).saturating_scale(MAX),
+        Rectangle::new(MAX, MAX)
+    );
+    assert_eq!(
+        Rectangle::new(MAX, 0).saturating_scale(MAX),
+        Rectangle::new(MAX, 0)
+    );
+    assert_eq!(
+        Rectangle::new(MAX, MAX).saturating_scale(0),
+        Rectangle::new(0, 0)
+    );
+}
+
+#[test]
+fn translate_stays_inside_u32() {
+    let rectangle = Rectangle::at(0, MAX, 1, 1);
+    assert_eq!(rectangle.translate(0, 0), Some(rectangle));
+    assert_eq!(rectangle.translate(-1, 0), None);
+    assert_eq!(rectangle.translate(0, 1), None);
+    assert_eq!(
+        rectangle.translate(i64::from(MAX), -i64::from(MAX)),
+        Some(Rectangle::at(MAX, 0, 1, 1))
+    );
+    // the addition itself would overflow an i64
+    assert_eq!(Rectangle::at(MAX, 0, 1, 1).translate(i64::MAX, 0), None);
+    assert_eq!(rectangle.translate(i64::MIN, 0), None);
+}
+
+#[test]
+fn union_wider_than_u32() {
+    let left = Rectangle::at(0, 0, 1, 1);
+    let right = Rectangle::at(MAX, 0, 1, 1);
+    // the bounding box is MAX + 1 wide
+    assert_eq!(
+        left.checked_union(&right),
+        Err(OverflowError { operation: "union" })
+    );
+    assert_eq!(left.union(&right), Rectangle::at(0, 0, MAX, 1));
+    // exactly MAX wide still fits
+    let almost = Rectangle::at(MAX - 1, 0, 1, 1);
+    assert_eq!(left.checked_union(&almost), Ok(Rectangle::at(0, 0, MAX, 1)));
+    assert_eq!(right.right(), u64::from(MAX) + 1);
+}
+
+// plain u32s almost always overflow when multiplied, so half the time pick from a range whose products fit
+fn side() -> impl Strategy<Value = u32> {
+    prop_oneof![0..=u32::from(u16::MAX), any::<u32>()]
+}
+
+proptest! {
+    // checked_scale only gives up when one of the sides really doesn't fit in a u32
+    #[test]
+    fn checked_scale_fails_exactly_when_a_side_overflows(width in side(), height in side(), factor in side()) {
+        let fits = |side: u32| u64::from(side) * u64::from(factor) <= u64::from(MAX);
+        let scaled = Rectangle::new(width, height).checked_scale(factor);
+        prop_assert_eq!(scaled.is_err(), !(fits(width) && fits(height)));
+        if let Ok(scaled) = scaled {
+            prop_assert_eq!(u64::from(scaled.width), u64::from(width) * u64::from(factor));
+            prop_assert_eq!(u64::from(scaled.height), u64::from(height) * u64::from(factor));
+        }
+    }
+
+    // saturating_scale agrees with the exact product wherever it fits, and is u32::MAX wherever it doesn't
+    #[test]
+    fn saturating_scale_caps_at_the_largest_side(width in side(), height in side(), factor in side()) {
+        let scaled = Rectangle::new(width, height).saturating_scale(factor);
+        let exact = |side: u32| u64::from(side) * u64::from(factor);
+        prop_assert_eq!(u64::from(scaled.width), exact(width).min(u64::from(MAX)));
+        prop_assert_eq!(u64::from(scaled.height), exact(height).min(u64::from(MAX)));
+        if let Ok(checked) = Rectangle::new(width, height).checked_scale(factor) {
+            prop_assert_eq!(checked, scaled);
+        }
+    }
+}
@@ -0,0 +1,137 @@
+// what every packing strategy promises: nothing overlaps or sticks out, turned items are turned, and nothing goes missing
+use rectangles::{pack, OverflowError, Packing, Rectangle, Strategy};
+
+const STRATEGIES: [Strategy; 3] = [Strategy::Shelf, Strategy::Guillotine, Strategy::MaxRects];
+
+// the promises that hold whatever the strategy and whatever it managed to fit
+fn check(container: &Rectangle, items: &[Rectangle], packing: &Packing) {
+    for (i, placement) in packing.placements.iter().enumerate() {
+        assert!(
+            container.contains(&placement.rect),
+            "{placement:?} sticks out of {container}"
+        );
+        let item = items[placement.item];
+        let size = (placement.rect.width, placement.rect.height);
+        if placement.rotated {
+            assert_eq!(size, (item.height, item.width), "{placement:?}");
+        } else {
+            assert_eq!(size, (item.width, item.height), "{placement:?}");
+        }
+        for other in &packing.placements[i + 1..] {
+            assert!(
+                !placement.rect.intersects(&other.rect),
+                "{placement:?} overlaps {other:?}"
+            );
+        }
+    }
+
+    // every item is either placed or reported as unplaced, exactly once
+    let mut seen: Vec<usize> = packing
+        .placements
+        .iter()
+        .map(|placement| placement.item)
+        .chain(packing.unplaced.iter().copied())
+        .collect();
+    seen.sort_unstable();
+    assert_eq!(seen, (0..items.len()).collect::<Vec<_>>());
+
+    let placed: u64 = packing
+        .placements
+        .iter()
+        .map(|placement| placement.rect.area_method())
+        .sum();
+    assert_eq!(packing.used_area, placed);
+    assert_eq!(packing.unused_area, container.area_method() - placed);
+}
+
+fn sprites() -> Vec<Rectangle> {
+    vec![
+        Rectangle::new(40, 30),
+        Rectangle::new(30, 40),
+        Rectangle::new(60, 20),
+        Rectangle::new(20, 20),
+        Rectangle::new(50, 10),
+        Rectangle::new(10, 50),
+        Rectangle::new(5, 5),
+    ]
+}
+
+#[test]
+fn nothing_overlaps_or_sticks_out() {
+    // away from the origin, so the placements have to be moved to where the container is
+    let container = Rectangle::at(7, 11, 100, 60);
+    for strategy in STRATEGIES {
+        for allow_rotation in [false, true] {
+            let packing = pack(&container, &sprites(), strategy, allow_rotation).unwrap();
+            check(&container, &sprites(), &packing);
+            if !allow_rotation {
+                assert!(packing
+                    .placements
+                    .iter()
+                    .all(|placement| !placement.rotated));
+            }
+        }
+    }
+}
+
+#[test]
+fn an_item_that_only_fits_on_its_side_is_turned() {
+    let container = Rectangle::new(50, 10);
+    let items = [Rectangle::new(10, 50)];
+    for strategy in STRATEGIES {
+        let packing = pack(&container, &items, strategy, true).unwrap();
+        check(&container, &items, &packing);
+        assert_eq!(packing.placements.len(), 1, "{strategy:?}");
+        let placement = packing.placements[0];
+        assert!(placement.rotated, "{strategy:?}");
+        assert_eq!(placement.rect, Rectangle::new(50, 10), "{strategy:?}");
+        assert_eq!(packing.unused_area, 0);
+
+        // without rotation it doesn't go in at all
+        let packing = pack(&container, &items, strategy, false).unwrap();
+        assert_eq!(packing.unplaced, vec![0], "{strategy:?}");
+        assert_eq!(packing.unused_area, container.area_method());
+    }
+}
+
+#[test]
+fn items_that_dont_fit_are_reported() {
+    let container = Rectangle::new(20, 20);
+    let items = [
+        Rectangle::new(20, 20),
+        Rectangle::new(1, 1),
+        Rectangle::new(30, 5),
+        // no space at all, so it fits even in a full container
+        Rectangle::new(0, 20),
+    ];
+    for strategy in STRATEGIES {
+        let packing = pack(&container, &items, strategy, true).unwrap();
+        check(&container, &items, &packing);
+        let mut unplaced = packing.unplaced.clone();
+        unplaced.sort_unstable();
+        assert_eq!(unplaced, vec![1, 2], "{strategy:?}");
+        assert_eq!(packing.used_area, 400);
+        assert_eq!(packing.unused_area, 0);
+    }
+}
+
+#[test]
+fn a_container_at_the_end_of_the_u32_range() {
+    let items = [Rectangle::new(1, 1), Rectangle::new(2, 1)];
+    // the far corner of this container is u32::MAX, so everything in it still has a position
+    let edge = Rectangle::at(u32::MAX - 2, 0, 3, 1);
+    for strategy in STRATEGIES {
+        let packing = pack(&edge, &items, strategy, false).unwrap();
+        check(&edge, &items, &packing);
+        assert!(packing.unplaced.is_empty());
+    }
+    // this one runs past it, so an item placed at its far end would be at u32::MAX + 1
+    let past = Rectangle::at(u32::MAX, 0, 3, 1);
+    for strategy in STRATEGIES {
+        assert_eq!(
+            pack(&past, &items, strategy, false),
+            Err(OverflowError { operation: "pack" }),
+            "{strategy:?}"
+        );
+    }
+}
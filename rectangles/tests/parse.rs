@@ -0,0 +1,182 @@
+// reading rectangles from "WxH+X+Y", CSV and JSON, and where the errors say the problem is
+use rectangles::parse::{parse_csv, parse_json};
+use rectangles::{ParseError, Rectangle};
+
+fn error(line: usize, column: usize, message: &str) -> ParseError {
+    ParseError {
+        line,
+        column,
+        message: String::from(message),
+    }
+}
+
+#[test]
+fn the_text_form_round_trips() {
+    assert_eq!("30x50".parse(), Ok(Rectangle::new(30, 50)));
+    assert_eq!("30X50+10+20".parse(), Ok(Rectangle::at(10, 20, 30, 50)));
+    assert_eq!(
+        " 30 x 50 + 10 + 20 ".parse(),
+        Ok(Rectangle::at(10, 20, 30, 50))
+    );
+    for rectangle in [Rectangle::new(30, 50), Rectangle::at(1, 0, 2, 3)] {
+        assert_eq!(rectangle.to_string().parse(), Ok(rectangle));
+    }
+    assert_eq!(Rectangle::new(30, 50).to_string(), "30x50");
+    assert_eq!(Rectangle::at(0, 7, 30, 50).to_string(), "30x50+0+7");
+}
+
+#[test]
+fn the_text_form_points_at_the_mistake() {
+    let parse = |s: &str| s.parse::<Rectangle>();
+    assert_eq!(
+        parse("30"),
+        Err(error(1, 3, "expected 'x' between the width and the height"))
+    );
+    assert_eq!(
+        parse("30x"),
+        Err(error(1, 4, "expected a number for the height"))
+    );
+    assert_eq!(
+        parse("30x50+10"),
+        Err(error(1, 9, "expected '+' between the x and y positions"))
+    );
+    assert_eq!(parse("30x50 cm"), Err(error(1, 7, "unexpected 'c'")));
+    assert_eq!(
+        parse("4294967296x1"),
+        Err(error(1, 1, "the width is too large"))
+    );
+    // an ideographic space is three bytes but one character
+    assert_eq!(
+        parse("\u{3000}30x\u{e9}"),
+        Err(error(1, 5, "expected a number for the height"))
+    );
+}
+
+#[test]
+fn csv_in_every_layout() {
+    let text = "\
+# one of each
+30x50
+10x20+1+2
+30, 50
+1,2,30,50
+
+";
+    assert_eq!(
+        parse_csv(text),
+        Ok(vec![
+            Rectangle::new(30, 50),
+            Rectangle::at(1, 2, 10, 20),
+            Rectangle::new(30, 50),
+            Rectangle::at(1, 2, 30, 50),
+        ])
+    );
+    // a header can put the columns in any order and leave the position out
+    assert_eq!(
+        parse_csv("Height,width\n50,30\n"),
+        Ok(vec![Rectangle::new(30, 50)])
+    );
+    assert_eq!(
+        parse_csv("y,height,x,width\n2,50,1,30\n"),
+        Ok(vec![Rectangle::at(1, 2, 30, 50)])
+    );
+}
+
+#[test]
+fn csv_errors_say_where() {
+    assert_eq!(
+        parse_csv("30x50\n\n1,2,3\n"),
+        Err(error(3, 1, "expected 1, 2 or 4 fields, found 3"))
+    );
+    assert_eq!(
+        parse_csv("30x50\n 12, lots\n"),
+        Err(error(
+            2,
+            6,
+            "expected a number for the height, found 'lots'"
+        ))
+    );
+    assert_eq!(
+        parse_csv("10x20+1+\n"),
+        Err(error(1, 9, "expected a number for the y position"))
+    );
+    assert_eq!(
+        parse_csv("width,depth\n"),
+        Err(error(1, 7, "unknown column 'depth'"))
+    );
+    assert_eq!(
+        parse_csv("width,height,width\n"),
+        Err(error(1, 14, "column 'width' appears twice"))
+    );
+    assert_eq!(
+        parse_csv("x,width\n"),
+        Err(error(1, 8, "the header has no height column"))
+    );
+    assert_eq!(
+        parse_csv("x,y,width,height\n1,2,3\n"),
+        Err(error(2, 6, "expected at least 4 fields"))
+    );
+}
+
+#[test]
+fn csv_columns_count_characters() {
+    // the label in the skipped can_hold column takes two bytes, but it's still only one column
+    assert_eq!(
+        parse_csv("can_hold,width,height\n\u{e9},x,3\n"),
+        Err(error(2, 3, "expected a number for the width, found 'x'"))
+    );
+}
+
+#[test]
+fn the_csv_report_can_be_read_back() {
+    let report = "\
+index,x,y,width,height,area,perimeter,can_hold
+0,0,0,30,50,1500,160,1
+1,5,5,10,10,100,40,
+";
+    assert_eq!(
+        parse_csv(report),
+        Ok(vec![Rectangle::new(30, 50), Rectangle::at(5, 5, 10, 10)])
+    );
+}
+
+#[test]
+fn json_strings_and_objects() {
+    let text = r#"[
+        "30x50",
+        "10x20+1+2",
+        {"width": 30, "height": 50},
+        {"x": 1, "y": 2, "width": 30, "height": 50}
+    ]"#;
+    assert_eq!(
+        parse_json(text),
+        Ok(vec![
+            Rectangle::new(30, 50),
+            Rectangle::at(1, 2, 10, 20),
+            Rectangle::new(30, 50),
+            Rectangle::at(1, 2, 30, 50),
+        ])
+    );
+    assert_eq!(parse_json("[]"), Ok(vec![]));
+}
+
+#[test]
+fn json_errors_say_where() {
+    assert_eq!(
+        parse_json("[\n  \"30x50\",\n  {\"width\": 30}\n]"),
+        Err(error(3, 15, "missing field `height`"))
+    );
+    assert_eq!(
+        parse_json("[\"30y50\"]"),
+        Err(error(
+            1,
+            8,
+            "expected 'x' between the width and the height at character 3 of \"30y50\""
+        ))
+    );
+    // serde_json counts bytes, these are characters
+    assert_eq!(
+        parse_json("[\"\u{3000}3x5\", x]"),
+        Err(error(1, 10, "expected value"))
+    );
+}
@@ -0,0 +1,110 @@
+// the renderers' output compared against saved copies, so any change to it shows up here first
+use rectangles::{render_ascii, render_svg, Rectangle, RenderOptions};
+
+fn layout() -> Vec<(&'static str, Rectangle)> {
+    vec![
+        ("door", Rectangle::at(0, 0, 4, 3)),
+        ("window", Rectangle::at(2, 1, 5, 2)),
+        ("a<b", Rectangle::at(8, 4, 2, 2)),
+    ]
+}
+
+fn highlighted() -> RenderOptions {
+    RenderOptions {
+        highlight_overlaps: true,
+        ..RenderOptions::default()
+    }
+}
+
+#[test]
+fn ascii_snapshot() {
+    assert_eq!(
+        render_ascii(&layout(), &RenderOptions::default()),
+        "\
+AAAA......
+AABBBBB...
+AABBBBB...
+..........
+........CC
+........CC
+
+A  door  4x3
+B  window  5x2+2+1
+C  a<b  2x2+8+4
+"
+    );
+}
+
+#[test]
+fn ascii_snapshot_with_overlaps() {
+    assert_eq!(
+        render_ascii(&layout(), &highlighted()),
+        "\
+AAAA......
+AA##BBB...
+AA##BBB...
+..........
+........CC
+........CC
+
+A  door  4x3
+B  window  5x2+2+1
+C  a<b  2x2+8+4
+#  overlap
+"
+    );
+}
+
+#[test]
+fn svg_snapshot() {
+    assert_eq!(
+        render_svg(&layout(), &highlighted()),
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 6" width="10" height="6">
+  <rect x="0" y="0" width="4" height="3" fill="#4e79a7" fill-opacity="0.5" stroke="#4e79a7"/>
+  <text x="2" y="2" font-family="monospace" font-size="12" dominant-baseline="hanging">door</text>
+  <rect x="2" y="1" width="5" height="2" fill="#f28e2b" fill-opacity="0.5" stroke="#f28e2b"/>
+  <text x="4" y="3" font-family="monospace" font-size="12" dominant-baseline="hanging">window</text>
+  <rect x="8" y="4" width="2" height="2" fill="#59a14f" fill-opacity="0.5" stroke="#59a14f"/>
+  <text x="10" y="6" font-family="monospace" font-size="12" dominant-baseline="hanging">a&lt;b</text>
+  <rect x="2" y="1" width="2" height="2" fill="#ff0000" fill-opacity="0.6" class="overlap"/>
+</svg>
+"##
+    );
+}
+
+#[test]
+fn nothing_to_draw() {
+    let empty: [(&str, Rectangle); 0] = [];
+    assert_eq!(render_ascii(&empty, &RenderOptions::default()), "");
+}
+
+#[test]
+fn tall_layouts_are_scaled_down_too() {
+    let options = RenderOptions {
+        max_columns: 4,
+        ..RenderOptions::default()
+    };
+    assert_eq!(
+        render_ascii(&[("tall", Rectangle::new(10, 4_000_000_000))], &options),
+        "\
+A
+A
+A
+A
+
+each character is 1000000000x1000000000 pixels
+
+A  tall  10x4000000000
+"
+    );
+}
+
+#[test]
+fn cells_past_the_end_of_u32() {
+    // the rectangle sticks out past u32::MAX, so the cells that draw it start there too
+    let items = [("edge", Rectangle::at(u32::MAX - 1, 0, 4, 1))];
+    assert_eq!(
+        render_ascii(&items, &RenderOptions::default()),
+        "AAAA\n\nA  edge  4x1+4294967294+0\n"
+    );
+}
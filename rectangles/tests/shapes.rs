@@ -0,0 +1,110 @@
+// the Shape trait for circles and triangles, and what each of them agrees to hold
+use rectangles::{Circle, Rectangle, Shape, Square, Triangle};
+use std::f64::consts::PI;
+
+fn close(actual: f64, expected: f64) -> bool {
+    (actual - expected).abs() < 1e-9
+}
+
+#[test]
+fn a_circle_measures_up() {
+    let circle = Circle::at(20, 30, 5);
+    assert!(close(circle.area(), 25.0 * PI));
+    assert!(close(circle.perimeter(), 10.0 * PI));
+    assert!(close(circle.outer_radius(), 5.0));
+    assert!(close(circle.inner_radius(), 5.0));
+    assert_eq!(circle.bounding_box(), Rectangle::at(15, 25, 10, 10));
+    // poking out past the top left corner moves the box but keeps its size
+    assert_eq!(
+        Circle::at(2, 2, 5).bounding_box(),
+        Rectangle::at(0, 0, 10, 10)
+    );
+}
+
+#[test]
+fn a_triangle_measures_up() {
+    // a 3, 4, 5 right triangle
+    let right = Triangle::new((0, 0), (4, 0), (0, 3));
+    assert!(close(right.area(), 6.0));
+    assert!(close(right.perimeter(), 12.0));
+    assert_eq!(right.bounding_box(), Rectangle::at(0, 0, 4, 3));
+    // the hypotenuse is the diameter of the smallest circle around it
+    assert!(close(right.outer_radius(), 2.5));
+    assert!(close(right.inner_radius(), 1.0));
+
+    // all the angles are acute, so the smallest circle goes through every corner
+    let acute = Triangle::new((0, 0), (6, 0), (3, 4));
+    assert!(close(acute.area(), 12.0));
+    assert!(close(acute.outer_radius(), 5.0 * 5.0 * 6.0 / (4.0 * 12.0)));
+    assert!(close(acute.inner_radius(), 1.5));
+
+    // an obtuse angle, so the longest side is the diameter again
+    assert!(close(
+        Triangle::new((0, 0), (10, 0), (5, 1)).outer_radius(),
+        5.0
+    ));
+
+    // three corners in a line make a triangle with no inside
+    let flat = Triangle::new((0, 0), (2, 2), (4, 4));
+    assert!(close(flat.area(), 0.0));
+    assert!(close(flat.inner_radius(), 0.0));
+    assert!(close(flat.outer_radius(), 32f64.sqrt() / 2.0));
+}
+
+#[test]
+fn a_circle_holds_whatever_its_circle_fits_in() {
+    let circle = Circle::at(50, 50, 5);
+    assert!(circle.can_hold(&Circle::at(0, 0, 5)));
+    assert!(!circle.can_hold(&Circle::at(0, 0, 6)));
+    // a 7x7 square's corners are just inside, an 8x8 one's aren't, even though its box fits in the circle's box
+    assert!(circle.can_hold(&Square::at(0, 0, 7)));
+    assert!(!circle.can_hold(&Square::at(0, 0, 8)));
+    assert!(circle.can_hold(&Triangle::new((0, 0), (4, 0), (0, 3))));
+    assert!(!circle.can_hold(&Triangle::new((0, 0), (12, 0), (0, 1))));
+}
+
+#[test]
+fn a_triangle_holds_whatever_fits_in_its_inner_circle() {
+    // a 30, 40, 50 right triangle has a circle of radius 10 inside it
+    let triangle = Triangle::new((0, 0), (40, 0), (0, 30));
+    assert!(close(triangle.inner_radius(), 10.0));
+    assert!(triangle.can_hold(&Circle::at(0, 0, 10)));
+    assert!(!triangle.can_hold(&Circle::at(0, 0, 11)));
+    assert!(triangle.can_hold(&Square::at(0, 0, 14)));
+    // conservative: a 20x1 strip would lie along the bottom edge, but its circle doesn't fit in the inner one
+    let strip: Rectangle = Rectangle::new(20, 1);
+    assert!(!Shape::can_hold(&triangle, &strip));
+}
+
+#[test]
+fn circles_and_triangles_use_the_same_rule() {
+    // both compare the other shape's outer radius with their own inner radius, which for a circle is its radius
+    let triangle = Triangle::new((0, 0), (40, 0), (0, 30));
+    let circle = Circle::at(100, 100, 10);
+    assert!(close(circle.inner_radius(), triangle.inner_radius()));
+    for other in [
+        Box::new(Circle::at(0, 0, 10)) as Box<dyn Shape>,
+        Box::new(Circle::at(0, 0, 11)),
+        Box::new(Square::at(0, 0, 14)),
+        Box::new(Square::at(0, 0, 15)),
+        Box::new(Triangle::new((0, 0), (12, 0), (0, 16))),
+        Box::new(Triangle::new((0, 0), (12, 0), (0, 17))),
+    ] {
+        assert_eq!(
+            circle.can_hold(other.as_ref()),
+            triangle.can_hold(other.as_ref()),
+            "{other:?}"
+        );
+    }
+}
+
+#[test]
+fn a_rectangle_holds_shapes_by_their_boxes() {
+    let rect: Rectangle = Rectangle::new(10, 10);
+    assert!(Shape::can_hold(&rect, &Circle::at(50, 50, 5)));
+    assert!(!Shape::can_hold(&rect, &Circle::at(50, 50, 6)));
+    assert!(Shape::can_hold(
+        &rect,
+        &Triangle::new((0, 0), (10, 0), (0, 10))
+    ));
+}
@@ -0,0 +1,115 @@
+// the grid index has to give exactly the answers checking every rectangle would give, whatever the rectangles look like
+use rectangles::{GridIndex, Rectangle};
+
+// a small linear congruential generator, the same as the benchmark uses, so the tests don't need rand
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self, bound: u32) -> u32 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        ((self.0 >> 33) % u64::from(bound)) as u32
+    }
+
+    fn rectangle(&mut self) -> Rectangle {
+        // mostly small rectangles on a small canvas, with the odd empty one and the odd huge one
+        match self.next(20) {
+            0 => Rectangle::at(self.next(500), self.next(500), 0, self.next(30)),
+            1 => Rectangle::at(self.next(500), self.next(500), u32::MAX, u32::MAX),
+            2 => Rectangle::at(u32::MAX - self.next(5), self.next(500), 10, 10),
+            _ => Rectangle::at(
+                self.next(500),
+                self.next(500),
+                1 + self.next(60),
+                1 + self.next(60),
+            ),
+        }
+    }
+}
+
+fn ids_where(rectangles: &[Option<Rectangle>], check: impl Fn(&Rectangle) -> bool) -> Vec<usize> {
+    rectangles
+        .iter()
+        .enumerate()
+        .filter(|(_, rectangle)| rectangle.as_ref().is_some_and(&check))
+        .map(|(id, _)| id)
+        .collect()
+}
+
+#[test]
+fn grid_matches_checking_everything() {
+    let mut rng = Lcg(7);
+    for cell_size in [1, 16, 64, 1000] {
+        let mut index = GridIndex::new(cell_size);
+        let mut naive = Vec::new();
+        for _ in 0..300 {
+            let rectangle = rng.rectangle();
+            assert_eq!(index.insert(rectangle), naive.len());
+            naive.push(Some(rectangle));
+        }
+        // take some of them out again so removal gets checked too
+        for id in (0..naive.len()).step_by(7) {
+            assert_eq!(index.remove(id), naive[id].take());
+        }
+        assert_eq!(index.len(), naive.iter().flatten().count());
+
+        let mut windows: Vec<Rectangle> = (0..200).map(|_| rng.rectangle()).collect();
+        windows.push(Rectangle::new(u32::MAX, u32::MAX));
+        windows.push(Rectangle::at(u32::MAX, u32::MAX, u32::MAX, u32::MAX));
+        for window in &windows {
+            assert_eq!(
+                index.query_window(window),
+                ids_where(&naive, |rectangle| rectangle.intersects(window)),
+                "query_window({window:?}) with cells of {cell_size}"
+            );
+            assert_eq!(
+                index.containing(window),
+                ids_where(&naive, |rectangle| rectangle.contains(window)),
+                "containing({window:?}) with cells of {cell_size}"
+            );
+            assert_eq!(
+                index.can_hold(window),
+                ids_where(&naive, |rectangle| rectangle.can_hold(window)),
+                "can_hold({window:?}) with cells of {cell_size}"
+            );
+            let (x, y) = (window.x, window.y);
+            assert_eq!(
+                index.query_point(x, y),
+                ids_where(&naive, |rectangle| rectangle.contains_point(x, y)),
+                "query_point({x}, {y}) with cells of {cell_size}"
+            );
+        }
+    }
+}
+
+#[test]
+fn containing_is_about_position_not_size() {
+    let mut index = GridIndex::new(10);
+    let big_elsewhere = index.insert(Rectangle::at(100, 100, 50, 50));
+    let around = index.insert(Rectangle::at(0, 0, 20, 20));
+    let target = Rectangle::at(5, 5, 10, 10);
+    // both are big enough to hold the target, but only one of them is where it is
+    assert!(index.get(big_elsewhere).unwrap().can_hold(&target));
+    assert_eq!(index.containing(&target), vec![around]);
+    // can_hold is the one that's about size
+    assert_eq!(index.can_hold(&target), vec![big_elsewhere, around]);
+}
+
+#[test]
+fn can_hold_turns_the_target_if_it_has_to() {
+    let mut index = GridIndex::new(10);
+    let wide = index.insert(Rectangle::at(0, 0, 30, 10));
+    let tall = index.insert(Rectangle::at(500, 500, 10, 30));
+    let small = index.insert(Rectangle::new(5, 5));
+    assert_eq!(index.can_hold(&Rectangle::new(10, 30)), vec![wide, tall]);
+    assert_eq!(index.can_hold(&Rectangle::new(29, 9)), vec![wide, tall]);
+    assert!(index.can_hold(&Rectangle::new(11, 11)).is_empty());
+    assert_eq!(
+        index.can_hold(&Rectangle::new(5, 5)),
+        vec![wide, tall, small]
+    );
+    index.remove(wide);
+    assert_eq!(index.can_hold(&Rectangle::new(30, 10)), vec![tall]);
+}
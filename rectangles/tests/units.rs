@@ -0,0 +1,152 @@
+// rectangles made of other coordinate types: the same geometry as for u32, lengths that carry their unit, and
+// converting between units at a given dpi
+use rectangles::{Dpi, Inches, Length, Millimetres, OverflowError, Pixels, Rectangle};
+
+type Mm = Length<f64, Millimetres>;
+type In = Length<f64, Inches>;
+type Px = Length<f64, Pixels>;
+
+fn close(actual: f64, expected: f64) -> bool {
+    (actual - expected).abs() < 1e-9
+}
+
+#[test]
+fn lengths_convert_between_units() {
+    let inch = In::new(1.0);
+    assert!(close(inch.convert::<Millimetres>(Dpi::SCREEN).value, 25.4));
+    assert!(close(
+        Mm::new(50.8).convert::<Inches>(Dpi::PRINT).value,
+        2.0
+    ));
+    // there and back again
+    let length = Mm::new(123.4);
+    assert!(close(
+        length
+            .convert::<Inches>(Dpi::PRINT)
+            .convert::<Millimetres>(Dpi::PRINT)
+            .value,
+        123.4
+    ));
+    // converting to the same unit changes nothing
+    assert!(close(
+        length.convert::<Millimetres>(Dpi::SCREEN).value,
+        123.4
+    ));
+}
+
+#[test]
+fn pixels_depend_on_the_dpi() {
+    let inch = In::new(1.0);
+    assert!(close(inch.convert::<Pixels>(Dpi::SCREEN).value, 96.0));
+    assert!(close(inch.convert::<Pixels>(Dpi::PRINT).value, 300.0));
+    assert!(close(inch.convert::<Pixels>(Dpi(72.0)).value, 72.0));
+    // the same 300 pixels are an inch on paper but over three on screen
+    let pixels = Px::new(300.0);
+    assert!(close(pixels.convert::<Inches>(Dpi::PRINT).value, 1.0));
+    assert!(close(pixels.convert::<Inches>(Dpi::SCREEN).value, 3.125));
+    assert!(close(
+        Px::new(96.0).convert::<Millimetres>(Dpi::SCREEN).value,
+        25.4
+    ));
+}
+
+#[test]
+fn rectangles_convert_position_and_all() {
+    let letter: Rectangle<In> = Rectangle::at(1.0, 2.0, 8.5, 11.0).map(Length::new);
+    let in_mm = letter.convert::<Millimetres>(Dpi::PRINT);
+    assert!(close(in_mm.x.value, 25.4));
+    assert!(close(in_mm.y.value, 50.8));
+    assert!(close(in_mm.width.value, 215.9));
+    assert!(close(in_mm.height.value, 279.4));
+    let in_pixels = letter.convert::<Pixels>(Dpi::PRINT);
+    assert!(close(in_pixels.width.value, 2550.0));
+    assert!(close(in_pixels.height.value, 3300.0));
+}
+
+#[test]
+fn lengths_print_their_unit() {
+    assert_eq!(Mm::new(210.0).to_string(), "210mm");
+    assert_eq!(format!("{:.1}", In::new(8.5)), "8.5in");
+    assert_eq!(format!("{:?}", Px::new(3.0)), "3.0px");
+}
+
+#[test]
+fn the_geometry_works_for_lengths() {
+    let sheet: Rectangle<Mm> = Rectangle::new(210.0, 297.0).map(Length::new);
+    let label: Rectangle<Mm> = Rectangle::at(10.0, 10.0, 50.0, 20.0).map(Length::new);
+    assert!(sheet.contains(&label));
+    assert!(sheet.contains_point(Mm::new(209.9), Mm::new(0.0)));
+    assert!(!sheet.contains_point(Mm::new(210.0), Mm::new(0.0)));
+    assert_eq!(sheet.intersection(&label), Some(label));
+    assert!(close(sheet.right().value, 210.0));
+    assert!(close(sheet.perimeter().value, 1014.0));
+    let beside: Rectangle<Mm> = Rectangle::at(210.0, 0.0, 10.0, 10.0).map(Length::new);
+    assert!(!sheet.intersects(&beside));
+    let both = sheet.union(&beside);
+    assert!(close(both.width.value, 220.0));
+    assert!(close(both.height.value, 297.0));
+}
+
+#[test]
+fn the_geometry_works_for_signed_and_float_coordinates() {
+    // signed positions can go left of and above the origin
+    let offscreen: Rectangle<i64> = Rectangle::at(-20, -10, 30, 30);
+    let screen: Rectangle<i64> = Rectangle::new(100, 100);
+    assert_eq!(
+        offscreen.intersection(&screen),
+        Some(Rectangle::at(0, 0, 10, 20))
+    );
+    assert_eq!(offscreen.union(&screen), Rectangle::at(-20, -10, 120, 110));
+    assert!(offscreen.contains_point(-20, -10));
+    assert!(!offscreen.contains_point(10, 0));
+    assert_eq!(offscreen.right(), 10);
+    assert_eq!(offscreen.area_method(), 900);
+    assert_eq!(offscreen.perimeter(), 120);
+
+    let precise: Rectangle<f64> = Rectangle::at(0.5, 0.5, 2.5, 4.0);
+    assert!(precise.can_hold(&Rectangle::square(2.5)));
+    assert!(!precise.can_hold(&Rectangle::square(2.6)));
+    assert!(close(precise.area_method(), 10.0));
+    assert_eq!(
+        precise.intersection(&Rectangle::new(1.0, 1.0)),
+        Some(Rectangle::at(0.5, 0.5, 0.5, 0.5))
+    );
+}
+
+#[test]
+fn the_edges_dont_overflow_in_other_types_either() {
+    // right at the end of the i64 range, the far edge needs an i128
+    let edge: Rectangle<i64> = Rectangle::at(i64::MAX, 0, i64::MAX, 1);
+    assert_eq!(edge.right(), i128::from(i64::MAX) * 2);
+    assert_eq!(edge.area_method(), i128::from(i64::MAX));
+    let far_left: Rectangle<i64> = Rectangle::at(i64::MIN, 0, 1, 1);
+    assert_eq!(
+        far_left.checked_union(&edge),
+        Err(OverflowError { operation: "union" })
+    );
+    assert_eq!(far_left.union(&edge).width, i64::MAX);
+
+    let big: Rectangle<f32> = Rectangle::at(f32::MAX, 0.0, f32::MAX, 1.0);
+    assert_eq!(
+        Rectangle::new(1.0, 1.0).checked_union(&big),
+        Err(OverflowError { operation: "union" })
+    );
+}
+
+#[test]
+#[should_panic(expected = "its sides can't be negative")]
+fn a_negative_width_is_rejected() {
+    let _: Rectangle<i64> = Rectangle::new(-1, 5);
+}
+
+#[test]
+#[should_panic(expected = "its sides can't be negative")]
+fn a_negative_length_is_rejected() {
+    let _: Rectangle<Mm> = Rectangle::new(210.0, -297.0).map(Length::new);
+}
+
+#[test]
+#[should_panic(expected = "its sides can't be negative")]
+fn a_nan_height_is_rejected() {
+    let _: Rectangle<f64> = Rectangle::at(0.0, 0.0, 1.0, f64::NAN);
+}
@@ -0,0 +1,613 @@
+// a small HTTP/JSON API over the restaurant, so a web front end can do what eat_at_restaurant does
+// it's only built with the server feature, and src/bin/restaurant-server.rs is what runs it
+//
+//   GET  /menu                  the dishes and their prices
+//   POST /orders                {"items": [{"dish": "breakfast", "toast": "rye"}, {"dish": "salad"}]}
+//   GET  /orders/<id>           the order, its status and what it costs
+//   POST /kitchen/cook          the kitchen cooks the next order in the queue
+//   POST /orders/<id>/serve     take a cooked order to its table
+//   POST /orders/<id>/payment   {"tip_percent": 18, "coupons": ["..."], "split": 2 or [[0], [1]]}, everything optional
+//                               a tip of at most 100%, split at most 100 ways
+//   GET  /waitlist              the parties waiting for a table
+//   POST /waitlist              {"name": "Ferris", "size": 2, "priority": "high"}, priority is optional
+//
+// every error comes back as {"error": {"code": "...", "message": "..."}} with a matching status code, and the code
+// is meant for programs to check while the message is meant for people
+// this only speaks enough HTTP/1.1 for one request per connection, it's meant to sit behind something sturdier
+use crate::billing::{BillingError, Payment, Rate, Split, MAX_DINERS};
+use crate::front_of_house::hosting::{self, HostingError, Priority, Time};
+use crate::front_of_house::serving;
+use crate::menu::{Dish, Item};
+use crate::order::{Order, OrderError, OrderId};
+use crate::storage::{Storage, StorageError};
+use crate::{back_of_house, Restaurant};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// requests bigger than this are turned away rather than read into memory
+pub const MAX_BODY: usize = 64 * 1024;
+
+// the longest the request line or any one header line can be, counting its line ending
+pub const MAX_HEADER: usize = 8 * 1024;
+
+// the most header lines a request can have
+pub const MAX_HEADERS: usize = 100;
+
+// the biggest tip a payment can add, anything more is much more likely a typo than generosity
+pub const MAX_TIP_PERCENT: u32 = 100;
+
+// how long a client gets to send its request before the connection is dropped
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+// what a client asked for, with the query string and headers already dealt with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Response {
+        Response { status: 200, body }
+    }
+
+    fn created(body: Value) -> Response {
+        Response { status: 201, body }
+    }
+}
+
+// something the client needs to hear about instead of what they asked for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiError {
+    pub status: u16,
+    // a short snake_case name that stays the same even if the message is reworded
+    pub code: &'static str,
+    pub message: String,
+}
+
+impl ApiError {
+    fn new(status: u16, code: &'static str, message: impl fmt::Display) -> ApiError {
+        ApiError {
+            status,
+            code,
+            message: message.to_string(),
+        }
+    }
+
+    fn bad_request(message: impl fmt::Display) -> ApiError {
+        ApiError::new(400, "bad_request", message)
+    }
+
+    fn not_found(path: &str) -> ApiError {
+        ApiError::new(404, "not_found", format!("there is nothing at {path}"))
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: {}", self.status, self.code, self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<ApiError> for Response {
+    fn from(err: ApiError) -> Response {
+        Response {
+            status: err.status,
+            body: json!({ "error": { "code": err.code, "message": err.message } }),
+        }
+    }
+}
+
+impl From<OrderError> for ApiError {
+    fn from(err: OrderError) -> ApiError {
+        let (status, code) = match err {
+            OrderError::Empty => (422, "empty_order"),
+            OrderError::NotOnMenu(_) => (422, "not_on_menu"),
+            OrderError::OutOfSeason { .. } => (422, "out_of_season"),
+            OrderError::OutOfStock { .. } => (422, "out_of_stock"),
+            OrderError::UnknownOrder(_) => (404, "unknown_order"),
+            OrderError::NoSuchLine { .. } => (422, "no_such_line"),
+            OrderError::WrongStatus { .. } => (409, "wrong_status"),
+        };
+        ApiError::new(status, code, err)
+    }
+}
+
+impl From<HostingError> for ApiError {
+    fn from(err: HostingError) -> ApiError {
+        let (status, code) = match err {
+            HostingError::EmptyParty => (422, "empty_party"),
+            HostingError::PartyTooLarge { .. } => (422, "party_too_large"),
+            HostingError::UnknownParty(_) => (404, "unknown_party"),
+        };
+        ApiError::new(status, code, err)
+    }
+}
+
+impl From<BillingError> for ApiError {
+    fn from(err: BillingError) -> ApiError {
+        let code = match err {
+            BillingError::Order(err) => return err.into(),
+            BillingError::UnknownCoupon(_) => "unknown_coupon",
+            BillingError::NoDiners => "no_diners",
+            BillingError::TooManyDiners(_) => "too_many_diners",
+            BillingError::NoSuchLine(_) => "no_such_line",
+            BillingError::Unassigned(_) => "unassigned_line",
+        };
+        ApiError::new(422, code, err)
+    }
+}
+
+// saving only happens once a request has changed the restaurant, so by the time this goes back the change has been
+// made and sending the same request again would make it twice
+impl From<StorageError> for ApiError {
+    fn from(err: StorageError) -> ApiError {
+        ApiError::new(
+            500,
+            "storage",
+            format!("the change was made but couldn't be saved, so don't send it again: {err}"),
+        )
+    }
+}
+
+// the restaurant the API works on, and optionally somewhere to save it after every change
+pub struct Server {
+    restaurant: Restaurant,
+    storage: Option<Box<dyn Storage + Send>>,
+    // waitlist times are minutes since the server started
+    opened: Instant,
+}
+
+impl Server {
+    pub fn new(restaurant: Restaurant) -> Server {
+        Server {
+            restaurant,
+            storage: None,
+            opened: Instant::now(),
+        }
+    }
+
+    // save the whole restaurant to storage after every request that changes it
+    pub fn with_storage<S: Storage + Send + 'static>(mut self, storage: S) -> Server {
+        self.storage = Some(Box::new(storage));
+        self
+    }
+
+    pub fn restaurant(&self) -> &Restaurant {
+        &self.restaurant
+    }
+
+    // answer one request; this never fails, errors are answered with an error response
+    pub fn handle(&mut self, request: &Request) -> Response {
+        let now = (self.opened.elapsed().as_secs() / 60) as Time;
+        let response = self.route(request, now).unwrap_or_else(Response::from);
+        if request.method == "POST" && response.status < 400 {
+            if let Some(storage) = &mut self.storage {
+                if let Err(err) = self.restaurant.save(storage.as_mut()) {
+                    return ApiError::from(err).into();
+                }
+            }
+        }
+        response
+    }
+
+    fn route(&mut self, request: &Request, now: Time) -> Result<Response, ApiError> {
+        let segments: Vec<&str> = request
+            .path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect();
+        let restaurant = &mut self.restaurant;
+        match (request.method.as_str(), segments.as_slice()) {
+            ("GET", ["menu"]) => {
+                let items: Vec<Value> = restaurant
+                    .menu()
+                    .items()
+                    .iter()
+                    .map(|item| json!({ "dish": item.dish.to_string(), "price": item.price }))
+                    .collect();
+                Ok(Response::ok(json!({ "items": items })))
+            }
+            ("POST", ["orders"]) => {
+                let body: OrderRequest = parse_body(request)?;
+                let items = body
+                    .items
+                    .into_iter()
+                    .map(|item| {
+                        let dish: Dish = item.dish.parse().map_err(|dish| {
+                            ApiError::new(
+                                422,
+                                "unknown_dish",
+                                format!("there is no dish called {dish}"),
+                            )
+                        })?;
+                        Ok(match dish {
+                            Dish::Breakfast => Item::Breakfast(
+                                restaurant.breakfast(item.toast.as_deref().unwrap_or("white")),
+                            ),
+                            Dish::Appetizer(appetizer) => Item::Appetizer(appetizer),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, ApiError>>()?;
+                let id = serving::take_order(restaurant, items)?;
+                Ok(Response::created(order_json(order(restaurant, id)?)))
+            }
+            ("GET", ["orders", id]) => {
+                let id = parse_id(id)?;
+                Ok(Response::ok(order_json(order(restaurant, id)?)))
+            }
+            ("POST", ["kitchen", "cook"]) => {
+                let cooked = back_of_house::cook_order(restaurant);
+                Ok(Response::ok(json!({ "cooked": cooked })))
+            }
+            ("POST", ["orders", id, "serve"]) => {
+                let id = parse_id(id)?;
+                serving::serve_order(restaurant, id)?;
+                Ok(Response::ok(order_json(order(restaurant, id)?)))
+            }
+            ("POST", ["orders", id, "payment"]) => {
+                let id = parse_id(id)?;
+                let body: PaymentRequest = parse_body(request)?;
+                if body.tip_percent > MAX_TIP_PERCENT {
+                    return Err(ApiError::bad_request(format!(
+                        "a {}% tip is too big, it can be at most {MAX_TIP_PERCENT}%",
+                        body.tip_percent
+                    )));
+                }
+                let tip = Rate::percent(body.tip_percent).expect("tips up to 100% are rates");
+                let split = match body.split {
+                    SplitRequest::Evenly(diners) => Split::Evenly(diners),
+                    SplitRequest::ByItem(diners) => Split::ByItem(diners),
+                };
+                // turned away here rather than by billing, since no order can make a split this big make sense
+                let diners = match &split {
+                    Split::Evenly(diners) => *diners as usize,
+                    Split::ByItem(diners) => diners.len(),
+                };
+                if diners > MAX_DINERS as usize {
+                    return Err(ApiError::new(
+                        400,
+                        "too_many_diners",
+                        BillingError::TooManyDiners(diners),
+                    ));
+                }
+                let payment = Payment {
+                    tip,
+                    coupons: body.coupons,
+                    split,
+                    ..Payment::default()
+                };
+                let bill = serving::take_payment(restaurant, id, &payment)?;
+                Ok(Response::created(
+                    json!({ "bill": bill, "receipt": bill.to_string() }),
+                ))
+            }
+            ("GET", ["waitlist"]) => Ok(Response::ok(waitlist_json(restaurant, now))),
+            ("POST", ["waitlist"]) => {
+                let body: WaitlistRequest = parse_body(request)?;
+                let party = hosting::add_to_waitlist(
+                    restaurant,
+                    &body.name,
+                    body.size,
+                    body.priority,
+                    now,
+                )?;
+                // whoever fits at a free table sits down straight away, which may be the party that just arrived
+                let seated = hosting::seat_at_table(restaurant, now).contains(&party);
+                let estimated_wait = hosting::estimated_wait(restaurant, party, now);
+                Ok(Response::created(json!({
+                    "party": party,
+                    "seated": seated,
+                    "estimated_wait": estimated_wait,
+                })))
+            }
+            (
+                _,
+                ["menu"]
+                | ["orders"]
+                | ["orders", _]
+                | ["kitchen", "cook"]
+                | ["orders", _, "serve" | "payment"]
+                | ["waitlist"],
+            ) => Err(ApiError::new(
+                405,
+                "method_not_allowed",
+                format!("{} isn't allowed on {}", request.method, request.path),
+            )),
+            _ => Err(ApiError::not_found(&request.path)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrderRequest {
+    items: Vec<ItemRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ItemRequest {
+    dish: String,
+    // only for breakfasts, which come with white toast if nobody says otherwise
+    toast: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct PaymentRequest {
+    tip_percent: u32,
+    coupons: Vec<String>,
+    split: SplitRequest,
+}
+
+// a number of diners, or a list of line indexes for each diner
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SplitRequest {
+    Evenly(u32),
+    ByItem(Vec<Vec<usize>>),
+}
+
+impl Default for SplitRequest {
+    fn default() -> SplitRequest {
+        SplitRequest::Evenly(1)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaitlistRequest {
+    name: String,
+    size: u32,
+    #[serde(default, with = "priority")]
+    priority: Priority,
+}
+
+// priorities are written in lowercase in requests, like everything else in the API
+mod priority {
+    use crate::front_of_house::hosting::Priority;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer};
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Priority, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "normal" => Ok(Priority::Normal),
+            "high" => Ok(Priority::High),
+            other => Err(D::Error::custom(format!(
+                "{other} isn't a priority, try normal or high"
+            ))),
+        }
+    }
+}
+
+fn parse_body<T: DeserializeOwned>(request: &Request) -> Result<T, ApiError> {
+    // an empty body is the same as an empty object, so a payment with all the defaults doesn't need one
+    let body = if request.body.trim().is_empty() {
+        "{}"
+    } else {
+        &request.body
+    };
+    serde_json::from_str(body).map_err(|err| ApiError::new(400, "bad_json", err))
+}
+
+fn parse_id(id: &str) -> Result<OrderId, ApiError> {
+    id.parse()
+        .map_err(|_| ApiError::bad_request(format!("{id} isn't an order number")))
+}
+
+fn order(restaurant: &Restaurant, id: OrderId) -> Result<&Order, ApiError> {
+    Ok(restaurant.order(id).ok_or(OrderError::UnknownOrder(id))?)
+}
+
+fn order_json(order: &Order) -> Value {
+    let lines: Vec<Value> = order
+        .lines()
+        .iter()
+        .map(|line| {
+            json!({
+                "item": line.item.to_string(),
+                "dish": line.item.dish().to_string(),
+                "price": line.price,
+                "comped": line.comped,
+                "remaking": line.remaking,
+            })
+        })
+        .collect();
+    json!({
+        "id": order.id(),
+        "status": order.status().to_string(),
+        "lines": lines,
+        "total": order.total(),
+    })
+}
+
+fn waitlist_json(restaurant: &Restaurant, now: Time) -> Value {
+    let waits = hosting::estimated_waits(restaurant, now);
+    let parties: Vec<Value> = restaurant
+        .waitlist()
+        .iter()
+        .zip(waits)
+        .map(|(party, (_, estimated_wait))| {
+            json!({
+                "party": party.id,
+                "name": party.name,
+                "size": party.size,
+                "priority": match party.priority {
+                    Priority::Normal => "normal",
+                    Priority::High => "high",
+                },
+                "estimated_wait": estimated_wait,
+            })
+        })
+        .collect();
+    json!({ "parties": parties })
+}
+
+// answer requests on listener until it fails, one thread per connection
+// the restaurant is shared between them behind a lock, so requests are handled one at a time
+pub fn serve(listener: TcpListener, server: Server) -> io::Result<()> {
+    let server = Arc::new(Mutex::new(server));
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let server = Arc::clone(&server);
+        thread::spawn(move || {
+            // a connection failing only affects that client
+            let _ = handle_connection(stream, &server);
+        });
+    }
+    Ok(())
+}
+
+fn handle_connection(mut stream: TcpStream, server: &Mutex<Server>) -> io::Result<()> {
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    let request = match read_request(&mut BufReader::new(stream.try_clone()?)) {
+        Ok(request) => request,
+        Err(err) => {
+            write_response(&mut stream, &err.into())?;
+            // some of the request may not have been read, and closing with it still there resets the connection, which
+            // can throw away the answer before the client gets to read it
+            stream.shutdown(Shutdown::Write)?;
+            io::copy(&mut (&stream).take(MAX_BODY as u64), &mut io::sink())?;
+            return Ok(());
+        }
+    };
+    // a request that panics gets an error instead of taking the server down with it, and if a panic did get past this
+    // and poison the lock, the restaurant is still served as it was left rather than never again
+    let mut server = server.lock().unwrap_or_else(PoisonError::into_inner);
+    let response = panic::catch_unwind(AssertUnwindSafe(|| server.handle(&request)))
+        .unwrap_or_else(|_| {
+            ApiError::new(500, "internal", "something went wrong handling the request").into()
+        });
+    write_response(&mut stream, &response)
+}
+
+// read one request, or work out which error to answer with instead
+fn read_request<R: BufRead>(reader: &mut R) -> Result<Request, ApiError> {
+    let mut line = String::new();
+    if read_line(reader, &mut line)? > MAX_HEADER {
+        return Err(ApiError::bad_request(format!(
+            "the request line can be at most {MAX_HEADER} bytes"
+        )));
+    }
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target), Some(_version)) = (parts.next(), parts.next(), parts.next())
+    else {
+        return Err(ApiError::bad_request(
+            "the request line should look like GET /menu HTTP/1.1",
+        ));
+    };
+    let method = method.to_string();
+    let path = target.split('?').next().unwrap_or_default().to_string();
+
+    let mut length = 0;
+    let mut headers = 0;
+    loop {
+        let mut header = String::new();
+        match read_line(reader, &mut header)? {
+            0 => {
+                return Err(ApiError::bad_request(
+                    "the connection closed in the middle of the headers",
+                ))
+            }
+            read if read > MAX_HEADER => {
+                return Err(ApiError::new(
+                    431,
+                    "headers_too_large",
+                    format!("a header can be at most {MAX_HEADER} bytes"),
+                ))
+            }
+            _ => {}
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        headers += 1;
+        if headers > MAX_HEADERS {
+            return Err(ApiError::new(
+                431,
+                "headers_too_large",
+                format!("a request can have at most {MAX_HEADERS} headers"),
+            ));
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(ApiError::bad_request(format!("{header} isn't a header")));
+        };
+        if name.trim().eq_ignore_ascii_case("content-length") {
+            length = value
+                .trim()
+                .parse()
+                .map_err(|_| ApiError::bad_request("Content-Length should be a number"))?;
+        }
+    }
+    if length > MAX_BODY {
+        return Err(ApiError::new(
+            413,
+            "too_large",
+            format!("request bodies can be at most {MAX_BODY} bytes"),
+        ));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(io_error)?;
+    let body =
+        String::from_utf8(body).map_err(|_| ApiError::bad_request("the body isn't UTF-8"))?;
+    Ok(Request { method, path, body })
+}
+
+// read one line, but no more than one byte past MAX_HEADER of it, so a line that never ends can't use up the memory
+// a result over MAX_HEADER means the line was too long
+fn read_line<R: BufRead>(reader: &mut R, line: &mut String) -> Result<usize, ApiError> {
+    reader
+        .take(MAX_HEADER as u64 + 1)
+        .read_line(line)
+        .map_err(io_error)
+}
+
+fn io_error(err: io::Error) -> ApiError {
+    ApiError::bad_request(format!("couldn't read the request: {err}"))
+}
+
+fn write_response<W: Write>(writer: &mut W, response: &Response) -> io::Result<()> {
+    let body = response.body.to_string();
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        response.status,
+        reason(response.status),
+        body.len()
+    )?;
+    writer.flush()
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        422 => "Unprocessable Entity",
+        _ => "Internal Server Error",
+    }
+}
@@ -0,0 +1,193 @@
+// the kitchen: it takes orders off the queue in the order they were placed and cooks them
+// this used to be an inline module in lib.rs, it moved to its own file once it had real work to do
+use crate::corrections::{AuditEntry, AuditEvent, Complaint};
+use crate::menu::Item;
+use crate::order::{OrderError, OrderId, Status};
+use crate::seasons::{Season, SeasonalMenu};
+use crate::Restaurant;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// deal with a customer sending food back: write down what was wrong, comp it if asked to, and have the kitchen make the
+// lines that were wrong again, ahead of every order that hasn't had a complaint
+// the order has to have been served, and nothing is changed if any of the lines in the complaint don't exist, or if
+// one of them needs an ingredient that has been 86'd
+// a line listed more than once is only complained about, comped and remade once
+pub fn fix_incorrect_order(
+    restaurant: &mut Restaurant,
+    complaint: Complaint,
+) -> Result<(), OrderError> {
+    let Complaint {
+        order: id,
+        lines,
+        problem,
+        comp,
+    } = complaint;
+    let order = restaurant.order(id).ok_or(OrderError::UnknownOrder(id))?;
+    let lines = if lines.is_empty() {
+        (0..order.lines().len()).collect()
+    } else {
+        let mut lines = lines;
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    };
+    // making a line again takes the same ingredients as ordering it, so it has to wait its turn for them too
+    if order.status() == Status::Served {
+        let items = lines
+            .iter()
+            .map(|&line| order.line(line).map(|line| &line.item))
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some((item, ingredient)) = restaurant
+            .inventory
+            .shortage(restaurant.queued_items(), items)
+        {
+            return Err(OrderError::OutOfStock {
+                dish: item.dish(),
+                ingredient,
+            });
+        }
+    }
+    let order = restaurant.order_mut(id)?;
+    order.remake(&lines)?;
+
+    let mut events = vec![AuditEvent::Complaint {
+        lines: lines.clone(),
+        problem,
+    }];
+    if comp {
+        for &line in &lines {
+            order.comp(line)?;
+            let amount = order.line(line)?.price;
+            events.push(AuditEvent::Comped { line, amount });
+        }
+    }
+    events.push(AuditEvent::Requeued);
+    restaurant.remakes.push_back(id);
+    restaurant.audit.extend(
+        events
+            .into_iter()
+            .map(|event| AuditEntry { order: id, event }),
+    );
+    Ok(())
+}
+
+// cook the order that has been waiting longest and hand it over to be served, using up its ingredients
+// orders being made again after a complaint go first, in the order the complaints came in
+// returns which order that was, or None if there was nothing waiting
+pub fn cook_order(restaurant: &mut Restaurant) -> Option<OrderId> {
+    let (id, remake) = match restaurant.remakes.pop_front() {
+        Some(id) => (id, true),
+        None => (restaurant.kitchen.pop_front()?, false),
+    };
+    restaurant
+        .order_mut(id)
+        .and_then(|order| order.advance(Status::Cooking))
+        .expect("only placed orders are in the kitchen queue");
+    // a remake only uses up what goes into the lines that were sent back
+    let order = restaurant.orders.get_mut(&id).expect("queued orders exist");
+    restaurant.inventory.use_for(order);
+    order.remade();
+    super::deliver_order(restaurant, id); // super goes up one level in the module tree
+                                          // this is useful when the calling code is unlikely to be separated from its parent module, but the parent module may be moved in the module tree
+    if remake {
+        restaurant.audit.push(AuditEntry {
+            order: id,
+            event: AuditEvent::Remade,
+        });
+    }
+    Some(id)
+}
+
+// whether the kitchen can make an item right now
+// a dish can be out of season, and so can a breakfast, if it was put together for another season's fruit
+pub fn check_in_season(restaurant: &Restaurant, item: &Item) -> Result<(), OrderError> {
+    let season = restaurant.season();
+    let in_season = match item {
+        Item::Breakfast(breakfast) => breakfast.season == season,
+        Item::Appetizer(_) => true,
+    };
+    if in_season && restaurant.seasonal_menu().is_available(item.dish(), season) {
+        Ok(())
+    } else {
+        Err(OrderError::OutOfSeason {
+            dish: item.dish(),
+            season,
+        })
+    }
+}
+
+// structs and enums can also be made public
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breakfast {
+    pub toast: String,      // this field will be public
+    seasonal_fruit: String, // this one will be private
+    // this is emulating the idea that the customer can pick the toast, but the fruit is seasonal and the restaurant decides what it is
+    season: Season, // the kitchen checks this to make sure the fruit is actually in season
+}
+
+impl Breakfast {
+    // we need to implement a public constructor, otherwise we would not be able to create a Breakfast outside of the module since seasonal_fruit is private
+    // there's one for each season, using the fruit from the standard seasonal menu
+    pub fn spring(toast: &str) -> Breakfast {
+        Breakfast::in_season(toast, Season::Spring, SeasonalMenu::standard())
+    }
+
+    pub fn summer(toast: &str) -> Breakfast {
+        Breakfast::in_season(toast, Season::Summer, SeasonalMenu::standard())
+    }
+
+    pub fn autumn(toast: &str) -> Breakfast {
+        Breakfast::in_season(toast, Season::Autumn, SeasonalMenu::standard())
+    }
+
+    pub fn winter(toast: &str) -> Breakfast {
+        Breakfast::in_season(toast, Season::Winter, SeasonalMenu::standard())
+    }
+
+    // a breakfast with the fruit a particular seasonal menu picks for the season
+    pub fn in_season(toast: &str, season: Season, seasons: &SeasonalMenu) -> Breakfast {
+        Breakfast {
+            toast: String::from(toast),
+            seasonal_fruit: String::from(seasons.fruit(season)), // this is a private field, but it can be accessed from inside the module
+            // the "chef" still gets to decide what the fruit is, but the customer can't pick it
+            season,
+        }
+    }
+
+    // the customer can look at the fruit, they just can't change it, since this hands out a &str rather than the field itself
+    pub fn seasonal_fruit(&self) -> &str {
+        &self.seasonal_fruit
+    }
+
+    pub fn season(&self) -> Season {
+        self.season
+    }
+}
+
+// the customer gets to see the fruit on their ticket, they just don't get to choose it
+impl fmt::Display for Breakfast {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "breakfast with {} toast and {}",
+            self.toast, self.seasonal_fruit
+        )
+    }
+}
+
+// making an enum public makes all of its variants public as well
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Appetizer {
+    Soup,
+    Salad,
+}
+
+impl fmt::Display for Appetizer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Appetizer::Soup => "soup",
+            Appetizer::Salad => "salad",
+        })
+    }
+}
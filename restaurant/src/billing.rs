@@ -0,0 +1,423 @@
+// working out what a served order costs, and who pays what
+// everything is done in whole cents, and every place a fraction of a cent could come up is rounded on purpose:
+// - comped items come off the subtotal first, then the server's discounts, then any coupons, each one taking its share of
+//   what's left; together they can't take the subtotal below zero
+// - tax is charged on the subtotal after discounts, and rounded once for the whole bill using Billing::rounding
+// - the tip is a share of the subtotal before discounts and tax, so a coupon doesn't shrink the server's tip, and it's
+//   rounded the same way
+// - when a check is split, each part of the bill is shared out separately in proportion to what each diner had
+//   (not counting their comped items, except for the tip),
+//   and the cents that don't divide evenly go to the diners with the biggest leftover fractions, earliest diner first,
+//   so the shares always add up to exactly the total
+use crate::menu::{dollars, Cents};
+use crate::order::{Order, OrderError, OrderId, OrderLine};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// a percentage stored in hundredths of a percent, so 8.25% tax is exact
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub struct Rate {
+    basis_points: u32,
+}
+
+impl Rate {
+    pub const ZERO: Rate = Rate { basis_points: 0 };
+
+    // None for a percentage too big to be stored in basis points, which is anything over 42,949,672%
+    pub fn percent(percent: u32) -> Option<Rate> {
+        Some(Rate {
+            basis_points: percent.checked_mul(100)?,
+        })
+    }
+
+    // hundredths of a percent, e.g. Rate::basis_points(825) is 8.25%
+    pub fn basis_points(basis_points: u32) -> Rate {
+        Rate { basis_points }
+    }
+
+    pub fn as_basis_points(self) -> u32 {
+        self.basis_points
+    }
+
+    // this rate of an amount, rounded to a whole cent
+    // a rate over 100% of a huge amount can come to more than Cents can hold, in which case it stops at Cents::MAX
+    pub fn of(self, cents: Cents, rounding: Rounding) -> Cents {
+        let exact = u128::from(cents) * u128::from(self.basis_points);
+        Cents::try_from(rounding.divide(exact, 10_000)).unwrap_or(Cents::MAX)
+    }
+}
+
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let whole = self.basis_points / 100;
+        match self.basis_points % 100 {
+            0 => write!(f, "{whole}%"),
+            fraction if fraction % 10 == 0 => write!(f, "{whole}.{}%", fraction / 10),
+            fraction => write!(f, "{whole}.{fraction:02}%"),
+        }
+    }
+}
+
+// what to do with a fraction of a cent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Rounding {
+    // half a cent or more rounds up, which is what most tills do
+    #[default]
+    HalfUp,
+    // exactly half a cent rounds to the even cent, so over lots of bills the roundings cancel out
+    HalfEven,
+    // always in the customer's favour
+    Down,
+    Up,
+}
+
+impl Rounding {
+    fn divide(self, numerator: u128, denominator: u128) -> u128 {
+        let (quotient, remainder) = (numerator / denominator, numerator % denominator);
+        let round_up = match self {
+            Rounding::HalfUp => remainder * 2 >= denominator,
+            Rounding::HalfEven => {
+                remainder * 2 > denominator || (remainder * 2 == denominator && quotient % 2 == 1)
+            }
+            Rounding::Down => false,
+            Rounding::Up => remainder > 0,
+        };
+        quotient + u128::from(round_up)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Discount {
+    Percent(Rate),
+    Amount(Cents),
+}
+
+impl fmt::Display for Discount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Discount::Percent(rate) => write!(f, "{rate} off"),
+            Discount::Amount(cents) => write!(f, "{} off", dollars(*cents)),
+        }
+    }
+}
+
+// how the restaurant charges, the same for every order
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Billing {
+    pub tax: Rate,
+    pub rounding: Rounding,
+    // coupon codes customers can hand over, and what each one takes off
+    pub coupons: BTreeMap<String, Discount>,
+}
+
+// the most people one check can be split between
+pub const MAX_DINERS: u32 = 100;
+
+// how a check is divided between the people at the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Split {
+    // everything shared equally between this many diners
+    Evenly(u32),
+    // one list of order line indexes per diner; every line has to be on at least one list,
+    // and a line on several lists, like a shared appetizer, is split between those diners
+    ByItem(Vec<Vec<usize>>),
+}
+
+impl Default for Split {
+    // one person pays for everything
+    fn default() -> Split {
+        Split::Evenly(1)
+    }
+}
+
+// the choices the table makes when paying
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Payment {
+    pub tip: Rate,
+    // discounts the server gives, e.g. to make up for a mistake
+    pub discounts: Vec<Discount>,
+    pub coupons: Vec<String>,
+    pub split: Split,
+}
+
+// what one diner pays, broken down the same way as the whole bill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct Share {
+    pub items: Cents,
+    pub discount: Cents,
+    pub tax: Cents,
+    pub tip: Cents,
+    pub total: Cents,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Bill {
+    pub order: OrderId,
+    // what each item was called on the order and what it cost
+    pub lines: Vec<(String, Cents)>,
+    pub subtotal: Cents,
+    // a description of each discount and how much it actually took off
+    pub discounts: Vec<(String, Cents)>,
+    pub tax_rate: Rate,
+    pub tax: Cents,
+    pub tip_rate: Rate,
+    pub tip: Cents,
+    pub total: Cents,
+    // one per diner, adding up to the total
+    pub shares: Vec<Share>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BillingError {
+    Order(OrderError),
+    UnknownCoupon(String),
+    // a split between nobody
+    NoDiners,
+    // a split between more than MAX_DINERS people
+    TooManyDiners(usize),
+    // a line index past the end of the order
+    NoSuchLine(usize),
+    // a line nobody is paying for
+    Unassigned(usize),
+}
+
+impl fmt::Display for BillingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BillingError::Order(err) => err.fmt(f),
+            BillingError::UnknownCoupon(code) => write!(f, "{code} is not a coupon we take"),
+            BillingError::NoDiners => {
+                f.write_str("a check has to be split between at least one diner")
+            }
+            BillingError::TooManyDiners(diners) => write!(
+                f,
+                "a check can be split between at most {MAX_DINERS} diners, not {diners}"
+            ),
+            BillingError::NoSuchLine(line) => write!(f, "the order has no line {line}"),
+            BillingError::Unassigned(line) => write!(f, "nobody is paying for line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for BillingError {}
+
+impl From<OrderError> for BillingError {
+    fn from(err: OrderError) -> BillingError {
+        BillingError::Order(err)
+    }
+}
+
+// work out the bill for an order without charging anyone, e.g. to show the table before they pay
+pub fn bill(order: &Order, billing: &Billing, payment: &Payment) -> Result<Bill, BillingError> {
+    let lines: Vec<(String, Cents)> = order
+        .lines()
+        .iter()
+        .map(|line| (line.item.to_string(), line.price))
+        .collect();
+    let subtotal = order.total();
+
+    let mut offered: Vec<(String, &Discount)> = payment
+        .discounts
+        .iter()
+        .map(|discount| (format!("Discount ({discount})"), discount))
+        .collect();
+    for code in &payment.coupons {
+        let discount = billing
+            .coupons
+            .get(code)
+            .ok_or_else(|| BillingError::UnknownCoupon(code.clone()))?;
+        offered.push((format!("Coupon {code} ({discount})"), discount));
+    }
+    // comped items come off before anything else, at their full price
+    let mut discounts: Vec<(String, Cents)> = order
+        .lines()
+        .iter()
+        .filter(|line| line.comped)
+        .map(|line| (format!("Comp ({})", line.item), line.price))
+        .collect();
+    let comped: Cents = discounts.iter().map(|(_, amount)| amount).sum();
+    let mut remaining = subtotal - comped;
+    for (label, discount) in offered {
+        let amount = match discount {
+            Discount::Percent(rate) => rate.of(remaining, billing.rounding),
+            Discount::Amount(cents) => *cents,
+        }
+        .min(remaining);
+        remaining -= amount;
+        discounts.push((label, amount));
+    }
+    let discount = subtotal - comped - remaining;
+
+    let tax = billing.tax.of(remaining, billing.rounding);
+    let tip = payment.tip.of(subtotal, billing.rounding);
+    let total = remaining + tax + tip;
+
+    // each diner's comps are worked out from their own items, so only the people who had a comped dish get it for free
+    let items = diner_items(order, &payment.split, |_| true)?;
+    let comps = diner_items(order, &payment.split, |line| line.comped)?;
+    let charged: Vec<Cents> = items
+        .iter()
+        .zip(&comps)
+        .map(|(items, comps)| items - comps)
+        .collect();
+    let discount_shares = allocate(discount, &charged);
+    let tax_shares = allocate(tax, &charged);
+    let tip_shares = allocate(tip, &items);
+    let shares = (0..items.len())
+        .map(|diner| {
+            let items = items[diner];
+            let discount = comps[diner] + discount_shares[diner];
+            let (tax, tip) = (tax_shares[diner], tip_shares[diner]);
+            Share {
+                items,
+                discount,
+                tax,
+                tip,
+                total: items - discount + tax + tip,
+            }
+        })
+        .collect();
+
+    Ok(Bill {
+        order: order.id(),
+        lines,
+        subtotal,
+        discounts,
+        tax_rate: billing.tax,
+        tax,
+        tip_rate: payment.tip,
+        tip,
+        total,
+        shares,
+    })
+}
+
+// what each diner's items come to before discounts, tax and tip
+// only the lines that pass the filter are counted, which is how comped items are shared out the same way
+fn diner_items<F: Fn(&OrderLine) -> bool>(
+    order: &Order,
+    split: &Split,
+    filter: F,
+) -> Result<Vec<Cents>, BillingError> {
+    let lines = order.lines();
+    // checked before anything is allocated for each diner
+    let count = match split {
+        Split::Evenly(diners) => *diners as usize,
+        Split::ByItem(diners) => diners.len(),
+    };
+    if count > MAX_DINERS as usize {
+        return Err(BillingError::TooManyDiners(count));
+    }
+    let diners = match split {
+        Split::Evenly(0) => return Err(BillingError::NoDiners),
+        Split::Evenly(diners) => {
+            let total = lines
+                .iter()
+                .filter(|line| filter(line))
+                .map(|line| line.price)
+                .sum();
+            return Ok(allocate(total, &vec![1; *diners as usize]));
+        }
+        Split::ByItem(diners) if diners.is_empty() => return Err(BillingError::NoDiners),
+        Split::ByItem(diners) => diners,
+    };
+    let mut sharing = vec![Vec::new(); lines.len()];
+    for (diner, picked) in diners.iter().enumerate() {
+        for &line in picked {
+            let diners = sharing
+                .get_mut(line)
+                .ok_or(BillingError::NoSuchLine(line))?;
+            // listing the same line twice for one diner doesn't make them pay for it twice
+            if !diners.contains(&diner) {
+                diners.push(diner);
+            }
+        }
+    }
+    let mut items = vec![0; diners.len()];
+    for (line, diners) in sharing.iter().enumerate() {
+        if diners.is_empty() {
+            return Err(BillingError::Unassigned(line));
+        }
+        if !filter(&lines[line]) {
+            continue;
+        }
+        for (&diner, share) in diners
+            .iter()
+            .zip(allocate(lines[line].price, &vec![1; diners.len()]))
+        {
+            items[diner] += share;
+        }
+    }
+    Ok(items)
+}
+
+// share out an amount in proportion to the weights, by the largest remainder method
+// everyone first gets their exact share rounded down, then the cents left over go one each to whoever lost the most
+// to rounding, with ties going to whoever comes first; if every weight is zero, everyone weighs the same
+fn allocate(amount: Cents, weights: &[Cents]) -> Vec<Cents> {
+    let total_weight: u128 = weights.iter().map(|&weight| u128::from(weight)).sum();
+    if total_weight == 0 {
+        return allocate(amount, &vec![1; weights.len()]);
+    }
+    let exact: Vec<u128> = weights
+        .iter()
+        .map(|&weight| u128::from(amount) * u128::from(weight))
+        .collect();
+    let mut shares: Vec<Cents> = exact
+        .iter()
+        .map(|&exact| (exact / total_weight) as Cents)
+        .collect();
+    let mut left_over = amount - shares.iter().sum::<Cents>();
+    let mut by_remainder: Vec<usize> = (0..weights.len()).collect();
+    // sort_by_key is stable, so equal remainders stay in diner order
+    by_remainder.sort_by_key(|&diner| std::cmp::Reverse(exact[diner] % total_weight));
+    for diner in by_remainder {
+        if left_over == 0 {
+            break;
+        }
+        shares[diner] += 1;
+        left_over -= 1;
+    }
+    shares
+}
+
+// the bill as a plain text receipt, with the amounts lined up on the right
+impl fmt::Display for Bill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut rows = Vec::new();
+        for (item, price) in &self.lines {
+            rows.push((format!("  {item}"), dollars(*price)));
+        }
+        rows.push(("Subtotal".to_string(), dollars(self.subtotal)));
+        for (label, amount) in &self.discounts {
+            rows.push((label.clone(), format!("-{}", dollars(*amount))));
+        }
+        if self.tax_rate != Rate::ZERO {
+            rows.push((format!("Tax ({})", self.tax_rate), dollars(self.tax)));
+        }
+        if self.tip_rate != Rate::ZERO {
+            rows.push((format!("Tip ({})", self.tip_rate), dollars(self.tip)));
+        }
+        rows.push(("Total".to_string(), dollars(self.total)));
+        if self.shares.len() > 1 {
+            for (diner, share) in self.shares.iter().enumerate() {
+                rows.push((format!("  Diner {}", diner + 1), dollars(share.total)));
+            }
+        }
+
+        let label_width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0) + 2;
+        let amount_width = rows
+            .iter()
+            .map(|(_, amount)| amount.len())
+            .max()
+            .unwrap_or(0);
+        writeln!(f, "Order {}", self.order)?;
+        for (label, amount) in rows {
+            writeln!(f, "{label:<label_width$}{amount:>amount_width$}")?;
+        }
+        Ok(())
+    }
+}
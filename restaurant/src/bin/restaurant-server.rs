@@ -0,0 +1,57 @@
+// serves the restaurant's HTTP/JSON API on localhost, see src/api.rs for what it answers
+// usage: restaurant-server [--port <port>] [--state <file>]
+// with --state, everything is saved to that JSON lines file after every change and picked up again on the next start
+use restaurant::api::{self, Server};
+use restaurant::{JsonLinesStorage, Restaurant};
+use std::env;
+use std::net::{Ipv4Addr, TcpListener};
+use std::process;
+
+const DEFAULT_PORT: u16 = 8080;
+
+fn main() {
+    let mut port = DEFAULT_PORT;
+    let mut state = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--port", Some(value)) => {
+                port = value
+                    .parse()
+                    .unwrap_or_else(|_| fail(&format!("{value} isn't a port number")));
+            }
+            ("--state", Some(value)) => state = Some(value),
+            _ => fail("usage: restaurant-server [--port <port>] [--state <file>]"),
+        }
+    }
+
+    let mut restaurant = Restaurant::default();
+    let storage = state.map(|path| {
+        let storage = JsonLinesStorage::open(&path)
+            .unwrap_or_else(|err| fail(&format!("couldn't open {path}: {err}")));
+        restaurant
+            .restore(&storage)
+            .unwrap_or_else(|err| fail(&format!("couldn't restore from {path}: {err}")));
+        storage
+    });
+    let server = match storage {
+        Some(storage) => Server::new(restaurant).with_storage(storage),
+        None => Server::new(restaurant),
+    };
+
+    // only this machine can reach the API, anything else has to go through a proxy in front of it
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+        .unwrap_or_else(|err| fail(&format!("couldn't listen on port {port}: {err}")));
+    match listener.local_addr() {
+        Ok(addr) => println!("Serving the restaurant on http://{addr}"),
+        Err(_) => println!("Serving the restaurant on port {port}"),
+    }
+    if let Err(err) = api::serve(listener, server) {
+        fail(&err.to_string());
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {message}");
+    process::exit(1);
+}
@@ -0,0 +1,72 @@
+// plays out a simulated service and prints how it went, see src/simulation.rs
+// everything not given on the command line comes from SimConfig::default()
+use restaurant::{simulate, Season, SimConfig};
+use std::env;
+use std::process;
+use std::str::FromStr;
+
+const USAGE: &str = "\
+usage: restaurant-sim [options]
+
+options:
+  --seed <n>                 seed for the random arrivals, orders and timings (default 0)
+  --hours <n>                how long the doors are open (default 4)
+  --tables <a,b,...>         the capacity of each table (default 2,2,4,4,4,6)
+  --cooks <n>                how many orders the kitchen can cook at once (default 2)
+  --arrivals <n>             parties arriving per hour, on average, at most 60 (default 6)
+  --patience <minutes>       how long a party waits for a table before leaving (default 30)
+  --season <season>          spring, summer, autumn or winter (default summer)";
+
+fn main() {
+    let mut config = SimConfig::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let Some(value) = args.next() else {
+            fail(&format!("{arg} needs a value"));
+        };
+        match arg.as_str() {
+            "--seed" => config.seed = parse(&arg, &value),
+            "--hours" => {
+                config.open_for = parse::<u32>(&arg, &value)
+                    .checked_mul(60)
+                    .unwrap_or_else(|| fail(&format!("{value} hours is too long")))
+            }
+            "--tables" => {
+                config.tables = value
+                    .split(',')
+                    .map(|capacity| parse(&arg, capacity))
+                    .collect()
+            }
+            "--cooks" => config.cooks = parse(&arg, &value),
+            "--arrivals" => config.arrivals_per_hour = parse(&arg, &value),
+            "--patience" => config.patience = parse(&arg, &value),
+            "--season" => {
+                config.season = match value.to_ascii_lowercase().as_str() {
+                    "spring" => Season::Spring,
+                    "summer" => Season::Summer,
+                    "autumn" => Season::Autumn,
+                    "winter" => Season::Winter,
+                    _ => fail(&format!("{value} isn't a season")),
+                }
+            }
+            _ => fail(&format!("unknown option {arg}")),
+        }
+    }
+
+    match simulate(&config) {
+        Ok(report) => print!("{report}"),
+        Err(err) => fail(&err.to_string()),
+    }
+}
+
+fn parse<T: FromStr>(option: &str, value: &str) -> T {
+    value
+        .trim()
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("{value} isn't a valid value for {option}")))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {message}\n\n{USAGE}");
+    process::exit(2);
+}
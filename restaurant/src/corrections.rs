@@ -0,0 +1,162 @@
+// complaints about orders, what was done about them, and how often each dish has to be made again
+// back_of_house::fix_incorrect_order is where a complaint comes in; everything it does is written to the audit trail,
+// which is what remake_report counts from
+use crate::menu::{dollars, Cents, Dish};
+use crate::order::OrderId;
+use crate::Restaurant;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+// what was wrong with the food
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Problem {
+    // the kitchen made something other than what was ordered
+    WrongItem,
+    Undercooked,
+    Overcooked,
+    Cold,
+    Other(String),
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::WrongItem => f.write_str("wrong item"),
+            Problem::Undercooked => f.write_str("undercooked"),
+            Problem::Overcooked => f.write_str("overcooked"),
+            Problem::Cold => f.write_str("cold"),
+            Problem::Other(problem) => f.write_str(problem),
+        }
+    }
+}
+
+// a customer sending food back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Complaint {
+    pub order: OrderId,
+    // which lines of the order were wrong, by index; empty means all of them
+    pub lines: Vec<usize>,
+    pub problem: Problem,
+    // whether the restaurant pays for the lines that were wrong
+    pub comp: bool,
+}
+
+// one thing that happened while putting an order right
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuditEvent {
+    Complaint { lines: Vec<usize>, problem: Problem },
+    Comped { line: usize, amount: Cents },
+    // sent back to the kitchen, ahead of every order that hasn't had a complaint
+    Requeued,
+    // the kitchen made it again
+    Remade,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub order: OrderId,
+    pub event: AuditEvent,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "order {}: ", self.order)?;
+        match &self.event {
+            AuditEvent::Complaint { lines, problem } => {
+                let lines: Vec<String> = lines.iter().map(usize::to_string).collect();
+                write!(f, "complaint about line {} ({problem})", lines.join(", "))
+            }
+            AuditEvent::Comped { line, amount } => {
+                write!(f, "comped line {line} ({})", dollars(*amount))
+            }
+            AuditEvent::Requeued => f.write_str("sent back to the kitchen"),
+            AuditEvent::Remade => f.write_str("remade"),
+        }
+    }
+}
+
+// how often one dish had to be made again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemakeRate {
+    pub dish: Dish,
+    // how many times it has been ordered
+    pub ordered: u32,
+    // how many of those were sent back
+    pub remade: u32,
+}
+
+impl RemakeRate {
+    // the share of orders for this dish that were sent back, from 0.0 to 1.0
+    pub fn rate(&self) -> f64 {
+        if self.ordered == 0 {
+            0.0
+        } else {
+            f64::from(self.remade) / f64::from(self.ordered)
+        }
+    }
+}
+
+// remake rates for every dish that has been ordered, which prints as a table
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemakeReport {
+    pub rates: Vec<RemakeRate>,
+}
+
+impl fmt::Display for RemakeReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<12}{:>8}{:>8}{:>8}",
+            "dish", "ordered", "remade", "rate"
+        )?;
+        for rate in &self.rates {
+            writeln!(
+                f,
+                "{:<12}{:>8}{:>8}{:>7.1}%",
+                rate.dish.to_string(),
+                rate.ordered,
+                rate.remade,
+                rate.rate() * 100.0
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// how often each dish has been sent back, in dish order
+// a line sent back twice counts twice, since the kitchen made it three times
+pub fn remake_report(restaurant: &Restaurant) -> RemakeReport {
+    let mut rates: BTreeMap<Dish, RemakeRate> = BTreeMap::new();
+    for order in restaurant.orders() {
+        for line in order.lines() {
+            let dish = line.item.dish();
+            rates
+                .entry(dish)
+                .or_insert(RemakeRate {
+                    dish,
+                    ordered: 0,
+                    remade: 0,
+                })
+                .ordered += 1;
+        }
+    }
+    for entry in restaurant.audit_trail() {
+        let AuditEvent::Complaint { lines, .. } = &entry.event else {
+            continue;
+        };
+        let Some(order) = restaurant.order(entry.order) else {
+            continue;
+        };
+        for line in lines {
+            if let Ok(line) = order.line(*line) {
+                if let Some(rate) = rates.get_mut(&line.item.dish()) {
+                    rate.remade += 1;
+                }
+            }
+        }
+    }
+    RemakeReport {
+        rates: rates.into_values().collect(),
+    }
+}
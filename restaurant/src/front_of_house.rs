@@ -1,12 +1,74 @@
-// by default, modules are private to any thing except the module and its children, so we must mark this as public so it can be used in eat_at_restaurant     
+// by default, modules are private to any thing except the module and its children, so we must mark this as public so it can be used in eat_at_restaurant
 pub mod hosting; // this is implemented in src\front_of_house\hosting.rs
-                    // we could have also put it at src\front_of_house\mod.rs, but we can only use one style of module organization per crate
-                    // the only downside to this method is that it can be confusing to have mutliple files named mod.rs in the same project                
+                 // we could have also put it at src\front_of_house\mod.rs, but we can only use one style of module organization per crate
+                 // the only downside to this method is that it can be confusing to have mutliple files named mod.rs in the same project
 
-mod serving {
-    fn take_order() {}
+// serving is public now too, since taking orders and payments is how customers use the restaurant
+pub mod serving {
+    use crate::back_of_house;
+    use crate::billing::{self, Bill, BillingError, Payment};
+    use crate::menu::Item;
+    use crate::order::{Order, OrderError, OrderId, OrderLine, Status};
+    use crate::Restaurant;
 
-    fn serve_order() {}
+    // write down an order, price it from the menu and put it in the kitchen queue
+    // nothing is written down if any item isn't on the menu, the kitchen won't make it this season, or there aren't
+    // enough ingredients left for everything on the order
+    pub fn take_order(
+        restaurant: &mut Restaurant,
+        items: Vec<Item>,
+    ) -> Result<OrderId, OrderError> {
+        if items.is_empty() {
+            return Err(OrderError::Empty);
+        }
+        let lines = items
+            .into_iter()
+            .map(|item| {
+                let dish = item.dish();
+                back_of_house::check_in_season(restaurant, &item)?;
+                let price = restaurant
+                    .menu
+                    .price(dish)
+                    .ok_or(OrderError::NotOnMenu(dish))?;
+                Ok(OrderLine {
+                    item,
+                    price,
+                    comped: false,
+                    remaking: false,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some((item, ingredient)) = restaurant.inventory.shortage(
+            restaurant.queued_items(),
+            lines.iter().map(|line| &line.item),
+        ) {
+            return Err(OrderError::OutOfStock {
+                dish: item.dish(),
+                ingredient,
+            });
+        }
+        let id = restaurant.next_order_id();
+        restaurant.orders.insert(id, Order::new(id, lines));
+        restaurant.kitchen.push_back(id);
+        Ok(id)
+    }
 
-    fn take_payment() {}
+    // take a ready order out to its table
+    pub fn serve_order(restaurant: &mut Restaurant, id: OrderId) -> Result<(), OrderError> {
+        restaurant.order_mut(id)?.advance(Status::Served)
+    }
+
+    // settle the bill for a served order, returning the bill so it can be printed as a receipt
+    // the bill is also kept by the restaurant, see Restaurant::payment
+    pub fn take_payment(
+        restaurant: &mut Restaurant,
+        id: OrderId,
+        payment: &Payment,
+    ) -> Result<Bill, BillingError> {
+        let order = restaurant.order(id).ok_or(OrderError::UnknownOrder(id))?;
+        let bill = billing::bill(order, &restaurant.billing, payment)?;
+        restaurant.order_mut(id)?.advance(Status::Paid)?;
+        restaurant.payments.insert(id, bill.clone());
+        Ok(bill)
+    }
 }
@@ -1,5 +1,406 @@
-   
-pub fn add_to_waitlist() {} // since this function is used in eat_at_restaurant, it must be also marked as public
-                            // the pub from pub mod hosting is not enough, because it only makes the module public, not its contents
+// the host stand: the waitlist, the tables, and deciding who sits where
+// parties wait in order of priority and then arrival, and each one gets the smallest free table that fits them
+// when no single table is big enough, up to MAX_COMBINED free tables are pushed together instead
+// smaller parties can be seated ahead of a bigger one that doesn't fit yet, but only MAX_PASSED_OVER times, after which
+// the tables are held for the bigger party so it can't be left waiting forever
+// everything that happens is recorded as an Event, so a front desk screen can follow along with Restaurant::take_events
+use crate::Restaurant;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
-fn seat_at_table() {}
+// minutes since the restaurant opened
+pub type Time = u32;
+
+// parties are numbered from 1 in the order they arrive
+pub type PartyId = u64;
+
+// tables are numbered from 1 in the order they're added
+pub type TableId = u32;
+
+// the most tables that can be pushed together for one party
+pub const MAX_COMBINED: usize = 3;
+
+// how many parties can be seated ahead of one that was waiting in front of them before everyone has to wait for it
+pub const MAX_PASSED_OVER: u32 = 3;
+
+// how long a party is expected to stay once seated, until Restaurant::set_expected_stay says otherwise
+pub const DEFAULT_EXPECTED_STAY: Time = 45;
+
+// parties with a higher priority are seated first, whenever they arrived
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default, Serialize, Deserialize,
+)]
+pub enum Priority {
+    #[default]
+    Normal,
+    // e.g. a reservation, or a regular the host wants to look after
+    High,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Party {
+    pub id: PartyId,
+    pub name: String,
+    pub size: u32,
+    pub arrived: Time,
+    pub priority: Priority,
+    // how many parties behind this one have been seated first, see MAX_PASSED_OVER
+    #[serde(default)]
+    pub passed_over: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Table {
+    pub id: TableId,
+    pub capacity: u32,
+    // None while the table is free
+    pub occupant: Option<Occupant>,
+}
+
+// who is sitting at a table and since when
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Occupant {
+    pub party: PartyId,
+    pub since: Time,
+}
+
+// something that happened at the host stand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Waitlisted {
+        party: PartyId,
+        estimated_wait: Option<Time>,
+    },
+    // a party sat down at one table, or at several pushed together
+    Seated {
+        party: PartyId,
+        tables: Vec<TableId>,
+        waited: Time,
+    },
+    // a party gave up waiting before they were seated
+    LeftWaitlist {
+        party: PartyId,
+    },
+    TableFreed {
+        table: TableId,
+    },
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Waitlisted {
+                party,
+                estimated_wait: Some(wait),
+            } => write!(f, "party {party} is on the waitlist, about {wait} minutes"),
+            Event::Waitlisted {
+                party,
+                estimated_wait: None,
+            } => write!(f, "party {party} is on the waitlist"),
+            Event::Seated {
+                party,
+                tables,
+                waited,
+            } => {
+                let tables: Vec<String> = tables.iter().map(TableId::to_string).collect();
+                write!(
+                    f,
+                    "party {party} seated at table {} after {waited} minutes",
+                    tables.join(" + ")
+                )
+            }
+            Event::LeftWaitlist { party } => write!(f, "party {party} left the waitlist"),
+            Event::TableFreed { table } => write!(f, "table {table} is free"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostingError {
+    EmptyParty,
+    // bigger than the MAX_COMBINED largest tables put together, so there's no way to ever seat them
+    PartyTooLarge { size: u32, largest: u32 },
+    UnknownParty(PartyId),
+}
+
+impl fmt::Display for HostingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HostingError::EmptyParty => f.write_str("a party needs at least one person"),
+            HostingError::PartyTooLarge { size, largest } => write!(
+                f,
+                "a party of {size} won't fit, the most we can seat together is {largest}"
+            ),
+            HostingError::UnknownParty(id) => write!(f, "there is no party {id}"),
+        }
+    }
+}
+
+impl std::error::Error for HostingError {}
+
+// the host stand's part of the Restaurant
+#[derive(Debug, Clone)]
+pub(crate) struct Seating {
+    pub(crate) tables: Vec<Table>,
+    // kept in the order parties will be seated in
+    pub(crate) waitlist: Vec<Party>,
+    pub(crate) events: Vec<Event>,
+    pub(crate) expected_stay: Time,
+    pub(crate) last_party: PartyId,
+}
+
+impl Default for Seating {
+    fn default() -> Seating {
+        Seating {
+            tables: Vec::new(),
+            waitlist: Vec::new(),
+            events: Vec::new(),
+            expected_stay: DEFAULT_EXPECTED_STAY,
+            last_party: 0,
+        }
+    }
+}
+
+// put another table on the floor
+pub fn add_table(restaurant: &mut Restaurant, capacity: u32) -> TableId {
+    let tables = &mut restaurant.seating.tables;
+    let id = tables.len() as TableId + 1;
+    tables.push(Table {
+        id,
+        capacity,
+        occupant: None,
+    });
+    id
+}
+
+// since this function is used in eat_at_restaurant, it must be also marked as public
+// the pub from pub mod hosting is not enough, because it only makes the module public, not its contents
+pub fn add_to_waitlist(
+    restaurant: &mut Restaurant,
+    name: &str,
+    size: u32,
+    priority: Priority,
+    now: Time,
+) -> Result<PartyId, HostingError> {
+    if size == 0 {
+        return Err(HostingError::EmptyParty);
+    }
+    let seating = &mut restaurant.seating;
+    let mut capacities: Vec<u32> = seating.tables.iter().map(|table| table.capacity).collect();
+    capacities.sort_unstable_by(|a, b| b.cmp(a));
+    // no party can be bigger than u32::MAX, so stopping there instead of overflowing doesn't change the answer
+    let largest = capacities
+        .iter()
+        .take(MAX_COMBINED)
+        .fold(0, |total: u32, &capacity| total.saturating_add(capacity));
+    if size > largest {
+        return Err(HostingError::PartyTooLarge { size, largest });
+    }
+
+    seating.last_party += 1;
+    let party = Party {
+        id: seating.last_party,
+        name: name.to_string(),
+        size,
+        arrived: now,
+        priority,
+        passed_over: 0,
+    };
+    // behind everyone with the same or a higher priority, which is everyone who arrived earlier with the same priority
+    let position = seating
+        .waitlist
+        .iter()
+        .position(|waiting| waiting.priority < priority)
+        .unwrap_or(seating.waitlist.len());
+    let id = party.id;
+    seating.waitlist.insert(position, party);
+    let estimated_wait = estimated_wait(restaurant, id, now);
+    restaurant.seating.events.push(Event::Waitlisted {
+        party: id,
+        estimated_wait,
+    });
+    Ok(id)
+}
+
+// seat as many waiting parties as the free tables allow, in waitlist order, and return who was seated
+// a party that doesn't fit anywhere yet doesn't hold up the smaller parties behind it, until it has been passed over
+// MAX_PASSED_OVER times; from then on nobody behind it is seated until it is
+pub fn seat_at_table(restaurant: &mut Restaurant, now: Time) -> Vec<PartyId> {
+    let seating = &mut restaurant.seating;
+    let mut seated = Vec::new();
+    let mut index = 0;
+    while index < seating.waitlist.len() {
+        let free: Vec<Slot> = seating
+            .tables
+            .iter()
+            .enumerate()
+            .filter(|(_, table)| table.occupant.is_none())
+            .map(|(index, table)| Slot {
+                index,
+                capacity: table.capacity,
+                free_at: now,
+            })
+            .collect();
+        let Some(chosen) = best_fit(&free, seating.waitlist[index].size) else {
+            if seating.waitlist[index].passed_over >= MAX_PASSED_OVER {
+                break;
+            }
+            index += 1;
+            continue;
+        };
+        let party = seating.waitlist.remove(index);
+        for waiting in &mut seating.waitlist[..index] {
+            waiting.passed_over += 1;
+        }
+        let mut tables = Vec::new();
+        for slot in chosen {
+            let table = &mut seating.tables[free[slot].index];
+            table.occupant = Some(Occupant {
+                party: party.id,
+                since: now,
+            });
+            tables.push(table.id);
+        }
+        seating.events.push(Event::Seated {
+            party: party.id,
+            tables,
+            waited: now.saturating_sub(party.arrived),
+        });
+        seated.push(party.id);
+    }
+    seated
+}
+
+// a party has finished, or given up waiting; either way they're gone and any tables they had are free again
+// this doesn't seat anyone else, call seat_at_table for that
+pub fn party_left(restaurant: &mut Restaurant, party: PartyId) -> Result<(), HostingError> {
+    let seating = &mut restaurant.seating;
+    if let Some(index) = seating
+        .waitlist
+        .iter()
+        .position(|waiting| waiting.id == party)
+    {
+        seating.waitlist.remove(index);
+        seating.events.push(Event::LeftWaitlist { party });
+        return Ok(());
+    }
+    let mut found = false;
+    for table in &mut seating.tables {
+        if table
+            .occupant
+            .is_some_and(|occupant| occupant.party == party)
+        {
+            table.occupant = None;
+            seating.events.push(Event::TableFreed { table: table.id });
+            found = true;
+        }
+    }
+    if found {
+        Ok(())
+    } else {
+        Err(HostingError::UnknownParty(party))
+    }
+}
+
+// how long each waiting party can expect to wait from now, in waitlist order
+// this plays the waitlist forward assuming every party stays for the expected stay, so it's a guess, not a promise
+// a party gets None if it can't be seated even once every table is free, which can happen if tables were taken away
+pub fn estimated_waits(restaurant: &Restaurant, now: Time) -> Vec<(PartyId, Option<Time>)> {
+    let seating = &restaurant.seating;
+    let stay = seating.expected_stay;
+    let mut slots: Vec<Slot> = seating
+        .tables
+        .iter()
+        .enumerate()
+        .map(|(index, table)| Slot {
+            index,
+            capacity: table.capacity,
+            free_at: match table.occupant {
+                Some(occupant) => occupant.since.saturating_add(stay).max(now),
+                None => now,
+            },
+        })
+        .collect();
+    seating
+        .waitlist
+        .iter()
+        .map(|party| {
+            let Some(chosen) = best_fit(&slots, party.size) else {
+                return (party.id, None);
+            };
+            let start = chosen
+                .iter()
+                .map(|&slot| slots[slot].free_at)
+                .max()
+                .unwrap_or(now);
+            for slot in chosen {
+                slots[slot].free_at = start.saturating_add(stay);
+            }
+            (party.id, Some(start - now))
+        })
+        .collect()
+}
+
+// the estimated wait for one party, None if they aren't waiting or can't be seated
+pub fn estimated_wait(restaurant: &Restaurant, party: PartyId, now: Time) -> Option<Time> {
+    estimated_waits(restaurant, now)
+        .into_iter()
+        .find(|&(id, _)| id == party)?
+        .1
+}
+
+// a table as far as choosing tables is concerned: how big it is and when it's free
+#[derive(Debug, Clone, Copy)]
+struct Slot {
+    // where the table is in Seating::tables
+    index: usize,
+    capacity: u32,
+    free_at: Time,
+}
+
+// the slots a party of this size should get, as indexes into slots
+// the best choice is the one that's free soonest, then the one with the fewest empty seats, then the one with the fewest tables
+fn best_fit(slots: &[Slot], size: u32) -> Option<Vec<usize>> {
+    let mut best = None;
+    search(slots, size, 0, &mut Vec::new(), &mut best);
+    best.map(|(_, chosen)| chosen)
+}
+
+type Score = (Time, u32, usize);
+
+// try every combination of up to MAX_COMBINED slots, starting from the ones already chosen
+fn search(
+    slots: &[Slot],
+    size: u32,
+    start: usize,
+    chosen: &mut Vec<usize>,
+    best: &mut Option<(Score, Vec<usize>)>,
+) {
+    let capacity = chosen.iter().fold(0, |total: u32, &slot| {
+        total.saturating_add(slots[slot].capacity)
+    });
+    if !chosen.is_empty() && capacity >= size {
+        let free_at = chosen
+            .iter()
+            .map(|&slot| slots[slot].free_at)
+            .max()
+            .unwrap_or(0);
+        let score = (free_at, capacity, chosen.len());
+        if best
+            .as_ref()
+            .is_none_or(|(best_score, _)| score < *best_score)
+        {
+            *best = Some((score, chosen.clone()));
+        }
+        // the party already fits, so adding another table can only make this worse
+        return;
+    }
+    if chosen.len() == MAX_COMBINED {
+        return;
+    }
+    for next in start..slots.len() {
+        chosen.push(next);
+        search(slots, size, next + 1, chosen, best);
+        chosen.pop();
+    }
+}
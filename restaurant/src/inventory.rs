@@ -0,0 +1,402 @@
+// what the kitchen has on the shelves, and what cooking each item uses up
+// recipes say which ingredients go into an item, and the restaurant ships with the ones in recipes.toml next to
+// Cargo.toml; every change to the stock is written to a ledger, so restocks and stock counts can be saved and replayed
+// only ingredients that have been restocked or counted are tracked, so a restaurant that never does either can cook
+// forever, the way it always could
+// once a tracked ingredient runs out it's 86'd: serving::take_order turns away anything that needs it until it's restocked
+// orders waiting for the kitchen have their ingredients held for them, so taking an order can't sell what an earlier one
+// is going to use
+use crate::menu::{Dish, Item};
+use crate::order::{Order, OrderId};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// an amount of an ingredient, in whatever unit it's stocked in
+pub type Quantity = u32;
+
+// how much of each ingredient goes into something
+pub type Recipe = BTreeMap<String, Quantity>;
+
+#[derive(Debug)]
+pub enum RecipeError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    UnknownDish(String),
+}
+
+impl fmt::Display for RecipeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecipeError::Io(err) => write!(f, "couldn't read the recipes: {err}"),
+            RecipeError::Toml(err) => write!(f, "the recipes aren't valid: {err}"),
+            RecipeError::UnknownDish(dish) => write!(f, "{dish:?} is not a dish"),
+        }
+    }
+}
+
+impl std::error::Error for RecipeError {}
+
+impl From<io::Error> for RecipeError {
+    fn from(err: io::Error) -> RecipeError {
+        RecipeError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for RecipeError {
+    fn from(err: toml::de::Error) -> RecipeError {
+        RecipeError::Toml(err)
+    }
+}
+
+// the layout of the TOML file, before any of it is checked
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipesFile {
+    #[serde(default)]
+    dishes: BTreeMap<String, Recipe>,
+    #[serde(default)]
+    toast: BTreeMap<String, Recipe>,
+    #[serde(default)]
+    fruit: BTreeMap<String, Recipe>,
+    #[serde(default)]
+    low_stock: BTreeMap<String, Quantity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipes {
+    dishes: BTreeMap<Dish, Recipe>,
+    // extra ingredients for a breakfast, by the kind of toast and by the fruit
+    toast: BTreeMap<String, Recipe>,
+    fruit: BTreeMap<String, Recipe>,
+    // how little of an ingredient there can be before an alert goes out
+    low_stock: BTreeMap<String, Quantity>,
+}
+
+impl Recipes {
+    // the recipes in recipes.toml, which are built into the library so they're always there
+    pub fn standard() -> &'static Recipes {
+        static STANDARD: OnceLock<Recipes> = OnceLock::new();
+        STANDARD.get_or_init(|| {
+            Recipes::from_toml(include_str!("../recipes.toml"))
+                .expect("recipes.toml is a valid set of recipes")
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Recipes, RecipeError> {
+        Recipes::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> Result<Recipes, RecipeError> {
+        let file: RecipesFile = toml::from_str(text)?;
+        let mut dishes = BTreeMap::new();
+        for (dish, recipe) in file.dishes {
+            let dish = dish
+                .parse()
+                .map_err(|_| RecipeError::UnknownDish(dish.clone()))?;
+            dishes.insert(dish, recipe);
+        }
+        Ok(Recipes {
+            dishes,
+            toast: file.toast,
+            fruit: file.fruit,
+            low_stock: file.low_stock,
+        })
+    }
+
+    // everything that goes into one item
+    // a toast or fruit without a recipe doesn't add anything, since there's no way to know what it takes
+    pub fn ingredients(&self, item: &Item) -> Recipe {
+        let mut recipe = self.dishes.get(&item.dish()).cloned().unwrap_or_default();
+        if let Item::Breakfast(breakfast) = item {
+            let extras = [
+                self.toast.get(&breakfast.toast),
+                self.fruit.get(breakfast.seasonal_fruit()),
+            ];
+            for (ingredient, quantity) in extras.into_iter().flatten().flatten() {
+                let total = recipe.entry(ingredient.clone()).or_insert(0);
+                *total = total.saturating_add(*quantity);
+            }
+        }
+        recipe
+    }
+
+    // the stock level an ingredient has to fall to for a low stock alert, None if it never gets one
+    pub fn low_stock(&self, ingredient: &str) -> Option<Quantity> {
+        self.low_stock.get(ingredient).copied()
+    }
+}
+
+impl Default for Recipes {
+    fn default() -> Recipes {
+        Recipes::standard().clone()
+    }
+}
+
+// one change to the stock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StockEvent {
+    // a delivery came in
+    Restocked {
+        ingredient: String,
+        quantity: Quantity,
+    },
+    // someone counted what's actually on the shelf, which replaces whatever the ledger thought was there
+    Counted {
+        ingredient: String,
+        quantity: Quantity,
+    },
+    // the kitchen cooked an order; if there wasn't enough, which only happens when a stock take finds less than the
+    // queued orders were promised, the stock only goes down to nothing and a Short alert goes out
+    Used {
+        order: OrderId,
+        ingredient: String,
+        quantity: Quantity,
+    },
+}
+
+impl fmt::Display for StockEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StockEvent::Restocked {
+                ingredient,
+                quantity,
+            } => write!(f, "restocked {quantity} {ingredient}"),
+            StockEvent::Counted {
+                ingredient,
+                quantity,
+            } => write!(f, "counted {quantity} {ingredient}"),
+            StockEvent::Used {
+                order,
+                ingredient,
+                quantity,
+            } => write!(f, "order {order} used {quantity} {ingredient}"),
+        }
+    }
+}
+
+// something the kitchen should know about the stock
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StockAlert {
+    Low {
+        ingredient: String,
+        left: Quantity,
+    },
+    // all gone, so nothing that needs it can be ordered
+    EightySixed {
+        ingredient: String,
+    },
+    // an order used more than there was, so the kitchen made it with something that wasn't on the shelf
+    Short {
+        ingredient: String,
+        missing: Quantity,
+    },
+    BackInStock {
+        ingredient: String,
+    },
+}
+
+impl fmt::Display for StockAlert {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StockAlert::Low { ingredient, left } => {
+                write!(f, "{ingredient} is running low, {left} left")
+            }
+            StockAlert::EightySixed { ingredient } => write!(f, "86 {ingredient}"),
+            StockAlert::Short {
+                ingredient,
+                missing,
+            } => write!(f, "{missing} more {ingredient} was used than was in stock"),
+            StockAlert::BackInStock { ingredient } => write!(f, "{ingredient} is back in stock"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    recipes: Recipes,
+    // only the ingredients that are being tracked
+    stock: BTreeMap<String, Quantity>,
+    ledger: Vec<StockEvent>,
+    alerts: Vec<StockAlert>,
+}
+
+impl Inventory {
+    // nothing is tracked until it's restocked or counted
+    pub fn new(recipes: Recipes) -> Inventory {
+        Inventory {
+            recipes,
+            ..Inventory::default()
+        }
+    }
+
+    pub fn recipes(&self) -> &Recipes {
+        &self.recipes
+    }
+
+    // switch to other recipes, e.g. ones from Recipes::load; the stock stays as it is
+    pub fn set_recipes(&mut self, recipes: Recipes) {
+        self.recipes = recipes;
+    }
+
+    // how much of an ingredient is left, None if it isn't tracked
+    pub fn stock(&self, ingredient: &str) -> Option<Quantity> {
+        self.stock.get(ingredient).copied()
+    }
+
+    // every tracked ingredient and how much of it is left, in alphabetical order
+    pub fn stocks(&self) -> impl Iterator<Item = (&str, Quantity)> {
+        self.stock
+            .iter()
+            .map(|(ingredient, &quantity)| (ingredient.as_str(), quantity))
+    }
+
+    // the tracked ingredients that have run out
+    pub fn eighty_sixed(&self) -> impl Iterator<Item = &str> {
+        self.stocks()
+            .filter(|&(_, quantity)| quantity == 0)
+            .map(|(ingredient, _)| ingredient)
+    }
+
+    pub fn restock(&mut self, ingredient: &str, quantity: Quantity) {
+        self.record(StockEvent::Restocked {
+            ingredient: ingredient.to_string(),
+            quantity,
+        });
+    }
+
+    // set an ingredient to what's really on the shelf, after a stock take
+    pub fn count(&mut self, ingredient: &str, quantity: Quantity) {
+        self.record(StockEvent::Counted {
+            ingredient: ingredient.to_string(),
+            quantity,
+        });
+    }
+
+    // every change to the stock so far, oldest first
+    pub fn ledger(&self) -> &[StockEvent] {
+        &self.ledger
+    }
+
+    // start again from nothing tracked and apply these events in order, e.g. a ledger that was saved earlier, or just the
+    // restocks from one, to see where the stock would be without anything being cooked
+    // this doesn't raise any alerts, since none of it is news
+    pub fn replay(&mut self, events: &[StockEvent]) {
+        self.stock.clear();
+        self.ledger.clear();
+        for event in events {
+            self.record(event.clone());
+        }
+        self.alerts.clear();
+    }
+
+    // everything that has happened to the stock that the kitchen should hear about since the last call, oldest first
+    pub fn take_alerts(&mut self) -> Vec<StockAlert> {
+        std::mem::take(&mut self.alerts)
+    }
+
+    // the first ingredient there isn't enough of to make all of these items, counting them together and on top of what's
+    // held for the items already waiting for the kitchen, along with the item that needs it
+    pub fn shortage<'a, 'b>(
+        &self,
+        waiting: impl IntoIterator<Item = &'b Item>,
+        items: impl IntoIterator<Item = &'a Item>,
+    ) -> Option<(&'a Item, String)> {
+        let mut needed: BTreeMap<String, Quantity> = BTreeMap::new();
+        for item in waiting {
+            for (ingredient, quantity) in self.recipes.ingredients(item) {
+                let total = needed.entry(ingredient).or_insert(0);
+                *total = total.saturating_add(quantity);
+            }
+        }
+        for item in items {
+            for (ingredient, quantity) in self.recipes.ingredients(item) {
+                let total = needed.entry(ingredient.clone()).or_insert(0);
+                *total = total.saturating_add(quantity);
+                if self.stock(&ingredient).is_some_and(|left| left < *total) {
+                    return Some((item, ingredient));
+                }
+            }
+        }
+        None
+    }
+
+    // take what the kitchen is about to make for an order out of the stock, comped items included, since the kitchen
+    // still made them; for a remake that's only the lines that were sent back
+    pub(crate) fn use_for(&mut self, order: &Order) {
+        let mut used: BTreeMap<String, Quantity> = BTreeMap::new();
+        for line in order.lines_to_cook() {
+            for (ingredient, quantity) in self.recipes.ingredients(&line.item) {
+                let total = used.entry(ingredient).or_insert(0);
+                *total = total.saturating_add(quantity);
+            }
+        }
+        for (ingredient, quantity) in used {
+            // untracked ingredients aren't written down, there's nothing to take them away from
+            if self.stock.contains_key(&ingredient) {
+                self.record(StockEvent::Used {
+                    order: order.id(),
+                    ingredient,
+                    quantity,
+                });
+            }
+        }
+    }
+
+    // write an event in the ledger, change the stock to match, and raise whatever alerts that calls for
+    fn record(&mut self, event: StockEvent) {
+        let mut missing = 0;
+        let (ingredient, after) = match &event {
+            StockEvent::Restocked {
+                ingredient,
+                quantity,
+            } => (
+                ingredient,
+                self.stock(ingredient)
+                    .unwrap_or(0)
+                    .saturating_add(*quantity),
+            ),
+            StockEvent::Counted {
+                ingredient,
+                quantity,
+            } => (ingredient, *quantity),
+            StockEvent::Used {
+                ingredient,
+                quantity,
+                ..
+            } => {
+                let left = self.stock(ingredient).unwrap_or(0);
+                missing = quantity.saturating_sub(left);
+                (ingredient, left.saturating_sub(*quantity))
+            }
+        };
+        let before = self.stock.insert(ingredient.clone(), after);
+        let ingredient = ingredient.clone();
+        self.ledger.push(event);
+
+        if missing > 0 {
+            self.alerts.push(StockAlert::Short {
+                ingredient: ingredient.clone(),
+                missing,
+            });
+        }
+        let was_out = before == Some(0);
+        if after == 0 && !was_out {
+            self.alerts.push(StockAlert::EightySixed { ingredient });
+        } else if after > 0 && was_out {
+            self.alerts.push(StockAlert::BackInStock { ingredient });
+        } else if let Some(low) = self.recipes.low_stock(&ingredient) {
+            // only when it first drops to the threshold, not every time something more is used
+            if after > 0 && after <= low && before.is_none_or(|before| before > low) {
+                self.alerts.push(StockAlert::Low {
+                    ingredient,
+                    left: after,
+                });
+            }
+        }
+    }
+}
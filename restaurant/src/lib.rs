@@ -1,66 +1,98 @@
 // implements a library representing a restaurant
-// the functions are just stubs as the idea is to show how to structure a library, not simulate a restaurant
+// the functions started out as stubs to show how to structure a library, now they model a small restaurant:
+// a menu, orders that go from placed to paid, and a kitchen that cooks them in the order they came in
 // the modules serve to group functions that are related to each other
 // this makes it easier to find functions and to understand the code
 // we have this module tree now:
 // crate
-// └── front_of_house
-// ├── hosting
-// │   ├── add_to_waitlist
-// │   └── seat_at_table
-// └── serving
-//     ├── take_order
-//     ├── serve_order
-//     └── take_payment
-
+// ├── api (only with the server feature)
+// ├── deliver_order
+// ├── front_of_house
+// │   ├── hosting
+// │   │   ├── add_table
+// │   │   ├── add_to_waitlist
+// │   │   ├── seat_at_table
+// │   │   ├── party_left
+// │   │   └── estimated_waits
+// │   └── serving
+// │       ├── take_order
+// │       ├── serve_order
+// │       └── take_payment
+// ├── back_of_house
+// │   ├── fix_incorrect_order
+// │   ├── cook_order
+// │   ├── Breakfast
+// │   └── Appetizer
+// ├── billing
+// ├── corrections
+// ├── inventory
+// ├── menu
+// ├── order
+// ├── restaurant
+// ├── seasons
+// ├── simulation
+// └── storage
+
+#[cfg(feature = "server")]
+pub mod api; // the HTTP/JSON API, run by src\bin\restaurant-server.rs
+pub mod back_of_house; // this is implemented in src\back_of_house.rs
+pub mod billing;
+pub mod corrections;
 pub mod front_of_house; // this is implemented in src\front_of_house.rs
-
-fn deliver_order() {}
-
-mod back_of_house {
-    fn fix_incorrect_order() {
-        cook_order();
-        super::deliver_order(); // super goes up one level in the module tree
-                                // this is useful when the calling code is unlikely to be separated from its parent module, but the parent module may be moved in the module tree
-    }
-
-    fn cook_order() {}
-
-    // structs and enums can also be made public
-    pub struct Breakfast {
-        pub toast: String, // this field will be public
-        seasonal_fruit: String, // this one will be private
-                                // this is emulating the idea that the customer can pick the toast, but the fruit is seasonal and the restaurant decides what it is
-    }
-
-    impl Breakfast {
-        // we need to implement a public constructor, otherwise we would not be able to create a Breakfast outside of the module since seasonal_fruit is private
-        pub fn summer(toast: &str) -> Breakfast {
-            Breakfast {
-                toast: String::from(toast),
-                seasonal_fruit: String::from("peaches"), // this is a private field, but it can be accessed from inside the module
-                                                         // the "chef" still gets to decide what the fruit is, but the customer can't pick it
-            }
-        }
-    }
-
-    // making an enum public makes all of its variants public as well
-    pub enum Appetizer {
-        Soup,
-        Salad,
-    }
+pub mod inventory;
+pub mod menu;
+pub mod order;
+mod restaurant;
+pub mod seasons;
+pub mod simulation;
+pub mod storage;
+
+pub use back_of_house::{Appetizer, Breakfast};
+pub use billing::{Bill, Billing, BillingError, Discount, Payment, Rate, Rounding, Split};
+pub use corrections::{
+    remake_report, AuditEntry, AuditEvent, Complaint, Problem, RemakeRate, RemakeReport,
+};
+pub use front_of_house::hosting::{
+    Event, Occupant, Party, PartyId, Priority, Table, TableId, Time,
+};
+pub use inventory::{Inventory, Quantity, Recipe, RecipeError, Recipes, StockAlert, StockEvent};
+pub use menu::{Cents, Dish, Item, Menu, MenuItem};
+pub use order::{Order, OrderError, OrderId, OrderLine, Status};
+pub use restaurant::Restaurant;
+pub use seasons::{MonthDay, Season, SeasonalMenu};
+pub use simulation::{simulate, SimConfig, SimError, SimReport};
+pub use storage::{JsonLinesStorage, MemoryStorage, Saved, Storage, StorageError};
+
+// the kitchen calls this once an order is cooked, to hand it over to the front of house
+fn deliver_order(restaurant: &mut Restaurant, id: OrderId) {
+    restaurant
+        .order_mut(id)
+        .and_then(|order| order.advance(Status::Ready))
+        .expect("only orders that are cooking get delivered");
 }
 
 // this is a public function because we want it to be able to be called from outside the library
+// most of this function is a tour of paths and use declarations, so a lot of what it brings into scope is never used
+#[allow(unused)]
 pub fn eat_at_restaurant() {
     // absolute path
     // will remain valid if eat_at_restaurant is moved to another module
     // absolute paths are preferred because they are more likely to remain valid when refactoring
-    crate::front_of_house::hosting::add_to_waitlist();
+    // the hosting functions work on a restaurant, so we need one of those first
+    let mut restaurant = Restaurant::default();
+    crate::front_of_house::hosting::add_to_waitlist(
+        &mut restaurant,
+        "Ferris",
+        2,
+        Priority::Normal,
+        0,
+    )
+    .expect("there is a table for two");
 
     // relative path
     // will remain valid if front_of_house and eat_at_restaurant are moved to another module
-    front_of_house::hosting::add_to_waitlist(); 
+    front_of_house::hosting::add_to_waitlist(&mut restaurant, "Corro", 4, Priority::Normal, 0)
+        .expect("there is a table for four");
 
     // order a breakfast in the summer with rye toast
     let mut meal = back_of_house::Breakfast::summer("rye");
@@ -71,19 +103,89 @@ pub fn eat_at_restaurant() {
     // this won't compile if uncommented because seasonal_fruit is private
     // we aren't allowed to see or modify what fruit comes with the meal
     // meal.seasonal_fruit = String::from("blueberries");
+    // but we can look at it, because Breakfast has a method that only lets us read it
+    println!("It comes with {}", meal.seasonal_fruit());
 
     // we can use the variants of Appetizer here because it is public
     let order1 = back_of_house::Appetizer::Soup;
     let order2 = back_of_house::Appetizer::Salad;
 
+    // the kitchen is down to its last two heads of lettuce, and every salad it makes uses one
+    restaurant.inventory_mut().restock("lettuce", 2);
+
+    // the restaurant can actually take the order now, and the kitchen cooks it
+    // soup is out of season in the summer, so the kitchen won't make it
+    let items = vec![Item::Breakfast(meal.clone()), Item::Appetizer(order1)];
+    if let Err(err) = front_of_house::serving::take_order(&mut restaurant, items) {
+        println!("Sorry, {err}");
+    }
+    let items = vec![Item::Breakfast(meal), Item::Appetizer(order2)];
+    let id = front_of_house::serving::take_order(&mut restaurant, items)
+        .expect("breakfast and salad are on the summer menu");
+    back_of_house::cook_order(&mut restaurant);
+    front_of_house::serving::serve_order(&mut restaurant, id)
+        .expect("the kitchen cooked the order");
+
+    // the salad came out wrong, so it goes back to the kitchen and we don't pay for it
+    let complaint = Complaint {
+        order: id,
+        lines: vec![1],
+        problem: Problem::WrongItem,
+        comp: true,
+    };
+    back_of_house::fix_incorrect_order(&mut restaurant, complaint).expect("the order was served");
+    back_of_house::cook_order(&mut restaurant);
+    front_of_house::serving::serve_order(&mut restaurant, id)
+        .expect("the kitchen made the order again");
+    for entry in restaurant.audit_trail() {
+        println!("{entry}");
+    }
+    print!("{}", remake_report(&restaurant));
+    // making the salad twice used up the lettuce, so nobody else can order one until there's a delivery
+    for alert in restaurant.inventory_mut().take_alerts() {
+        println!("{alert}");
+    }
+    // two of us are paying, and we split the check down the middle
+    restaurant.billing_mut().tax = Rate::basis_points(825);
+    let payment = Payment {
+        tip: Rate::percent(18).expect("18% is a rate"),
+        split: Split::Evenly(2),
+        ..Payment::default()
+    };
+    let bill = front_of_house::serving::take_payment(&mut restaurant, id, &payment)
+        .expect("the order was served");
+    print!("{bill}");
+
+    // if the restaurant has to close and open again, nothing is lost as long as it was saved first
+    let mut storage = MemoryStorage::new();
+    restaurant
+        .save(&mut storage)
+        .expect("memory storage can't fail");
+    let mut reopened = Restaurant::default();
+    reopened
+        .restore(&storage)
+        .expect("memory storage can't fail");
+    println!(
+        "Order {id} is still {}",
+        reopened.order(id).expect("the order was saved").status()
+    );
+
     // if we "import" the front_of_house module with use, we can use its functions without the full path
     // this works like a symlink in a filesystem, but only for the scope in which use was called
     use crate::front_of_house::hosting;
-    hosting::add_to_waitlist();
+    hosting::add_to_waitlist(&mut restaurant, "Bea", 6, Priority::High, 5)
+        .expect("there is a table for six");
 
     // we could also use just the function, but this is less idiomatic and can cause confusion, so it is not recommended
     use crate::front_of_house::hosting::add_to_waitlist;
-    add_to_waitlist();
+    add_to_waitlist(&mut restaurant, "Ada", 8, Priority::Normal, 5)
+        .expect("two tables for four can be pushed together");
+
+    // everyone who fits is seated, Ada's party of 8 gets two tables pushed together
+    hosting::seat_at_table(&mut restaurant, 10);
+    for event in restaurant.take_events() {
+        println!("{event}");
+    }
 
     // with structs, enums, or other items, the idiomatic way is to use the full path:
     // this isn't restaurant related, but whatever. neither are the next two examples
@@ -92,16 +194,24 @@ pub fn eat_at_restaurant() {
     map.insert(1, 2);
 
     // we can't use the same name for two items in the same scope, so we need to use their parents:
-    use std::fmt;   // if we used use std::fmt::Result, we would not be able to use use std::io::Result, so this is the more idiomatic solution
+    use std::fmt; // if we used use std::fmt::Result, we would not be able to use use std::io::Result, so this is the more idiomatic solution
     use std::io;
-    fn function1() -> fmt::Result {Ok(())}
-    fn function2() -> io::Result<()> {Ok(())}
+    fn function1() -> fmt::Result {
+        Ok(())
+    }
+    fn function2() -> io::Result<()> {
+        Ok(())
+    }
 
     // we can also use "use as" to rename items:
     use std::fmt::Result;
     use std::io::Result as IoResult; // since we already imported Result, we need to rename this to avoid a conflict
-    fn function3() -> Result {Ok(())}
-    fn function4() -> IoResult<()> {Ok(())} // and it works just fine
+    fn function3() -> Result {
+        Ok(())
+    }
+    fn function4() -> IoResult<()> {
+        Ok(())
+    } // and it works just fine
 
     // items brought into scope with use are private by default, so we can't use them outside of the scope in which use was called
     // we can fix this with pub use
@@ -110,15 +220,13 @@ pub fn eat_at_restaurant() {
     pub use crate::front_of_house::hosting as pub_hosting; // I had to rename this because otherwise it would conflict with the hosting module I imported in an earlier example
 
     // we can also use nested paths to bring multiple items into scope with less code
-    use std::{cmp::Ordering, arch}; // this is the same as "use std::cmp::Ordering; use std::arch;"
+    use std::{arch, cmp::Ordering}; // this is the same as "use std::cmp::Ordering; use std::arch;"
 
     // nested paths can be used at any level
     use std::env::{Args, Vars}; // this is the same as "use std::env::Args; use std::env::Vars;"
 
     // finally, if we want to bring all public items in a path into scope, we can use glob operator
     // or as I like to call it, the globerator
-    use std::collections::*; // this will bring all public items into scope. this should be used with caution, because it can cause name conflicts, 
+    use std::collections::*; // this will bring all public items into scope. this should be used with caution, because it can cause name conflicts,
                              // make things hard to read, and make it unclear where a name is defined
-
-
-}
\ No newline at end of file
+}
@@ -0,0 +1,138 @@
+// what the restaurant sells and how much it costs
+// the menu prices dishes, not the exact plate a customer asks for: every breakfast costs the same whatever toast comes with it
+use crate::back_of_house::{Appetizer, Breakfast};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+// money is counted in whole cents so adding up a bill never runs into floating point rounding
+pub type Cents = u64;
+
+// a kind of thing on the menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Dish {
+    Breakfast,
+    Appetizer(Appetizer),
+}
+
+impl fmt::Display for Dish {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Dish::Breakfast => f.write_str("breakfast"),
+            Dish::Appetizer(appetizer) => appetizer.fmt(f),
+        }
+    }
+}
+
+// the same names Display uses, e.g. "soup"
+impl FromStr for Dish {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Dish, String> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "breakfast" => Ok(Dish::Breakfast),
+            "soup" => Ok(Dish::Appetizer(Appetizer::Soup)),
+            "salad" => Ok(Dish::Appetizer(Appetizer::Salad)),
+            _ => Err(s.to_string()),
+        }
+    }
+}
+
+// one thing a customer ordered, with whatever choices they made about it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Item {
+    Breakfast(Breakfast),
+    Appetizer(Appetizer),
+}
+
+impl Item {
+    // which dish on the menu this is, which is what decides the price
+    pub fn dish(&self) -> Dish {
+        match self {
+            Item::Breakfast(_) => Dish::Breakfast,
+            Item::Appetizer(appetizer) => Dish::Appetizer(*appetizer),
+        }
+    }
+}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Item::Breakfast(breakfast) => breakfast.fmt(f),
+            Item::Appetizer(appetizer) => appetizer.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub dish: Dish,
+    pub price: Cents,
+}
+
+// the dishes the restaurant serves, in the order they're listed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Menu {
+    items: Vec<MenuItem>,
+}
+
+impl Menu {
+    // a menu with nothing on it yet
+    pub fn new() -> Menu {
+        Menu::default()
+    }
+
+    // the menu the restaurant opens with
+    pub fn standard() -> Menu {
+        let mut menu = Menu::new();
+        menu.add(Dish::Breakfast, 1250);
+        menu.add(Dish::Appetizer(Appetizer::Soup), 650);
+        menu.add(Dish::Appetizer(Appetizer::Salad), 725);
+        menu
+    }
+
+    // put a dish on the menu, or change its price if it's already there
+    pub fn add(&mut self, dish: Dish, price: Cents) {
+        match self.items.iter_mut().find(|item| item.dish == dish) {
+            Some(item) => item.price = price,
+            None => self.items.push(MenuItem { dish, price }),
+        }
+    }
+
+    // take a dish off the menu, returning what it used to cost
+    pub fn remove(&mut self, dish: Dish) -> Option<MenuItem> {
+        let index = self.items.iter().position(|item| item.dish == dish)?;
+        Some(self.items.remove(index))
+    }
+
+    // None if the dish isn't on the menu
+    pub fn price(&self, dish: Dish) -> Option<Cents> {
+        self.items
+            .iter()
+            .find(|item| item.dish == dish)
+            .map(|item| item.price)
+    }
+
+    pub fn items(&self) -> &[MenuItem] {
+        &self.items
+    }
+}
+
+impl fmt::Display for Menu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for item in &self.items {
+            writeln!(
+                f,
+                "{:<20}{:>10}",
+                item.dish.to_string(),
+                dollars(item.price)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// cents written out the way a customer reads them, e.g. 1250 is "$12.50"
+pub fn dollars(cents: Cents) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
@@ -0,0 +1,211 @@
+// an order and the steps it goes through, from being written down by a server to being paid for
+use crate::menu::{Cents, Dish, Item};
+use crate::seasons::Season;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// orders are numbered from 1 in the order they're taken
+pub type OrderId = u64;
+
+// where an order is in its life; an order only ever moves one step forward at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Status {
+    // written down and waiting in the kitchen queue
+    Placed,
+    Cooking,
+    // cooked and waiting to be taken to the table
+    Ready,
+    Served,
+    Paid,
+}
+
+impl Status {
+    // the step that comes after this one, None once the order is paid for
+    pub fn next(self) -> Option<Status> {
+        match self {
+            Status::Placed => Some(Status::Cooking),
+            Status::Cooking => Some(Status::Ready),
+            Status::Ready => Some(Status::Served),
+            Status::Served => Some(Status::Paid),
+            Status::Paid => None,
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Status::Placed => "placed",
+            Status::Cooking => "cooking",
+            Status::Ready => "ready",
+            Status::Served => "served",
+            Status::Paid => "paid",
+        })
+    }
+}
+
+// one item on an order, with the price it had on the menu when it was ordered
+// the price is copied so changing the menu later doesn't change what earlier customers owe
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrderLine {
+    pub item: Item,
+    pub price: Cents,
+    // the restaurant is paying for this one, after something went wrong with it
+    pub comped: bool,
+    // sent back and waiting for the kitchen to make it again
+    #[serde(default)]
+    pub remaking: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Order {
+    id: OrderId,
+    lines: Vec<OrderLine>,
+    status: Status,
+}
+
+// why something couldn't be done with an order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderError {
+    // an order has to have at least one item on it
+    Empty,
+    NotOnMenu(Dish),
+    // the kitchen won't make this dish in this season
+    OutOfSeason {
+        dish: Dish,
+        season: Season,
+    },
+    // a tracked ingredient the dish needs has run out, or there isn't enough of it for the whole order
+    OutOfStock {
+        dish: Dish,
+        ingredient: String,
+    },
+    UnknownOrder(OrderId),
+    NoSuchLine {
+        id: OrderId,
+        line: usize,
+    },
+    // the order isn't at the step just before the one it was asked to move to
+    WrongStatus {
+        id: OrderId,
+        from: Status,
+        to: Status,
+    },
+}
+
+impl fmt::Display for OrderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OrderError::Empty => f.write_str("an order needs at least one item"),
+            OrderError::NotOnMenu(dish) => write!(f, "{dish} is not on the menu"),
+            OrderError::OutOfSeason { dish, season } => {
+                write!(f, "the kitchen doesn't make {dish} in {season}")
+            }
+            OrderError::OutOfStock { dish, ingredient } => {
+                write!(
+                    f,
+                    "the kitchen is out of {ingredient}, so it can't make {dish}"
+                )
+            }
+            OrderError::UnknownOrder(id) => write!(f, "there is no order {id}"),
+            OrderError::NoSuchLine { id, line } => write!(f, "order {id} has no line {line}"),
+            OrderError::WrongStatus { id, from, to } => {
+                write!(f, "order {id} is {from}, so it can't be {to}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrderError {}
+
+impl Order {
+    // orders are only made by serving::take_order, which is what hands out ids and prices the items
+    pub(crate) fn new(id: OrderId, lines: Vec<OrderLine>) -> Order {
+        Order {
+            id,
+            lines,
+            status: Status::Placed,
+        }
+    }
+
+    pub fn id(&self) -> OrderId {
+        self.id
+    }
+
+    pub fn lines(&self) -> &[OrderLine] {
+        &self.lines
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    // what the items on the order add up to
+    // a menu can charge anything up to Cents::MAX for a dish, so the sum stops there rather than overflowing
+    pub fn total(&self) -> Cents {
+        self.lines
+            .iter()
+            .fold(0, |total: Cents, line| total.saturating_add(line.price))
+    }
+
+    // move the order on to the given step, which has to be the next one
+    pub(crate) fn advance(&mut self, to: Status) -> Result<(), OrderError> {
+        if self.status.next() != Some(to) {
+            return Err(OrderError::WrongStatus {
+                id: self.id,
+                from: self.status,
+                to,
+            });
+        }
+        self.status = to;
+        Ok(())
+    }
+
+    // the line at this index, or an error naming the order if there's no such line
+    pub fn line(&self, line: usize) -> Result<&OrderLine, OrderError> {
+        self.lines
+            .get(line)
+            .ok_or(OrderError::NoSuchLine { id: self.id, line })
+    }
+
+    pub(crate) fn comp(&mut self, line: usize) -> Result<(), OrderError> {
+        self.line(line)?;
+        self.lines[line].comped = true;
+        Ok(())
+    }
+
+    // send a served order back to the start so the kitchen makes these lines again
+    // this is the only way an order ever goes backwards
+    pub(crate) fn remake(&mut self, lines: &[usize]) -> Result<(), OrderError> {
+        if self.status != Status::Served {
+            return Err(OrderError::WrongStatus {
+                id: self.id,
+                from: self.status,
+                to: Status::Placed,
+            });
+        }
+        for &line in lines {
+            self.line(line)?;
+        }
+        for &line in lines {
+            self.lines[line].remaking = true;
+        }
+        self.status = Status::Placed;
+        Ok(())
+    }
+
+    // the lines the kitchen has to make: only the ones sent back when the order is a remake, otherwise all of them
+    pub fn lines_to_cook(&self) -> impl Iterator<Item = &OrderLine> {
+        let remake = self.lines.iter().any(|line| line.remaking);
+        self.lines
+            .iter()
+            .filter(move |line| !remake || line.remaking)
+    }
+
+    // the kitchen has made everything that was sent back
+    pub(crate) fn remade(&mut self) {
+        for line in &mut self.lines {
+            line.remaking = false;
+        }
+    }
+}
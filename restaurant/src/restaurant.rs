@@ -0,0 +1,257 @@
+// everything the restaurant needs to keep track of while it's open
+// the functions in front_of_house and back_of_house all work on a Restaurant, the way the staff all work in the same building
+use crate::back_of_house::Breakfast;
+use crate::billing::{Bill, Billing};
+use crate::corrections::{AuditEntry, AuditEvent};
+use crate::front_of_house::hosting::{self, Event, Party, Seating, Table, Time};
+use crate::inventory::Inventory;
+use crate::menu::{Item, Menu};
+use crate::order::{Order, OrderError, OrderId, Status};
+use crate::seasons::{MonthDay, Season, SeasonalMenu};
+use crate::storage::{Storage, StorageError};
+use std::collections::{BTreeMap, VecDeque};
+
+#[derive(Debug, Clone)]
+pub struct Restaurant {
+    pub(crate) menu: Menu,
+    pub(crate) orders: BTreeMap<OrderId, Order>,
+    // orders waiting for the kitchen, oldest first
+    pub(crate) kitchen: VecDeque<OrderId>,
+    // orders being made again after a complaint, which the kitchen does before anything in the normal queue
+    pub(crate) remakes: VecDeque<OrderId>,
+    pub(crate) audit: Vec<AuditEntry>,
+    pub(crate) seating: Seating,
+    pub(crate) billing: Billing,
+    // the bill for every paid order
+    pub(crate) payments: BTreeMap<OrderId, Bill>,
+    pub(crate) inventory: Inventory,
+    seasonal_menu: SeasonalMenu,
+    season: Season,
+    last_order: OrderId,
+}
+
+impl Restaurant {
+    pub fn new(menu: Menu) -> Restaurant {
+        Restaurant {
+            menu,
+            orders: BTreeMap::new(),
+            kitchen: VecDeque::new(),
+            remakes: VecDeque::new(),
+            audit: Vec::new(),
+            seating: Seating::default(),
+            billing: Billing::default(),
+            payments: BTreeMap::new(),
+            inventory: Inventory::default(),
+            seasonal_menu: SeasonalMenu::default(),
+            // the restaurant used to only ever serve summer breakfasts
+            season: Season::Summer,
+            last_order: 0,
+        }
+    }
+
+    pub fn menu(&self) -> &Menu {
+        &self.menu
+    }
+
+    // the menu can change while the restaurant is open; orders already taken keep the prices they were taken at
+    pub fn menu_mut(&mut self) -> &mut Menu {
+        &mut self.menu
+    }
+
+    pub fn seasonal_menu(&self) -> &SeasonalMenu {
+        &self.seasonal_menu
+    }
+
+    // switch to another seasonal menu, e.g. one from SeasonalMenu::load; the season stays the same
+    pub fn set_seasonal_menu(&mut self, seasonal_menu: SeasonalMenu) {
+        self.seasonal_menu = seasonal_menu;
+    }
+
+    // the season the kitchen is cooking for
+    pub fn season(&self) -> Season {
+        self.season
+    }
+
+    pub fn set_season(&mut self, season: Season) {
+        self.season = season;
+    }
+
+    // set the season to whichever one the seasonal menu says the date falls in
+    pub fn set_date(&mut self, date: MonthDay) {
+        self.season = self.seasonal_menu.season_on(date);
+    }
+
+    // a breakfast with this season's fruit
+    pub fn breakfast(&self, toast: &str) -> Breakfast {
+        Breakfast::in_season(toast, self.season, &self.seasonal_menu)
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&Order> {
+        self.orders.get(&id)
+    }
+
+    // every order taken so far, oldest first
+    pub fn orders(&self) -> impl Iterator<Item = &Order> {
+        self.orders.values()
+    }
+
+    // the orders the kitchen hasn't started on yet, in the order it will cook them
+    pub fn kitchen_queue(&self) -> impl Iterator<Item = OrderId> + '_ {
+        self.remakes.iter().chain(&self.kitchen).copied()
+    }
+
+    // everything the kitchen still has to make for the orders in its queue, which the stock is held for
+    pub(crate) fn queued_items(&self) -> impl Iterator<Item = &Item> + '_ {
+        self.kitchen_queue()
+            .filter_map(|id| self.orders.get(&id))
+            .flat_map(|order| order.lines_to_cook().map(|line| &line.item))
+    }
+
+    // everything that has been done to put orders right, oldest first
+    pub fn audit_trail(&self) -> &[AuditEntry] {
+        &self.audit
+    }
+
+    pub fn billing(&self) -> &Billing {
+        &self.billing
+    }
+
+    // tax, rounding and coupons can be changed at any time, bills that have already been paid stay as they were
+    pub fn billing_mut(&mut self) -> &mut Billing {
+        &mut self.billing
+    }
+
+    // the bill an order was paid with, None if it hasn't been paid
+    pub fn payment(&self, id: OrderId) -> Option<&Bill> {
+        self.payments.get(&id)
+    }
+
+    // every bill paid so far, in order number order
+    pub fn payments(&self) -> impl Iterator<Item = &Bill> {
+        self.payments.values()
+    }
+
+    pub fn inventory(&self) -> &Inventory {
+        &self.inventory
+    }
+
+    // for restocking, counting stock and changing recipes; using stock up is the kitchen's job
+    pub fn inventory_mut(&mut self) -> &mut Inventory {
+        &mut self.inventory
+    }
+
+    pub fn tables(&self) -> &[Table] {
+        &self.seating.tables
+    }
+
+    // the parties waiting for a table, in the order they'll be seated
+    pub fn waitlist(&self) -> &[Party] {
+        &self.seating.waitlist
+    }
+
+    // everything that has happened at the host stand since the last call, oldest first
+    pub fn take_events(&mut self) -> Vec<Event> {
+        std::mem::take(&mut self.seating.events)
+    }
+
+    // how long a party is expected to stay at their table, which is what wait estimates are based on
+    pub fn set_expected_stay(&mut self, minutes: Time) {
+        self.seating.expected_stay = minutes;
+    }
+
+    // hand everything the restaurant has to remember over to storage
+    // the tax, coupons, recipes, seasonal menu and season aren't saved, they're set up the same way every time the restaurant opens
+    pub fn save<S: Storage + ?Sized>(&self, storage: &mut S) -> Result<(), StorageError> {
+        storage.save_menu(&self.menu)?;
+        for order in self.orders.values() {
+            storage.save_order(order)?;
+        }
+        storage.save_seating(&self.seating.tables, &self.seating.waitlist)?;
+        for bill in self.payments.values() {
+            storage.save_payment(bill)?;
+        }
+        storage.save_audit_trail(&self.audit)?;
+        storage.save_stock_ledger(self.inventory.ledger())
+    }
+
+    // pick up where a saved restaurant left off, replacing whatever this one had
+    // anything that was never saved, like the seating plan before the first save, stays as it is
+    // the kitchen queue isn't saved, it's worked out again from which orders are still placed and which of those the
+    // audit trail says were sent back and not yet remade
+    pub fn restore<S: Storage + ?Sized>(&mut self, storage: &S) -> Result<(), StorageError> {
+        let saved = storage.load()?;
+        if let Some(menu) = saved.menu {
+            self.menu = menu;
+        }
+        if let Some((tables, waitlist)) = saved.seating {
+            self.seating.tables = tables;
+            self.seating.waitlist = waitlist;
+        }
+        self.orders = saved.orders;
+        self.payments = saved.payments;
+        self.audit = saved.audit;
+        self.seating.events.clear();
+        // the stock is worked out again from the ledger
+        if let Some(ledger) = saved.stock {
+            self.inventory.replay(&ledger);
+        }
+
+        let mut remakes = VecDeque::new();
+        for entry in &self.audit {
+            match entry.event {
+                AuditEvent::Requeued => remakes.push_back(entry.order),
+                AuditEvent::Remade => remakes.retain(|&id| id != entry.order),
+                _ => {}
+            }
+        }
+        let placed = |id: &OrderId| {
+            self.orders
+                .get(id)
+                .is_some_and(|order| order.status() == Status::Placed)
+        };
+        remakes.retain(placed);
+        self.kitchen = self
+            .orders
+            .keys()
+            .copied()
+            .filter(|id| placed(id) && !remakes.contains(id))
+            .collect();
+        self.remakes = remakes;
+
+        // carry on numbering from the highest id that was saved, so nothing is handed out twice
+        self.last_order = self.orders.keys().max().copied().unwrap_or(0);
+        let seated = self
+            .seating
+            .tables
+            .iter()
+            .filter_map(|table| table.occupant)
+            .map(|occupant| occupant.party);
+        let waiting = self.seating.waitlist.iter().map(|party| party.id);
+        self.seating.last_party = seated
+            .chain(waiting)
+            .max()
+            .unwrap_or(0)
+            .max(self.seating.last_party);
+        Ok(())
+    }
+
+    pub(crate) fn order_mut(&mut self, id: OrderId) -> Result<&mut Order, OrderError> {
+        self.orders.get_mut(&id).ok_or(OrderError::UnknownOrder(id))
+    }
+
+    pub(crate) fn next_order_id(&mut self) -> OrderId {
+        self.last_order += 1;
+        self.last_order
+    }
+}
+
+impl Default for Restaurant {
+    // a restaurant with the standard menu and a small dining room
+    fn default() -> Restaurant {
+        let mut restaurant = Restaurant::new(Menu::standard());
+        for capacity in [2, 2, 4, 4, 4, 6] {
+            hosting::add_table(&mut restaurant, capacity);
+        }
+        restaurant
+    }
+}
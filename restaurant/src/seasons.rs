@@ -0,0 +1,236 @@
+// which season it is, what fruit comes with breakfast in each one, and which dishes the kitchen can make when
+// the restaurant ships with the settings in seasons.toml next to Cargo.toml, see SeasonalMenu::standard;
+// a different file can be loaded with SeasonalMenu::load
+use crate::menu::Dish;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+        Season::Winter,
+    ];
+}
+
+impl fmt::Display for Season {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Autumn => "autumn",
+            Season::Winter => "winter",
+        })
+    }
+}
+
+// a day of the year, without the year; seasons start on the same day every year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MonthDay {
+    month: u32,
+    day: u32,
+}
+
+impl MonthDay {
+    // None if there's no such day, February 29th counts since it comes round every few years
+    pub fn new(month: u32, day: u32) -> Option<MonthDay> {
+        let days = match month {
+            2 => 29,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        (1..=days).contains(&day).then_some(MonthDay { month, day })
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+}
+
+impl fmt::Display for MonthDay {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:02}-{:02}", self.month, self.day)
+    }
+}
+
+// written as "MM-DD", e.g. "06-21"
+impl FromStr for MonthDay {
+    type Err = SeasonError;
+
+    fn from_str(s: &str) -> Result<MonthDay, SeasonError> {
+        let bad_date = || SeasonError::BadDate(s.to_string());
+        let (month, day) = s.trim().split_once('-').ok_or_else(bad_date)?;
+        let month = month.parse().map_err(|_| bad_date())?;
+        let day = day.parse().map_err(|_| bad_date())?;
+        MonthDay::new(month, day).ok_or_else(bad_date)
+    }
+}
+
+#[derive(Debug)]
+pub enum SeasonError {
+    Io(io::Error),
+    Toml(toml::de::Error),
+    BadDate(String),
+    // every season needs a start date and a fruit
+    MissingSeason(Season),
+    // two seasons can't start on the same day
+    SameStart(MonthDay),
+    UnknownDish(String),
+}
+
+impl fmt::Display for SeasonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeasonError::Io(err) => write!(f, "couldn't read the seasonal menu: {err}"),
+            SeasonError::Toml(err) => write!(f, "the seasonal menu isn't valid: {err}"),
+            SeasonError::BadDate(date) => {
+                write!(f, "{date:?} is not a date, dates are written as MM-DD")
+            }
+            SeasonError::MissingSeason(season) => write!(f, "the seasonal menu has no {season}"),
+            SeasonError::SameStart(date) => write!(f, "two seasons start on {date}"),
+            SeasonError::UnknownDish(dish) => write!(f, "{dish:?} is not a dish"),
+        }
+    }
+}
+
+impl std::error::Error for SeasonError {}
+
+impl From<io::Error> for SeasonError {
+    fn from(err: io::Error) -> SeasonError {
+        SeasonError::Io(err)
+    }
+}
+
+impl From<toml::de::Error> for SeasonError {
+    fn from(err: toml::de::Error) -> SeasonError {
+        SeasonError::Toml(err)
+    }
+}
+
+// the layout of the TOML file, before any of it is checked
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeasonsFile {
+    seasons: BTreeMap<Season, SeasonEntry>,
+    #[serde(default)]
+    availability: BTreeMap<String, Vec<Season>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeasonEntry {
+    starts: String,
+    fruit: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeasonalMenu {
+    // when each season starts, sorted by date
+    starts: Vec<(MonthDay, Season)>,
+    fruit: BTreeMap<Season, String>,
+    // the seasons each dish can be made in; dishes that aren't listed can be made all year
+    availability: BTreeMap<Dish, Vec<Season>>,
+}
+
+impl SeasonalMenu {
+    // the settings in seasons.toml, which are built into the library so they're always there
+    pub fn standard() -> &'static SeasonalMenu {
+        static STANDARD: OnceLock<SeasonalMenu> = OnceLock::new();
+        STANDARD.get_or_init(|| {
+            SeasonalMenu::from_toml(include_str!("../seasons.toml"))
+                .expect("seasons.toml is a valid seasonal menu")
+        })
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<SeasonalMenu, SeasonError> {
+        SeasonalMenu::from_toml(&fs::read_to_string(path)?)
+    }
+
+    pub fn from_toml(text: &str) -> Result<SeasonalMenu, SeasonError> {
+        let file: SeasonsFile = toml::from_str(text)?;
+        let mut starts = Vec::new();
+        let mut fruit = BTreeMap::new();
+        for season in Season::ALL {
+            let entry = file
+                .seasons
+                .get(&season)
+                .ok_or(SeasonError::MissingSeason(season))?;
+            starts.push((entry.starts.parse()?, season));
+            fruit.insert(season, entry.fruit.clone());
+        }
+        starts.sort();
+        if let Some(pair) = starts.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+            return Err(SeasonError::SameStart(pair[0].0));
+        }
+        let mut availability = BTreeMap::new();
+        for (dish, seasons) in file.availability {
+            let dish = dish
+                .parse()
+                .map_err(|_| SeasonError::UnknownDish(dish.clone()))?;
+            availability.insert(dish, seasons);
+        }
+        Ok(SeasonalMenu {
+            starts,
+            fruit,
+            availability,
+        })
+    }
+
+    // the season a day falls in
+    // days before the first start date of the year are still in the season that started late the year before
+    pub fn season_on(&self, date: MonthDay) -> Season {
+        self.starts
+            .iter()
+            .rev()
+            .find(|&&(start, _)| start <= date)
+            .or(self.starts.last())
+            .map(|&(_, season)| season)
+            .expect("a seasonal menu always has all four seasons")
+    }
+
+    pub fn starts(&self, season: Season) -> MonthDay {
+        self.starts
+            .iter()
+            .find(|&&(_, other)| other == season)
+            .map(|&(start, _)| start)
+            .expect("a seasonal menu always has all four seasons")
+    }
+
+    // the fruit that comes with breakfast in this season
+    pub fn fruit(&self, season: Season) -> &str {
+        &self.fruit[&season]
+    }
+
+    pub fn is_available(&self, dish: Dish, season: Season) -> bool {
+        self.availability
+            .get(&dish)
+            .is_none_or(|seasons| seasons.contains(&season))
+    }
+}
+
+impl Default for SeasonalMenu {
+    fn default() -> SeasonalMenu {
+        SeasonalMenu::standard().clone()
+    }
+}
@@ -0,0 +1,438 @@
+// a whole service played out on a virtual clock, to try out staffing and table layouts without real customers
+// parties arrive at random, wait for a table (or give up), order, wait for the kitchen, eat and pay, all through the same
+// front_of_house and back_of_house functions the real restaurant uses
+// the clock jumps from one event to the next instead of ticking, so a four hour service takes a few milliseconds, and the
+// same seed always plays out exactly the same way
+use crate::billing::{Payment, Rate};
+use crate::front_of_house::hosting::{self, Event, HostingError, PartyId, Priority, Time};
+use crate::front_of_house::serving;
+use crate::menu::{dollars, Cents, Dish, Item, Menu};
+use crate::order::OrderId;
+use crate::seasons::Season;
+use crate::{back_of_house, Restaurant};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fmt;
+
+// parties arrive at least a minute apart, so this is as busy as the door gets
+pub const MAX_ARRIVALS_PER_HOUR: f64 = 60.0;
+
+// the toast a simulated diner picks for their breakfast
+const TOASTS: [&str; 4] = ["white", "wheat", "rye", "sourdough"];
+
+// everything about the service being simulated
+// ranges are inclusive, and every time is in minutes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimConfig {
+    pub seed: u64,
+    // the capacity of each table on the floor
+    pub tables: Vec<u32>,
+    // how many orders the kitchen can cook at the same time
+    pub cooks: u32,
+    pub menu: Menu,
+    pub season: Season,
+    // how long the doors are open; parties already inside when they close still finish their meal
+    pub open_for: Time,
+    // on average, spread out at random, and at most MAX_ARRIVALS_PER_HOUR
+    pub arrivals_per_hour: f64,
+    // every party has at least one person in it, so the smallest size can't be 0
+    pub party_size: (u32, u32),
+    // how long a party waits for a table before walking out
+    pub patience: Time,
+    pub cook_time: (Time, Time),
+    // from the food arriving to paying and leaving
+    pub eat_time: (Time, Time),
+    pub tip: Rate,
+}
+
+impl Default for SimConfig {
+    // a four hour service in the default dining room
+    fn default() -> SimConfig {
+        SimConfig {
+            seed: 0,
+            tables: vec![2, 2, 4, 4, 4, 6],
+            cooks: 2,
+            menu: Menu::standard(),
+            season: Season::Summer,
+            open_for: 4 * 60,
+            arrivals_per_hour: 6.0,
+            party_size: (1, 6),
+            patience: 30,
+            cook_time: (8, 15),
+            eat_time: (25, 50),
+            tip: Rate::percent(18).expect("18% is a rate"),
+        }
+    }
+}
+
+impl SimConfig {
+    // whether this is a service that can be played out
+    pub fn validate(&self) -> Result<(), SimError> {
+        if self.cooks == 0 {
+            return Err(SimError::NoCooks);
+        }
+        if self.tables.is_empty() {
+            return Err(SimError::NoTables);
+        }
+        if let Some(index) = self.tables.iter().position(|&capacity| capacity == 0) {
+            return Err(SimError::EmptyTable(index));
+        }
+        if self.party_size.0 == 0 {
+            return Err(SimError::EmptyParty);
+        }
+        let rate = self.arrivals_per_hour;
+        if !(0.0..=MAX_ARRIVALS_PER_HOUR).contains(&rate) {
+            return Err(SimError::ArrivalRate(rate));
+        }
+        Ok(())
+    }
+}
+
+// why a service can't be simulated
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SimError {
+    // nobody to cook the orders
+    NoCooks,
+    // nowhere to seat anyone
+    NoTables,
+    // the table at this index in tables has no seats
+    EmptyTable(usize),
+    // party_size lets a party have nobody in it
+    EmptyParty,
+    // negative, not a number, or more than MAX_ARRIVALS_PER_HOUR
+    ArrivalRate(f64),
+}
+
+impl fmt::Display for SimError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SimError::NoCooks => f.write_str("the kitchen needs at least one cook"),
+            SimError::NoTables => f.write_str("the dining room needs at least one table"),
+            SimError::EmptyTable(index) => write!(f, "table {index} has no seats"),
+            SimError::EmptyParty => f.write_str("a party needs at least one person"),
+            SimError::ArrivalRate(rate) => write!(
+                f,
+                "parties can arrive between 0 and {MAX_ARRIVALS_PER_HOUR} times an hour, not {rate}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SimError {}
+
+// what happened over a simulated service
+#[derive(Debug, Clone, PartialEq)]
+pub struct SimReport {
+    pub arrived: u32,
+    pub seated: u32,
+    // gave up waiting for a table
+    pub walked_out: u32,
+    // too big to ever seat, so they were turned away at the door
+    pub turned_away: u32,
+    // in minutes, over the parties that got a table
+    pub average_wait: f64,
+    pub longest_wait: Time,
+    // how many times each table was sat at, on average
+    pub table_turnover: f64,
+    // the share of the service the cooks spent cooking, from 0.0 to 1.0
+    pub kitchen_utilisation: f64,
+    // everything diners paid, tax and tips included
+    pub revenue: Cents,
+    // revenue taken in each hour since opening, by when the bill was paid
+    pub revenue_per_hour: Vec<Cents>,
+    // when the last party left, which is when the service was really over
+    pub closed_at: Time,
+}
+
+impl fmt::Display for SimReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} parties arrived: {} seated, {} walked out, {} turned away",
+            self.arrived, self.seated, self.walked_out, self.turned_away
+        )?;
+        writeln!(
+            f,
+            "average wait {:.1} minutes, longest {} minutes",
+            self.average_wait, self.longest_wait
+        )?;
+        writeln!(f, "table turnover {:.2}", self.table_turnover)?;
+        writeln!(
+            f,
+            "kitchen utilisation {:.1}%",
+            self.kitchen_utilisation * 100.0
+        )?;
+        writeln!(
+            f,
+            "revenue {}, last party left after {}:{:02}",
+            dollars(self.revenue),
+            self.closed_at / 60,
+            self.closed_at % 60
+        )?;
+        for (hour, revenue) in self.revenue_per_hour.iter().enumerate() {
+            writeln!(f, "  hour {:<3}{:>10}", hour + 1, dollars(*revenue))?;
+        }
+        Ok(())
+    }
+}
+
+// the things that can happen, ordered by when they happen
+// ties are broken by the order they were scheduled in, so nothing depends on how the enum happens to sort
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SimEvent {
+    Arrival,
+    GiveUp(PartyId),
+    // the kitchen finished cooking an order and it goes out to the table
+    Cooked(OrderId),
+    // a party finished eating, pays and leaves
+    Leave(PartyId),
+}
+
+struct Simulation<'a> {
+    config: &'a SimConfig,
+    rng: StdRng,
+    restaurant: Restaurant,
+    now: Time,
+    // (when, scheduled, event), smallest first
+    events: BinaryHeap<Reverse<(Time, u64, SimEvent)>>,
+    scheduled: u64,
+    // how many people are in each party, since the restaurant forgets once they're seated
+    sizes: BTreeMap<PartyId, u32>,
+    // each seated party's order
+    orders: BTreeMap<PartyId, OrderId>,
+    parties: BTreeMap<OrderId, PartyId>,
+    // orders the kitchen is working on right now
+    cooking: u32,
+    cook_minutes: u64,
+    waits: Vec<Time>,
+    seatings: u32,
+    report: SimReport,
+}
+
+// play out a whole service, as long as the config makes sense
+pub fn simulate(config: &SimConfig) -> Result<SimReport, SimError> {
+    config.validate()?;
+    let mut restaurant = Restaurant::new(config.menu.clone());
+    for &capacity in &config.tables {
+        hosting::add_table(&mut restaurant, capacity);
+    }
+    restaurant.set_season(config.season);
+    // a party stays for about an average cooking time plus an average meal
+    let (cook, eat) = (config.cook_time, config.eat_time);
+    restaurant.set_expected_stay((cook.0 + cook.1) / 2 + (eat.0 + eat.1) / 2);
+
+    let mut simulation = Simulation {
+        config,
+        rng: StdRng::seed_from_u64(config.seed),
+        restaurant,
+        now: 0,
+        events: BinaryHeap::new(),
+        scheduled: 0,
+        sizes: BTreeMap::new(),
+        orders: BTreeMap::new(),
+        parties: BTreeMap::new(),
+        cooking: 0,
+        cook_minutes: 0,
+        waits: Vec::new(),
+        seatings: 0,
+        report: SimReport {
+            arrived: 0,
+            seated: 0,
+            walked_out: 0,
+            turned_away: 0,
+            average_wait: 0.0,
+            longest_wait: 0,
+            table_turnover: 0.0,
+            kitchen_utilisation: 0.0,
+            revenue: 0,
+            revenue_per_hour: vec![0; config.open_for.div_ceil(60) as usize],
+            closed_at: 0,
+        },
+    };
+    simulation.schedule_arrival();
+    simulation.run();
+    Ok(simulation.finish())
+}
+
+impl Simulation<'_> {
+    fn run(&mut self) {
+        while let Some(Reverse((time, _, event))) = self.events.pop() {
+            self.now = time;
+            match event {
+                SimEvent::Arrival => self.arrive(),
+                SimEvent::GiveUp(party) => {
+                    // the party only gives up if they're still waiting, by now they may well be eating
+                    if self
+                        .restaurant
+                        .waitlist()
+                        .iter()
+                        .any(|waiting| waiting.id == party)
+                    {
+                        hosting::party_left(&mut self.restaurant, party)
+                            .expect("the party is on the waitlist");
+                        self.report.walked_out += 1;
+                    }
+                }
+                SimEvent::Cooked(order) => {
+                    serving::serve_order(&mut self.restaurant, order)
+                        .expect("the kitchen cooked the order");
+                    self.cooking -= 1;
+                    let eat_time = self.between(self.config.eat_time);
+                    self.schedule(eat_time, SimEvent::Leave(self.parties[&order]));
+                    self.start_cooking();
+                }
+                SimEvent::Leave(party) => {
+                    let order = self.orders[&party];
+                    let payment = Payment {
+                        tip: self.config.tip,
+                        ..Payment::default()
+                    };
+                    let bill = serving::take_payment(&mut self.restaurant, order, &payment)
+                        .expect("the order was served");
+                    self.report.revenue += bill.total;
+                    let hour = (self.now / 60) as usize;
+                    if hour >= self.report.revenue_per_hour.len() {
+                        self.report.revenue_per_hour.resize(hour + 1, 0);
+                    }
+                    self.report.revenue_per_hour[hour] += bill.total;
+                    hosting::party_left(&mut self.restaurant, party).expect("the party was seated");
+                    self.report.closed_at = self.now;
+                    self.seat();
+                }
+            }
+        }
+    }
+
+    fn arrive(&mut self) {
+        self.report.arrived += 1;
+        let size = self.between(self.config.party_size);
+        let name = format!("party {}", self.report.arrived);
+        match hosting::add_to_waitlist(
+            &mut self.restaurant,
+            &name,
+            size,
+            Priority::Normal,
+            self.now,
+        ) {
+            Ok(party) => {
+                self.sizes.insert(party, size);
+                self.schedule(self.config.patience, SimEvent::GiveUp(party));
+                self.seat();
+            }
+            Err(HostingError::PartyTooLarge { .. }) => self.report.turned_away += 1,
+            Err(err) => panic!("the config was validated, so parties have someone in them: {err}"),
+        }
+        self.schedule_arrival();
+    }
+
+    // the next party arrives a random time from now, as long as the doors are still open then
+    // the gaps between arrivals are exponentially distributed, which is what arrivals at a steady average rate look like
+    // a gap is always at least a minute, so the clock moves on however busy the door is
+    fn schedule_arrival(&mut self) {
+        if self.config.arrivals_per_hour <= 0.0 {
+            return;
+        }
+        let gap = -(1.0 - self.rng.gen::<f64>()).ln() * 60.0 / self.config.arrivals_per_hour;
+        let gap = (gap.round() as Time).max(1);
+        if self.now.saturating_add(gap) < self.config.open_for {
+            self.schedule(gap, SimEvent::Arrival);
+        }
+    }
+
+    // seat everyone who fits, and send their orders to the kitchen
+    fn seat(&mut self) {
+        let seated = hosting::seat_at_table(&mut self.restaurant, self.now);
+        for event in self.restaurant.take_events() {
+            if let Event::Seated { tables, waited, .. } = event {
+                self.waits.push(waited);
+                self.seatings += tables.len() as u32;
+            }
+        }
+        for party in seated {
+            self.report.seated += 1;
+            // everyone at the table orders one thing, and if there's nothing to order the party gets up and goes again
+            let items: Option<Vec<Item>> =
+                (0..self.sizes[&party]).map(|_| self.pick_item()).collect();
+            let Some(items) = items else {
+                hosting::party_left(&mut self.restaurant, party)
+                    .expect("the party was just seated");
+                continue;
+            };
+            let order = serving::take_order(&mut self.restaurant, items)
+                .expect("only dishes in season are ordered");
+            self.orders.insert(party, order);
+            self.parties.insert(order, party);
+        }
+        self.start_cooking();
+    }
+
+    // a dish the kitchen will make this season, picked at random, None if there aren't any
+    fn pick_item(&mut self) -> Option<Item> {
+        let season = self.restaurant.season();
+        let dishes: Vec<Dish> = self
+            .restaurant
+            .menu()
+            .items()
+            .iter()
+            .map(|item| item.dish)
+            .filter(|&dish| self.restaurant.seasonal_menu().is_available(dish, season))
+            .collect();
+        if dishes.is_empty() {
+            return None;
+        }
+        Some(match dishes[self.rng.gen_range(0..dishes.len())] {
+            Dish::Breakfast => {
+                let toast = TOASTS[self.rng.gen_range(0..TOASTS.len())];
+                Item::Breakfast(self.restaurant.breakfast(toast))
+            }
+            Dish::Appetizer(appetizer) => Item::Appetizer(appetizer),
+        })
+    }
+
+    // give every free cook an order, if there are any waiting
+    // cook_order moves an order straight to ready, so the simulation holds on to it until its cooking time is up
+    fn start_cooking(&mut self) {
+        while self.cooking < self.config.cooks {
+            let Some(order) = back_of_house::cook_order(&mut self.restaurant) else {
+                break;
+            };
+            self.cooking += 1;
+            let cook_time = self.between(self.config.cook_time);
+            self.cook_minutes += u64::from(cook_time);
+            self.schedule(cook_time, SimEvent::Cooked(order));
+        }
+    }
+
+    fn schedule(&mut self, after: Time, event: SimEvent) {
+        self.scheduled += 1;
+        self.events.push(Reverse((
+            self.now.saturating_add(after),
+            self.scheduled,
+            event,
+        )));
+    }
+
+    fn between(&mut self, (low, high): (u32, u32)) -> u32 {
+        self.rng.gen_range(low..=high.max(low))
+    }
+
+    fn finish(mut self) -> SimReport {
+        let report = &mut self.report;
+        if !self.waits.is_empty() {
+            let total: u64 = self.waits.iter().map(|&wait| u64::from(wait)).sum();
+            report.average_wait = total as f64 / self.waits.len() as f64;
+            report.longest_wait = self.waits.iter().copied().max().unwrap_or(0);
+        }
+        if !self.config.tables.is_empty() {
+            report.table_turnover = f64::from(self.seatings) / self.config.tables.len() as f64;
+        }
+        let cook_capacity =
+            u64::from(self.config.cooks) * u64::from(report.closed_at.max(self.config.open_for));
+        if cook_capacity > 0 {
+            report.kitchen_utilisation = self.cook_minutes as f64 / cook_capacity as f64;
+        }
+        self.report
+    }
+}
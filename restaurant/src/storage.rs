@@ -0,0 +1,438 @@
+// keeping the restaurant's state somewhere it survives a restart
+// Restaurant::save hands everything to a Storage and Restaurant::restore reads it back, so swapping MemoryStorage for
+// JsonLinesStorage is all it takes to go from a restaurant that forgets everything to one that doesn't
+// settings that come from code or config files, like tax, coupons and the seasonal menu, aren't stored
+use crate::billing::Bill;
+use crate::corrections::AuditEntry;
+use crate::front_of_house::hosting::{Party, Table};
+use crate::inventory::StockEvent;
+use crate::menu::Menu;
+use crate::order::{Order, OrderId};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+// bump this whenever the layout of a record changes, and teach migrate() how to bring the previous version forward
+pub const SCHEMA_VERSION: u64 = 2;
+
+// somewhere to keep menus, orders, the waitlist, payments and stock
+// every save replaces whatever was saved before for the same thing: the menu, the order with that id, the whole
+// seating plan, the payment for that order, the whole audit trail, or the whole stock ledger
+pub trait Storage {
+    fn save_menu(&mut self, menu: &Menu) -> Result<(), StorageError>;
+    fn save_order(&mut self, order: &Order) -> Result<(), StorageError>;
+    // the tables and waitlist are saved together since a party moves from one to the other
+    fn save_seating(&mut self, tables: &[Table], waitlist: &[Party]) -> Result<(), StorageError>;
+    fn save_payment(&mut self, bill: &Bill) -> Result<(), StorageError>;
+    fn save_audit_trail(&mut self, audit: &[AuditEntry]) -> Result<(), StorageError>;
+    fn save_stock_ledger(&mut self, ledger: &[StockEvent]) -> Result<(), StorageError>;
+    // the latest of everything that has been saved
+    fn load(&self) -> Result<Saved, StorageError>;
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Saved {
+    pub menu: Option<Menu>,
+    pub orders: BTreeMap<OrderId, Order>,
+    // None until the seating plan has been saved at least once
+    pub seating: Option<(Vec<Table>, Vec<Party>)>,
+    pub payments: BTreeMap<OrderId, Bill>,
+    pub audit: Vec<AuditEntry>,
+    // None until the stock ledger has been saved at least once
+    pub stock: Option<Vec<StockEvent>>,
+}
+
+#[derive(Debug)]
+pub enum StorageError {
+    Io(io::Error),
+    // line numbers start at 1, like in an editor
+    Corrupt { line: usize, err: serde_json::Error },
+    MissingVersion,
+    UnsupportedVersion(u64),
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StorageError::Io(err) => write!(f, "couldn't read or write the saved state: {err}"),
+            StorageError::Corrupt { line, err } => {
+                write!(f, "line {line} of the saved state is corrupt: {err}")
+            }
+            StorageError::MissingVersion => f.write_str("the saved state has no schema version"),
+            StorageError::UnsupportedVersion(version) => write!(
+                f,
+                "the saved state uses schema version {version}, but this build only understands up to {SCHEMA_VERSION}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+impl From<io::Error> for StorageError {
+    fn from(err: io::Error) -> StorageError {
+        StorageError::Io(err)
+    }
+}
+
+// keeps everything in memory, so it's gone when the program exits
+// handy for tests, and for anything that wants to snapshot a restaurant and put it back later
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    saved: Saved,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn save_menu(&mut self, menu: &Menu) -> Result<(), StorageError> {
+        self.saved.menu = Some(menu.clone());
+        Ok(())
+    }
+
+    fn save_order(&mut self, order: &Order) -> Result<(), StorageError> {
+        self.saved.orders.insert(order.id(), order.clone());
+        Ok(())
+    }
+
+    fn save_seating(&mut self, tables: &[Table], waitlist: &[Party]) -> Result<(), StorageError> {
+        self.saved.seating = Some((tables.to_vec(), waitlist.to_vec()));
+        Ok(())
+    }
+
+    fn save_payment(&mut self, bill: &Bill) -> Result<(), StorageError> {
+        self.saved.payments.insert(bill.order, bill.clone());
+        Ok(())
+    }
+
+    fn save_audit_trail(&mut self, audit: &[AuditEntry]) -> Result<(), StorageError> {
+        self.saved.audit = audit.to_vec();
+        Ok(())
+    }
+
+    fn save_stock_ledger(&mut self, ledger: &[StockEvent]) -> Result<(), StorageError> {
+        self.saved.stock = Some(ledger.to_vec());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Saved, StorageError> {
+        Ok(self.saved.clone())
+    }
+}
+
+// one line of a JSON lines file
+// the audit trail and stock ledger only ever grow, so most saves append just the entries that are new with
+// AuditAdded and StockAdded instead of writing the whole list out again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Record {
+    Menu(Menu),
+    Order(Order),
+    Seating {
+        tables: Vec<Table>,
+        waitlist: Vec<Party>,
+    },
+    Payment(Bill),
+    Audit(Vec<AuditEntry>),
+    #[serde(rename = "audit_added")]
+    AuditAdded(Vec<AuditEntry>),
+    Stock(Vec<StockEvent>),
+    #[serde(rename = "stock_added")]
+    StockAdded(Vec<StockEvent>),
+}
+
+// the first line of a JSON lines file
+#[derive(Debug, Serialize, Deserialize)]
+struct Header {
+    version: u64,
+}
+
+// how many replaced records a file can pile up, on top of one for every record that's still needed, before it's
+// compacted, so a small restaurant isn't rewriting its file on every save
+const COMPACT_SLACK: usize = 64;
+
+// keeps everything in a file with one JSON record per line, after a header line with the schema version
+// saving only appends a line for something that actually changed, so a crash can at worst lose the line being
+// written; loading reads the whole file and keeps the last record for each thing
+// once replaced records outnumber the ones still needed the file is compacted, so it stays in proportion to the
+// restaurant instead of to how many times it was saved
+#[derive(Debug, Clone)]
+pub struct JsonLinesStorage {
+    path: PathBuf,
+    // what the file holds, so a save can tell what has changed since the last one
+    saved: Saved,
+    // how many records there are in the file after the header
+    lines: usize,
+}
+
+impl JsonLinesStorage {
+    // use the file at path, creating it if it doesn't exist yet
+    // a file from an older schema version, or one that ends partway through a record, is rewritten straight away, so
+    // new records never go after an old header or get stuck to the end of a half-written line
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<JsonLinesStorage, StorageError> {
+        let mut storage = JsonLinesStorage {
+            path: path.as_ref().to_path_buf(),
+            saved: Saved::default(),
+            lines: 0,
+        };
+        match fs::read_to_string(&storage.path) {
+            Ok(contents) if !contents.trim().is_empty() => {
+                storage.saved = read(&contents)?;
+                if version(&contents)? != SCHEMA_VERSION || !contents.ends_with('\n') {
+                    storage.compact()?;
+                } else {
+                    storage.lines = contents
+                        .lines()
+                        .skip(1)
+                        .filter(|line| !line.trim().is_empty())
+                        .count();
+                    storage.compact_if_stale()?;
+                }
+            }
+            Ok(_) => storage.rewrite(&[])?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => storage.rewrite(&[])?,
+            Err(err) => return Err(err.into()),
+        }
+        Ok(storage)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // rewrite the file with only the latest record for each thing
+    pub fn compact(&mut self) -> Result<(), StorageError> {
+        let saved = self.saved.clone();
+        let mut records = Vec::new();
+        records.extend(saved.menu.map(Record::Menu));
+        records.extend(saved.orders.into_values().map(Record::Order));
+        records.extend(
+            saved
+                .seating
+                .map(|(tables, waitlist)| Record::Seating { tables, waitlist }),
+        );
+        records.extend(saved.payments.into_values().map(Record::Payment));
+        if !saved.audit.is_empty() {
+            records.push(Record::Audit(saved.audit));
+        }
+        records.extend(saved.stock.map(Record::Stock));
+        self.rewrite(&records)?;
+        self.lines = records.len();
+        Ok(())
+    }
+
+    // how many records a compacted file would have
+    fn needed(&self) -> usize {
+        usize::from(self.saved.menu.is_some())
+            + self.saved.orders.len()
+            + usize::from(self.saved.seating.is_some())
+            + self.saved.payments.len()
+            + usize::from(!self.saved.audit.is_empty())
+            + usize::from(self.saved.stock.is_some())
+    }
+
+    // compacting only once the replaced records outnumber the rest means each compaction is paid for by at least as
+    // many appends, so the total written stays in proportion to what was saved
+    fn compact_if_stale(&mut self) -> Result<(), StorageError> {
+        if self.lines > 2 * self.needed() + COMPACT_SLACK {
+            self.compact()?;
+        }
+        Ok(())
+    }
+
+    // replace the file with a header and these records
+    // the new contents go to a temporary file first so a crash halfway through can't leave a half-written file behind
+    fn rewrite(&self, records: &[Record]) -> Result<(), StorageError> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut contents = to_line(&Header {
+            version: SCHEMA_VERSION,
+        });
+        for record in records {
+            contents.push_str(&to_line(record));
+        }
+        let tmp = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp, contents)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    fn append(&mut self, record: Record) -> Result<(), StorageError> {
+        let mut file = OpenOptions::new().append(true).open(&self.path)?;
+        file.write_all(to_line(&record).as_bytes())?;
+        self.lines += 1;
+        Ok(())
+    }
+}
+
+impl Storage for JsonLinesStorage {
+    fn save_menu(&mut self, menu: &Menu) -> Result<(), StorageError> {
+        if self.saved.menu.as_ref() == Some(menu) {
+            return Ok(());
+        }
+        self.append(Record::Menu(menu.clone()))?;
+        self.saved.menu = Some(menu.clone());
+        self.compact_if_stale()
+    }
+
+    fn save_order(&mut self, order: &Order) -> Result<(), StorageError> {
+        if self.saved.orders.get(&order.id()) == Some(order) {
+            return Ok(());
+        }
+        self.append(Record::Order(order.clone()))?;
+        self.saved.orders.insert(order.id(), order.clone());
+        self.compact_if_stale()
+    }
+
+    fn save_seating(&mut self, tables: &[Table], waitlist: &[Party]) -> Result<(), StorageError> {
+        let seating = (tables.to_vec(), waitlist.to_vec());
+        if self.saved.seating.as_ref() == Some(&seating) {
+            return Ok(());
+        }
+        self.append(Record::Seating {
+            tables: seating.0.clone(),
+            waitlist: seating.1.clone(),
+        })?;
+        self.saved.seating = Some(seating);
+        self.compact_if_stale()
+    }
+
+    fn save_payment(&mut self, bill: &Bill) -> Result<(), StorageError> {
+        if self.saved.payments.get(&bill.order) == Some(bill) {
+            return Ok(());
+        }
+        self.append(Record::Payment(bill.clone()))?;
+        self.saved.payments.insert(bill.order, bill.clone());
+        self.compact_if_stale()
+    }
+
+    fn save_audit_trail(&mut self, audit: &[AuditEntry]) -> Result<(), StorageError> {
+        let before = self.saved.audit.as_slice();
+        let record = match audit.strip_prefix(before) {
+            Some([]) => return Ok(()),
+            Some(added) => Record::AuditAdded(added.to_vec()),
+            None => Record::Audit(audit.to_vec()),
+        };
+        self.append(record)?;
+        self.saved.audit = audit.to_vec();
+        self.compact_if_stale()
+    }
+
+    fn save_stock_ledger(&mut self, ledger: &[StockEvent]) -> Result<(), StorageError> {
+        let record = match self
+            .saved
+            .stock
+            .as_deref()
+            .map(|before| ledger.strip_prefix(before))
+        {
+            Some(Some([])) => return Ok(()),
+            Some(Some(added)) => Record::StockAdded(added.to_vec()),
+            _ => Record::Stock(ledger.to_vec()),
+        };
+        self.append(record)?;
+        self.saved.stock = Some(ledger.to_vec());
+        self.compact_if_stale()
+    }
+
+    fn load(&self) -> Result<Saved, StorageError> {
+        read(&fs::read_to_string(&self.path)?)
+    }
+}
+
+// everything in a JSON lines file, keeping the last record for each thing
+fn read(contents: &str) -> Result<Saved, StorageError> {
+    let version = version(contents)?;
+    let mut saved = Saved::default();
+    for (index, line) in complete(contents).lines().enumerate().skip(1) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let corrupt = |err| StorageError::Corrupt {
+            line: index + 1,
+            err,
+        };
+        let value: Value = serde_json::from_str(line).map_err(corrupt)?;
+        let record: Record = serde_json::from_value(migrate(value, version)?).map_err(corrupt)?;
+        match record {
+            Record::Menu(menu) => saved.menu = Some(menu),
+            Record::Order(order) => {
+                saved.orders.insert(order.id(), order);
+            }
+            Record::Seating { tables, waitlist } => saved.seating = Some((tables, waitlist)),
+            Record::Payment(bill) => {
+                saved.payments.insert(bill.order, bill);
+            }
+            Record::Audit(audit) => saved.audit = audit,
+            Record::AuditAdded(added) => saved.audit.extend(added),
+            Record::Stock(ledger) => saved.stock = Some(ledger),
+            Record::StockAdded(added) => saved.stock.get_or_insert_with(Vec::new).extend(added),
+        }
+    }
+    Ok(saved)
+}
+
+// the file up to the end of its last line
+// a record is only there once its newline has been written, anything after the last one is a save that was cut short
+// by a crash or a full disk, and the state from before that save is the best there is
+fn complete(contents: &str) -> &str {
+    match contents.rfind('\n') {
+        Some(end) => &contents[..=end],
+        // nothing but the header, which has to be whole or there's no telling which version the file is
+        None => contents,
+    }
+}
+
+fn to_line<T: Serialize>(value: &T) -> String {
+    let mut line = serde_json::to_string(value).expect("saved state is always serializable");
+    line.push('\n');
+    line
+}
+
+// the schema version from the header line
+fn version(contents: &str) -> Result<u64, StorageError> {
+    let header = contents.lines().next().unwrap_or_default();
+    let header: Value =
+        serde_json::from_str(header).map_err(|err| StorageError::Corrupt { line: 1, err })?;
+    let version = header
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(StorageError::MissingVersion)?;
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(StorageError::UnsupportedVersion(version));
+    }
+    Ok(version)
+}
+
+// bring a record written with an older schema up to SCHEMA_VERSION, one version at a time
+fn migrate(mut value: Value, version: u64) -> Result<Value, StorageError> {
+    match version {
+        SCHEMA_VERSION => Ok(value),
+        // version 1 saved a table's occupant as a [party, since] pair instead of an object
+        1 => {
+            if let Some(tables) = value
+                .pointer_mut("/seating/tables")
+                .and_then(Value::as_array_mut)
+            {
+                for table in tables {
+                    let Some(occupant) = table.get_mut("occupant") else {
+                        continue;
+                    };
+                    if let Some([party, since]) = occupant.as_array().map(Vec::as_slice) {
+                        *occupant = json!({ "party": party, "since": since });
+                    }
+                }
+            }
+            migrate(value, 2)
+        }
+        _ => Err(StorageError::UnsupportedVersion(version)),
+    }
+}
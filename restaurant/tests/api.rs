@@ -0,0 +1,245 @@
+// the HTTP/JSON API over a real socket, the way a front end would talk to it
+#![cfg(feature = "server")]
+use restaurant::api::{self, Request, Server, MAX_HEADER, MAX_HEADERS, MAX_TIP_PERCENT};
+use restaurant::billing::MAX_DINERS;
+use restaurant::{
+    AuditEntry, Bill, Menu, Order, Party, Restaurant, Saved, StockEvent, Storage, StorageError,
+    Table,
+};
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+
+// a server with the default restaurant on a port nobody else is using
+fn start() -> SocketAddr {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("there's a free port");
+    let addr = listener.local_addr().unwrap();
+    thread::spawn(move || api::serve(listener, Server::new(Restaurant::default())));
+    addr
+}
+
+// send one request and get back the status and the JSON body
+fn send(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, Value) {
+    send_raw(
+        addr,
+        &format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        ),
+    )
+}
+
+// send exactly these bytes, for requests send wouldn't make
+fn send_raw(addr: SocketAddr, request: &str) -> (u16, Value) {
+    let mut stream = TcpStream::connect(addr).expect("the server is listening");
+    stream.write_all(request.as_bytes()).unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .expect("the headers end with a blank line");
+    let status = head
+        .split_whitespace()
+        .nth(1)
+        .and_then(|status| status.parse().ok())
+        .expect("the status line has a status");
+    (
+        status,
+        serde_json::from_str(body).expect("the body is JSON"),
+    )
+}
+
+fn error_code(body: &Value) -> &str {
+    body["error"]["code"].as_str().expect("errors have a code")
+}
+
+// a salad that's been taken, cooked and served, ready to pay for
+fn served_salad(addr: SocketAddr) -> u64 {
+    let (status, order) = send(addr, "POST", "/orders", r#"{"items": [{"dish": "salad"}]}"#);
+    assert_eq!(status, 201);
+    let id = order["id"].as_u64().expect("orders have an id");
+    // other tests share nothing with this server, so the kitchen cooks this order
+    assert_eq!(
+        send(addr, "POST", "/kitchen/cook", ""),
+        (200, json!({ "cooked": id }))
+    );
+    assert_eq!(
+        send(addr, "POST", &format!("/orders/{id}/serve"), "").0,
+        200
+    );
+    id
+}
+
+#[test]
+fn an_order_from_taken_to_paid() {
+    let addr = start();
+    let (status, menu) = send(addr, "GET", "/menu", "");
+    assert_eq!(status, 200);
+    assert!(menu["items"]
+        .as_array()
+        .is_some_and(|items| !items.is_empty()));
+
+    let id = served_salad(addr);
+    let (status, order) = send(addr, "GET", &format!("/orders/{id}"), "");
+    assert_eq!(status, 200);
+    assert_eq!(order["status"], "served");
+
+    let (status, paid) = send(
+        addr,
+        "POST",
+        &format!("/orders/{id}/payment"),
+        r#"{"tip_percent": 18, "split": 2}"#,
+    );
+    assert_eq!(status, 201);
+    assert_eq!(paid["bill"]["shares"].as_array().map(Vec::len), Some(2));
+
+    // it can't be paid for twice
+    let (status, body) = send(addr, "POST", &format!("/orders/{id}/payment"), "");
+    assert_eq!((status, error_code(&body)), (409, "wrong_status"));
+}
+
+#[test]
+fn payments_have_to_be_sensible() {
+    let addr = start();
+    let id = served_salad(addr);
+    let pay = |body: &str| send(addr, "POST", &format!("/orders/{id}/payment"), body);
+
+    let (status, body) = pay(&format!(r#"{{"tip_percent": {}}}"#, MAX_TIP_PERCENT + 1));
+    assert_eq!((status, error_code(&body)), (400, "bad_request"));
+    let (status, body) = pay(r#"{"tip_percent": 4294967295}"#);
+    assert_eq!((status, error_code(&body)), (400, "bad_request"));
+
+    let (status, body) = pay(&format!(r#"{{"split": {}}}"#, MAX_DINERS + 1));
+    assert_eq!((status, error_code(&body)), (400, "too_many_diners"));
+    let (status, body) = pay(r#"{"split": 4000000000}"#);
+    assert_eq!((status, error_code(&body)), (400, "too_many_diners"));
+    let diners = vec![vec![0]; MAX_DINERS as usize + 1];
+    let (status, body) = pay(&json!({ "split": diners }).to_string());
+    assert_eq!((status, error_code(&body)), (400, "too_many_diners"));
+
+    // none of that touched the order, so a sensible payment still goes through
+    let (status, _) = pay(&format!(
+        r#"{{"tip_percent": {MAX_TIP_PERCENT}, "split": {MAX_DINERS}}}"#
+    ));
+    assert_eq!(status, 201);
+}
+
+#[test]
+fn requests_that_make_no_sense() {
+    let addr = start();
+    let (status, body) = send(addr, "GET", "/kitchen", "");
+    assert_eq!((status, error_code(&body)), (404, "not_found"));
+    let (status, body) = send(addr, "DELETE", "/menu", "");
+    assert_eq!((status, error_code(&body)), (405, "method_not_allowed"));
+    let (status, body) = send(addr, "POST", "/orders", "{");
+    assert_eq!((status, error_code(&body)), (400, "bad_json"));
+    let (status, body) = send(addr, "GET", "/orders/7", "");
+    assert_eq!((status, error_code(&body)), (404, "unknown_order"));
+    let (status, body) = send(addr, "GET", "/orders/seven", "");
+    assert_eq!((status, error_code(&body)), (400, "bad_request"));
+}
+
+#[test]
+fn the_waitlist() {
+    let addr = start();
+    let (status, body) = send(
+        addr,
+        "POST",
+        "/waitlist",
+        r#"{"name": "Ferris", "size": 0}"#,
+    );
+    assert_eq!((status, error_code(&body)), (422, "empty_party"));
+
+    let (status, body) = send(
+        addr,
+        "POST",
+        "/waitlist",
+        r#"{"name": "Ferris", "size": 2, "priority": "high"}"#,
+    );
+    assert_eq!(status, 201);
+    let party = body["party"].clone();
+    let (status, waitlist) = send(addr, "GET", "/waitlist", "");
+    assert_eq!(status, 200);
+    // a party that was seated straight away isn't waiting any more
+    let waiting = waitlist["parties"]
+        .as_array()
+        .expect("the waitlist is a list")
+        .iter()
+        .any(|waiting| waiting["party"] == party);
+    assert_eq!(waiting, body["seated"] == false);
+}
+
+#[test]
+fn headers_that_go_on_and_on() {
+    let addr = start();
+    let long = "a".repeat(MAX_HEADER);
+    let (status, body) = send_raw(addr, &format!("GET /{long} HTTP/1.1\r\n\r\n"));
+    assert_eq!((status, error_code(&body)), (400, "bad_request"));
+    let (status, body) = send_raw(
+        addr,
+        &format!("GET /menu HTTP/1.1\r\nX-Long: {long}\r\n\r\n"),
+    );
+    assert_eq!((status, error_code(&body)), (431, "headers_too_large"));
+    let many = "X-Again: yes\r\n".repeat(MAX_HEADERS + 1);
+    let (status, body) = send_raw(addr, &format!("GET /menu HTTP/1.1\r\n{many}\r\n"));
+    assert_eq!((status, error_code(&body)), (431, "headers_too_large"));
+
+    // right up to the limits is fine
+    let many = "X-Again: yes\r\n".repeat(MAX_HEADERS);
+    let (status, _) = send_raw(addr, &format!("GET /menu HTTP/1.1\r\n{many}\r\n"));
+    assert_eq!(status, 200);
+    let header = format!("X-Long: {}\r\n", "a".repeat(MAX_HEADER - 10));
+    assert_eq!(header.len(), MAX_HEADER);
+    let (status, _) = send_raw(addr, &format!("GET /menu HTTP/1.1\r\n{header}\r\n"));
+    assert_eq!(status, 200);
+}
+
+// somewhere to save that's always full
+struct FullDisk;
+
+impl FullDisk {
+    fn full<T>() -> Result<T, StorageError> {
+        Err(StorageError::Io(io::Error::other("the disk is full")))
+    }
+}
+
+impl Storage for FullDisk {
+    fn save_menu(&mut self, _: &Menu) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn save_order(&mut self, _: &Order) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn save_seating(&mut self, _: &[Table], _: &[Party]) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn save_payment(&mut self, _: &Bill) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn save_audit_trail(&mut self, _: &[AuditEntry]) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn save_stock_ledger(&mut self, _: &[StockEvent]) -> Result<(), StorageError> {
+        FullDisk::full()
+    }
+    fn load(&self) -> Result<Saved, StorageError> {
+        FullDisk::full()
+    }
+}
+
+#[test]
+fn a_change_that_cant_be_saved_says_it_was_made() {
+    let mut server = Server::new(Restaurant::default()).with_storage(FullDisk);
+    let response = server.handle(&Request {
+        method: String::from("POST"),
+        path: String::from("/orders"),
+        body: String::from(r#"{"items": [{"dish": "salad"}]}"#),
+    });
+    assert_eq!(response.status, 500);
+    assert_eq!(error_code(&response.body), "storage");
+    let message = response.body["error"]["message"].as_str().unwrap();
+    assert!(message.contains("don't send it again"), "{message}");
+    // the order was taken, it just isn't saved anywhere
+    assert_eq!(server.restaurant().orders().count(), 1);
+}
@@ -0,0 +1,205 @@
+// the rounding rules at half a cent, and splits that always add back up to the bill
+use restaurant::back_of_house::cook_order;
+use restaurant::billing::{bill, Share, MAX_DINERS};
+use restaurant::front_of_house::serving::{serve_order, take_order};
+use restaurant::{
+    Appetizer, Bill, Billing, BillingError, Discount, Item, Order, Payment, Rate, Restaurant,
+    Rounding, Split,
+};
+
+// 50% of an odd number of cents always leaves exactly half a cent
+fn half_of(cents: u64, rounding: Rounding) -> u64 {
+    Rate::basis_points(5000).of(cents, rounding)
+}
+
+#[test]
+fn half_up_rounds_every_half_up() {
+    assert_eq!(half_of(1, Rounding::HalfUp), 1);
+    assert_eq!(half_of(3, Rounding::HalfUp), 2);
+    assert_eq!(half_of(5, Rounding::HalfUp), 3);
+    // just under half a cent still rounds down
+    assert_eq!(Rate::basis_points(4999).of(1, Rounding::HalfUp), 0);
+}
+
+#[test]
+fn half_even_rounds_to_the_even_cent() {
+    assert_eq!(half_of(1, Rounding::HalfEven), 0);
+    assert_eq!(half_of(3, Rounding::HalfEven), 2);
+    assert_eq!(half_of(5, Rounding::HalfEven), 2);
+    assert_eq!(half_of(7, Rounding::HalfEven), 4);
+    // anything past half a cent rounds up, whichever cent is even
+    assert_eq!(Rate::basis_points(5001).of(1, Rounding::HalfEven), 1);
+}
+
+#[test]
+fn down_and_up_ignore_the_half() {
+    assert_eq!(half_of(3, Rounding::Down), 1);
+    assert_eq!(Rate::basis_points(9999).of(1, Rounding::Down), 0);
+    assert_eq!(half_of(3, Rounding::Up), 2);
+    assert_eq!(Rate::basis_points(1).of(1, Rounding::Up), 1);
+    // whole cents are left alone by every rule
+    for rounding in [
+        Rounding::HalfUp,
+        Rounding::HalfEven,
+        Rounding::Down,
+        Rounding::Up,
+    ] {
+        assert_eq!(half_of(4, rounding), 2);
+    }
+}
+
+#[test]
+fn percentages_that_dont_fit() {
+    assert_eq!(Rate::percent(18), Some(Rate::basis_points(1800)));
+    assert_eq!(
+        Rate::percent(u32::MAX / 100),
+        Some(Rate::basis_points(u32::MAX / 100 * 100))
+    );
+    assert_eq!(Rate::percent(u32::MAX / 100 + 1), None);
+    assert_eq!(Rate::percent(50_000_000), None);
+}
+
+#[test]
+fn a_rate_of_a_huge_amount_stops_at_the_most_cents_there_are() {
+    let double = Rate::percent(200).expect("200% fits");
+    assert_eq!(double.of(u64::MAX / 2, Rounding::HalfUp), u64::MAX - 1);
+    assert_eq!(double.of(u64::MAX / 2 + 1, Rounding::HalfUp), u64::MAX);
+    assert_eq!(double.of(u64::MAX, Rounding::Down), u64::MAX);
+    assert_eq!(
+        Rate::basis_points(u32::MAX).of(u64::MAX, Rounding::Up),
+        u64::MAX
+    );
+    // up to 100% can never come to more than the amount itself
+    assert_eq!(
+        Rate::percent(100).unwrap().of(u64::MAX, Rounding::Up),
+        u64::MAX
+    );
+}
+
+// a served order of three salads at $7.25 and a breakfast at $12.50
+fn served_order() -> Order {
+    let mut restaurant = Restaurant::default();
+    let salad = Item::Appetizer(Appetizer::Salad);
+    let items = vec![
+        salad.clone(),
+        salad.clone(),
+        salad,
+        Item::Breakfast(restaurant.breakfast("rye")),
+    ];
+    let id = take_order(&mut restaurant, items).expect("everything is on the summer menu");
+    cook_order(&mut restaurant);
+    serve_order(&mut restaurant, id).expect("the order was cooked");
+    restaurant.order(id).expect("the order was taken").clone()
+}
+
+fn billing(rounding: Rounding) -> Billing {
+    Billing {
+        tax: Rate::basis_points(825),
+        rounding,
+        ..Billing::default()
+    }
+}
+
+// every part of the bill is shared out exactly, so the shares add up to each part and to the total
+fn assert_adds_up(bill: &Bill) {
+    let sum = |part: fn(&Share) -> u64| -> u64 { bill.shares.iter().map(part).sum() };
+    assert_eq!(sum(|share| share.items), bill.subtotal);
+    assert_eq!(sum(|share| share.tax), bill.tax);
+    assert_eq!(sum(|share| share.tip), bill.tip);
+    assert_eq!(
+        sum(|share| share.discount),
+        bill.discounts.iter().map(|(_, amount)| amount).sum::<u64>()
+    );
+    assert_eq!(sum(|share| share.total), bill.total);
+}
+
+#[test]
+fn even_splits_add_up() {
+    let order = served_order();
+    for rounding in [
+        Rounding::HalfUp,
+        Rounding::HalfEven,
+        Rounding::Down,
+        Rounding::Up,
+    ] {
+        for diners in 1..=13 {
+            let payment = Payment {
+                tip: Rate::basis_points(1750),
+                split: Split::Evenly(diners),
+                ..Payment::default()
+            };
+            let bill = bill(&order, &billing(rounding), &payment).expect("the order was served");
+            assert_eq!(bill.shares.len(), diners as usize);
+            assert_adds_up(&bill);
+            // nobody pays more than a cent more than anyone else for each of the four parts
+            let totals: Vec<u64> = bill.shares.iter().map(|share| share.total).collect();
+            let spread = totals.iter().max().unwrap() - totals.iter().min().unwrap();
+            assert!(spread <= 4, "{totals:?}");
+        }
+    }
+}
+
+#[test]
+fn splits_by_item_add_up() {
+    let order = served_order();
+    let splits = [
+        vec![vec![0], vec![1], vec![2], vec![3]],
+        // a shared salad, and a breakfast split three ways
+        vec![vec![0, 1, 3], vec![1, 2, 3], vec![3]],
+        vec![vec![0, 1, 2, 3], vec![0, 1, 2, 3], vec![0, 1, 2, 3]],
+        // listing a line twice for the same diner doesn't count twice
+        vec![vec![0, 0, 1], vec![2, 3, 3]],
+    ];
+    let payment = |split: &Vec<Vec<usize>>| Payment {
+        tip: Rate::basis_points(1800),
+        discounts: vec![Discount::Percent(Rate::basis_points(1000))],
+        split: Split::ByItem(split.clone()),
+        ..Payment::default()
+    };
+    for split in &splits {
+        let bill = bill(&order, &billing(Rounding::HalfEven), &payment(split))
+            .expect("every line is paid for");
+        assert_eq!(bill.shares.len(), split.len());
+        assert_adds_up(&bill);
+    }
+}
+
+#[test]
+fn splits_have_to_make_sense() {
+    let order = served_order();
+    let billing = Billing::default();
+    let split = |split| Payment {
+        split,
+        ..Payment::default()
+    };
+    assert_eq!(
+        bill(&order, &billing, &split(Split::Evenly(0))),
+        Err(BillingError::NoDiners)
+    );
+    assert_eq!(
+        bill(&order, &billing, &split(Split::ByItem(Vec::new()))),
+        Err(BillingError::NoDiners)
+    );
+    assert!(bill(&order, &billing, &split(Split::Evenly(MAX_DINERS))).is_ok());
+    // turned away before a share is worked out for each of them
+    assert_eq!(
+        bill(&order, &billing, &split(Split::Evenly(MAX_DINERS + 1))),
+        Err(BillingError::TooManyDiners(MAX_DINERS as usize + 1))
+    );
+    assert_eq!(
+        bill(&order, &billing, &split(Split::Evenly(4_000_000_000))),
+        Err(BillingError::TooManyDiners(4_000_000_000))
+    );
+    assert_eq!(
+        bill(&order, &billing, &split(Split::ByItem(vec![vec![0, 1, 2]]))),
+        Err(BillingError::Unassigned(3))
+    );
+    assert_eq!(
+        bill(
+            &order,
+            &billing,
+            &split(Split::ByItem(vec![vec![0, 1, 2, 3, 4]]))
+        ),
+        Err(BillingError::NoSuchLine(4))
+    );
+}
@@ -0,0 +1,109 @@
+// sending food back: only what was wrong is made again, and each line is only complained about once
+use restaurant::back_of_house::{cook_order, fix_incorrect_order};
+use restaurant::front_of_house::serving::{serve_order, take_order};
+use restaurant::{
+    remake_report, Appetizer, AuditEvent, Complaint, Dish, Item, OrderId, Problem, Restaurant,
+    StockEvent,
+};
+
+// a served breakfast with white toast and a salad, with the eggs and lettuce tracked
+fn served(restaurant: &mut Restaurant) -> OrderId {
+    restaurant.inventory_mut().restock("eggs", 20);
+    restaurant.inventory_mut().restock("lettuce", 20);
+    let breakfast = Item::Breakfast(restaurant.breakfast("white"));
+    let salad = Item::Appetizer(Appetizer::Salad);
+    let id = take_order(restaurant, vec![breakfast, salad]).expect("both are on the menu");
+    cook_order(restaurant);
+    serve_order(restaurant, id).expect("the order was cooked");
+    id
+}
+
+fn complaint(order: OrderId, lines: Vec<usize>) -> Complaint {
+    Complaint {
+        order,
+        lines,
+        problem: Problem::WrongItem,
+        comp: true,
+    }
+}
+
+#[test]
+fn only_the_lines_sent_back_are_made_again() {
+    let mut restaurant = Restaurant::default();
+    let id = served(&mut restaurant);
+    assert_eq!(restaurant.inventory().stock("eggs"), Some(18));
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(19));
+
+    fix_incorrect_order(&mut restaurant, complaint(id, vec![1])).expect("the order was served");
+    let order = restaurant.order(id).expect("the order was taken");
+    assert_eq!(
+        order
+            .lines_to_cook()
+            .map(|line| &line.item)
+            .collect::<Vec<_>>(),
+        vec![&Item::Appetizer(Appetizer::Salad)]
+    );
+    assert_eq!(cook_order(&mut restaurant), Some(id));
+
+    // the salad was made twice, the breakfast only once
+    assert_eq!(restaurant.inventory().stock("eggs"), Some(18));
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(18));
+    assert_eq!(
+        restaurant.inventory().ledger().last(),
+        Some(&StockEvent::Used {
+            order: id,
+            ingredient: String::from("lettuce"),
+            quantity: 1
+        })
+    );
+
+    // once it's been remade, nothing is left waiting to be made again
+    let order = restaurant.order(id).expect("the order was taken");
+    assert!(order.lines().iter().all(|line| !line.remaking));
+    assert_eq!(order.lines_to_cook().count(), 2);
+}
+
+#[test]
+fn a_line_listed_twice_is_only_sent_back_once() {
+    let mut restaurant = Restaurant::default();
+    let id = served(&mut restaurant);
+    fix_incorrect_order(&mut restaurant, complaint(id, vec![1, 1, 1]))
+        .expect("the order was served");
+
+    let events: Vec<&AuditEvent> = restaurant
+        .audit_trail()
+        .iter()
+        .map(|entry| &entry.event)
+        .collect();
+    assert_eq!(
+        events,
+        vec![
+            &AuditEvent::Complaint {
+                lines: vec![1],
+                problem: Problem::WrongItem
+            },
+            &AuditEvent::Comped {
+                line: 1,
+                amount: 725
+            },
+            &AuditEvent::Requeued,
+        ]
+    );
+    let report = remake_report(&restaurant);
+    let salads = report
+        .rates
+        .iter()
+        .find(|rate| rate.dish == Dish::Appetizer(Appetizer::Salad))
+        .expect("a salad was ordered");
+    assert_eq!((salads.ordered, salads.remade), (1, 1));
+}
+
+#[test]
+fn an_empty_complaint_is_about_everything() {
+    let mut restaurant = Restaurant::default();
+    let id = served(&mut restaurant);
+    fix_incorrect_order(&mut restaurant, complaint(id, Vec::new())).expect("the order was served");
+    cook_order(&mut restaurant);
+    assert_eq!(restaurant.inventory().stock("eggs"), Some(16));
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(18));
+}
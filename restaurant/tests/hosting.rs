@@ -0,0 +1,125 @@
+// the host stand: who gets seated first, and at which tables
+use restaurant::front_of_house::hosting::{
+    add_table, add_to_waitlist, estimated_wait, party_left, seat_at_table, HostingError,
+    MAX_COMBINED, MAX_PASSED_OVER,
+};
+use restaurant::{Event, Menu, PartyId, Priority, Restaurant, TableId, Time};
+
+fn restaurant_with_tables(capacities: &[u32]) -> Restaurant {
+    let mut restaurant = Restaurant::new(Menu::standard());
+    for &capacity in capacities {
+        add_table(&mut restaurant, capacity);
+    }
+    restaurant
+}
+
+fn arrive(restaurant: &mut Restaurant, name: &str, size: u32, priority: Priority) -> PartyId {
+    add_to_waitlist(restaurant, name, size, priority, 0).expect("the party fits somewhere")
+}
+
+// the tables each party in the events sat down at
+fn seated_at(restaurant: &mut Restaurant) -> Vec<(PartyId, Vec<TableId>)> {
+    restaurant
+        .take_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            Event::Seated { party, tables, .. } => Some((party, tables)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn high_priority_parties_go_first() {
+    let mut restaurant = restaurant_with_tables(&[2]);
+    let ann = arrive(&mut restaurant, "Ann", 2, Priority::Normal);
+    let bob = arrive(&mut restaurant, "Bob", 2, Priority::Normal);
+    let cat = arrive(&mut restaurant, "Cat", 2, Priority::High);
+    let dan = arrive(&mut restaurant, "Dan", 2, Priority::High);
+    let order: Vec<PartyId> = restaurant.waitlist().iter().map(|party| party.id).collect();
+    // high priority first, and within the same priority whoever arrived first
+    assert_eq!(order, vec![cat, dan, ann, bob]);
+
+    assert_eq!(seat_at_table(&mut restaurant, 10), vec![cat]);
+    party_left(&mut restaurant, cat).expect("Cat was seated");
+    assert_eq!(seat_at_table(&mut restaurant, 20), vec![dan]);
+    party_left(&mut restaurant, dan).expect("Dan was seated");
+    assert_eq!(seat_at_table(&mut restaurant, 30), vec![ann]);
+}
+
+#[test]
+fn big_parties_get_tables_pushed_together() {
+    let mut restaurant = restaurant_with_tables(&[4, 4, 2, 4]);
+    // no single table fits six, and 4 + 2 leaves fewer empty seats than 4 + 4
+    let six = arrive(&mut restaurant, "Six", 6, Priority::Normal);
+    assert_eq!(seat_at_table(&mut restaurant, 0), vec![six]);
+    assert_eq!(seated_at(&mut restaurant), vec![(six, vec![1, 3])]);
+
+    // the two tables left make eight
+    let eight = arrive(&mut restaurant, "Eight", 8, Priority::Normal);
+    assert_eq!(seat_at_table(&mut restaurant, 0), vec![eight]);
+    assert_eq!(seated_at(&mut restaurant), vec![(eight, vec![2, 4])]);
+}
+
+#[test]
+fn at_most_max_combined_tables() {
+    let mut restaurant = restaurant_with_tables(&[2, 2, 2, 2]);
+    let largest = 2 * MAX_COMBINED as u32;
+    let biggest = arrive(&mut restaurant, "Biggest", largest, Priority::Normal);
+    assert_eq!(seat_at_table(&mut restaurant, 0), vec![biggest]);
+    assert_eq!(seated_at(&mut restaurant), vec![(biggest, vec![1, 2, 3])]);
+
+    // there are enough seats on the floor, but not in MAX_COMBINED tables
+    assert_eq!(
+        add_to_waitlist(&mut restaurant, "Too many", largest + 1, Priority::High, 0),
+        Err(HostingError::PartyTooLarge {
+            size: largest + 1,
+            largest
+        })
+    );
+}
+
+#[test]
+fn a_big_party_is_only_passed_over_so_often() {
+    let mut restaurant = restaurant_with_tables(&[4, 2]);
+    let first = arrive(&mut restaurant, "First", 4, Priority::Normal);
+    assert_eq!(seat_at_table(&mut restaurant, 0), vec![first]);
+
+    // the big party needs both tables, so small parties that fit at the two top keep getting seated ahead of it
+    let big = arrive(&mut restaurant, "Big", 6, Priority::High);
+    for minute in 1..=MAX_PASSED_OVER {
+        let small = arrive(&mut restaurant, "Small", 2, Priority::Normal);
+        assert_eq!(seat_at_table(&mut restaurant, minute), vec![small]);
+        party_left(&mut restaurant, small).expect("the small party was seated");
+    }
+    assert_eq!(restaurant.waitlist()[0].passed_over, MAX_PASSED_OVER);
+
+    // now the two top is held for the big party, even though the next small party would fit
+    let last = arrive(&mut restaurant, "Last", 2, Priority::Normal);
+    assert!(seat_at_table(&mut restaurant, 10).is_empty());
+    party_left(&mut restaurant, first).expect("the first party was seated");
+    assert_eq!(seat_at_table(&mut restaurant, 20), vec![big]);
+    party_left(&mut restaurant, big).expect("the big party was seated");
+    assert_eq!(seat_at_table(&mut restaurant, 30), vec![last]);
+}
+
+#[test]
+fn huge_tables_and_late_times_dont_overflow() {
+    // two tables this big add up to more than a u32 holds
+    let mut restaurant = restaurant_with_tables(&[u32::MAX, u32::MAX, 4]);
+    let late = Time::MAX - 10;
+    let crowd = add_to_waitlist(&mut restaurant, "Crowd", u32::MAX, Priority::Normal, late)
+        .expect("one of the tables holds them");
+    assert_eq!(seat_at_table(&mut restaurant, late), vec![crowd]);
+    let pair = add_to_waitlist(&mut restaurant, "Pair", 2, Priority::Normal, late)
+        .expect("there are tables left");
+    assert_eq!(seat_at_table(&mut restaurant, late), vec![pair]);
+
+    // everyone is seated, so anyone else waits until a table frees up, which is past the end of the clock
+    let next = add_to_waitlist(&mut restaurant, "Next", u32::MAX, Priority::Normal, late)
+        .expect("the free table holds them");
+    assert_eq!(seat_at_table(&mut restaurant, late), vec![next]);
+    let last = add_to_waitlist(&mut restaurant, "Last", 3, Priority::Normal, late)
+        .expect("a table will free up");
+    assert_eq!(estimated_wait(&restaurant, last, late), Some(10));
+}
@@ -0,0 +1,223 @@
+// the stock: what queued orders hold on to, when alerts go out, and replaying the ledger
+use restaurant::back_of_house::{cook_order, fix_incorrect_order};
+use restaurant::front_of_house::serving::{serve_order, take_order};
+use restaurant::{
+    Appetizer, Complaint, Dish, Inventory, Item, OrderError, Problem, Recipes, Restaurant, Status,
+    StockAlert, StockEvent,
+};
+
+fn salad() -> Item {
+    Item::Appetizer(Appetizer::Salad)
+}
+
+fn out_of(dish: Dish, ingredient: &str) -> OrderError {
+    OrderError::OutOfStock {
+        dish,
+        ingredient: String::from(ingredient),
+    }
+}
+
+#[test]
+fn queued_orders_hold_their_ingredients() {
+    let mut restaurant = Restaurant::default();
+    restaurant.inventory_mut().restock("lettuce", 2);
+    // two is already low for lettuce
+    restaurant.inventory_mut().take_alerts();
+    take_order(&mut restaurant, vec![salad()]).expect("there's lettuce for one");
+    take_order(&mut restaurant, vec![salad()]).expect("there's lettuce for two");
+    // nothing has been cooked yet, but both heads of lettuce are spoken for
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(2));
+    assert_eq!(
+        take_order(&mut restaurant, vec![salad()]),
+        Err(out_of(Dish::Appetizer(Appetizer::Salad), "lettuce"))
+    );
+
+    cook_order(&mut restaurant);
+    assert_eq!(
+        take_order(&mut restaurant, vec![salad()]),
+        Err(out_of(Dish::Appetizer(Appetizer::Salad), "lettuce"))
+    );
+    cook_order(&mut restaurant);
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(0));
+    assert_eq!(
+        restaurant.inventory_mut().take_alerts(),
+        vec![StockAlert::EightySixed {
+            ingredient: String::from("lettuce")
+        }]
+    );
+}
+
+#[test]
+fn alerts_go_out_at_the_thresholds() {
+    let mut restaurant = Restaurant::default();
+    let eggs = || String::from("eggs");
+    // eggs get a low stock alert at 12, and a breakfast takes 2
+    restaurant.inventory_mut().restock("eggs", 14);
+    let breakfast = |restaurant: &mut Restaurant| {
+        let item = Item::Breakfast(restaurant.breakfast("white"));
+        let taken = take_order(restaurant, vec![item]);
+        cook_order(restaurant);
+        taken
+    };
+    breakfast(&mut restaurant).expect("there are 14 eggs");
+    assert_eq!(
+        restaurant.inventory_mut().take_alerts(),
+        vec![StockAlert::Low {
+            ingredient: eggs(),
+            left: 12
+        }]
+    );
+    // only once, not every time it goes down further
+    breakfast(&mut restaurant).expect("there are 12 eggs");
+    assert!(restaurant.inventory_mut().take_alerts().is_empty());
+
+    restaurant.inventory_mut().count("eggs", 2);
+    breakfast(&mut restaurant).expect("there are 2 eggs");
+    assert_eq!(
+        restaurant.inventory().eighty_sixed().collect::<Vec<_>>(),
+        vec!["eggs"]
+    );
+    assert_eq!(
+        restaurant.inventory_mut().take_alerts(),
+        vec![StockAlert::EightySixed { ingredient: eggs() }]
+    );
+    assert_eq!(
+        breakfast(&mut restaurant),
+        Err(out_of(Dish::Breakfast, "eggs"))
+    );
+
+    restaurant.inventory_mut().restock("eggs", 4);
+    assert_eq!(
+        restaurant.inventory_mut().take_alerts(),
+        vec![StockAlert::BackInStock { ingredient: eggs() }]
+    );
+    breakfast(&mut restaurant).expect("the eggs are back");
+}
+
+#[test]
+fn using_more_than_there_is_raises_an_alert() {
+    let mut restaurant = Restaurant::default();
+    restaurant.inventory_mut().restock("lettuce", 3);
+    let id = take_order(&mut restaurant, vec![salad(), salad()]).expect("there's lettuce for two");
+    // a stock take finds less than the ledger thought, after the order was taken
+    restaurant.inventory_mut().count("lettuce", 1);
+    restaurant.inventory_mut().take_alerts();
+
+    assert_eq!(cook_order(&mut restaurant), Some(id));
+    assert_eq!(restaurant.inventory().stock("lettuce"), Some(0));
+    assert_eq!(
+        restaurant.inventory_mut().take_alerts(),
+        vec![
+            StockAlert::Short {
+                ingredient: String::from("lettuce"),
+                missing: 1
+            },
+            StockAlert::EightySixed {
+                ingredient: String::from("lettuce")
+            },
+        ]
+    );
+}
+
+#[test]
+fn restocking_past_the_limit_stops_at_the_limit() {
+    let mut inventory = Inventory::default();
+    inventory.restock("eggs", u32::MAX);
+    inventory.restock("eggs", 5);
+    assert_eq!(inventory.stock("eggs"), Some(u32::MAX));
+}
+
+#[test]
+fn recipes_past_the_limit_stop_at_the_limit() {
+    let recipes = Recipes::from_toml(
+        r#"
+        [dishes]
+        breakfast = { eggs = 4294967295 }
+        salad = { lettuce = 4294967295 }
+        [toast]
+        rye = { eggs = 1 }
+        "#,
+    )
+    .expect("the recipes are valid");
+    let mut restaurant = Restaurant::default();
+    restaurant.inventory_mut().set_recipes(recipes);
+    let breakfast = Item::Breakfast(restaurant.breakfast("rye"));
+    let needed = restaurant.inventory().recipes().ingredients(&breakfast);
+    assert_eq!(needed.get("eggs"), Some(&u32::MAX));
+
+    // lettuce isn't being counted, so two salads can be cooked however much they take
+    let id = take_order(&mut restaurant, vec![salad(), salad()]).expect("lettuce isn't tracked");
+    assert_eq!(cook_order(&mut restaurant), Some(id));
+    assert_eq!(restaurant.inventory().stock("lettuce"), None);
+}
+
+#[test]
+fn replaying_the_ledger_gets_back_to_the_same_stock() {
+    let mut restaurant = Restaurant::default();
+    restaurant.inventory_mut().restock("lettuce", 10);
+    restaurant.inventory_mut().restock("eggs", 20);
+    for _ in 0..3 {
+        let breakfast = Item::Breakfast(restaurant.breakfast("rye"));
+        take_order(&mut restaurant, vec![breakfast, salad()]).expect("there's plenty");
+        cook_order(&mut restaurant);
+    }
+    restaurant.inventory_mut().count("lettuce", 5);
+    let ledger = restaurant.inventory().ledger().to_vec();
+
+    let mut replayed = Inventory::default();
+    replayed.replay(&ledger);
+    assert_eq!(
+        replayed.stocks().collect::<Vec<_>>(),
+        vec![("eggs", 14), ("lettuce", 5)]
+    );
+    assert_eq!(replayed.ledger(), ledger.as_slice());
+    // none of it is news, so there's nothing to tell the kitchen
+    assert!(replayed.take_alerts().is_empty());
+
+    // just the deliveries, to see what the stock would be if nothing had been cooked
+    let restocks: Vec<StockEvent> = ledger
+        .iter()
+        .filter(|event| matches!(event, StockEvent::Restocked { .. }))
+        .cloned()
+        .collect();
+    replayed.replay(&restocks);
+    assert_eq!(
+        replayed.stocks().collect::<Vec<_>>(),
+        vec![("eggs", 20), ("lettuce", 10)]
+    );
+}
+
+#[test]
+fn a_remake_needs_the_ingredients_too() {
+    let mut restaurant = Restaurant::default();
+    restaurant.inventory_mut().restock("lettuce", 2);
+    let id = take_order(&mut restaurant, vec![salad()]).expect("there's lettuce");
+    cook_order(&mut restaurant);
+    serve_order(&mut restaurant, id).expect("the order was cooked");
+    // the last head of lettuce is held for the next order
+    take_order(&mut restaurant, vec![salad()]).expect("there's lettuce for one more");
+
+    let complaint = Complaint {
+        order: id,
+        lines: vec![0],
+        problem: Problem::WrongItem,
+        comp: true,
+    };
+    assert_eq!(
+        fix_incorrect_order(&mut restaurant, complaint.clone()),
+        Err(out_of(Dish::Appetizer(Appetizer::Salad), "lettuce"))
+    );
+    // nothing about the order changed
+    let order = restaurant.order(id).expect("the order was taken");
+    assert_eq!(order.status(), Status::Served);
+    assert!(order
+        .lines()
+        .iter()
+        .all(|line| !line.comped && !line.remaking));
+    assert!(restaurant.audit_trail().is_empty());
+
+    // once there's lettuce again it can be made again
+    restaurant.inventory_mut().restock("lettuce", 1);
+    fix_incorrect_order(&mut restaurant, complaint).expect("there's lettuce now");
+    assert_eq!(cook_order(&mut restaurant), Some(id));
+}
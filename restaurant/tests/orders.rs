@@ -0,0 +1,166 @@
+// an order's life from being taken to being paid for, using only what customers of the library can see
+use restaurant::back_of_house::cook_order;
+use restaurant::front_of_house::serving::{serve_order, take_order, take_payment};
+use restaurant::{
+    Appetizer, BillingError, Cents, Dish, Item, OrderError, OrderId, Payment, Rate, Restaurant,
+    Split, Status,
+};
+
+fn salad() -> Item {
+    Item::Appetizer(Appetizer::Salad)
+}
+
+fn order_salad(restaurant: &mut Restaurant) -> OrderId {
+    take_order(restaurant, vec![salad()]).expect("salad is on the summer menu")
+}
+
+fn status(restaurant: &Restaurant, id: OrderId) -> Status {
+    restaurant.order(id).expect("the order was taken").status()
+}
+
+#[test]
+fn statuses_go_one_step_at_a_time() {
+    assert_eq!(Status::Placed.next(), Some(Status::Cooking));
+    assert_eq!(Status::Cooking.next(), Some(Status::Ready));
+    assert_eq!(Status::Ready.next(), Some(Status::Served));
+    assert_eq!(Status::Served.next(), Some(Status::Paid));
+    assert_eq!(Status::Paid.next(), None);
+}
+
+#[test]
+fn an_order_goes_from_placed_to_paid() {
+    let mut restaurant = Restaurant::default();
+    let id = order_salad(&mut restaurant);
+    assert_eq!(status(&restaurant, id), Status::Placed);
+
+    // it can't skip the kitchen
+    assert_eq!(
+        serve_order(&mut restaurant, id),
+        Err(OrderError::WrongStatus {
+            id,
+            from: Status::Placed,
+            to: Status::Served
+        })
+    );
+    assert_eq!(
+        take_payment(&mut restaurant, id, &Payment::default()),
+        Err(BillingError::Order(OrderError::WrongStatus {
+            id,
+            from: Status::Placed,
+            to: Status::Paid
+        }))
+    );
+    assert_eq!(status(&restaurant, id), Status::Placed);
+
+    assert_eq!(cook_order(&mut restaurant), Some(id));
+    assert_eq!(status(&restaurant, id), Status::Ready);
+    assert_eq!(serve_order(&mut restaurant, id), Ok(()));
+    assert_eq!(status(&restaurant, id), Status::Served);
+
+    // serving it twice doesn't work either
+    assert_eq!(
+        serve_order(&mut restaurant, id),
+        Err(OrderError::WrongStatus {
+            id,
+            from: Status::Served,
+            to: Status::Served
+        })
+    );
+
+    take_payment(&mut restaurant, id, &Payment::default()).expect("the order was served");
+    assert_eq!(status(&restaurant, id), Status::Paid);
+    assert_eq!(
+        take_payment(&mut restaurant, id, &Payment::default()),
+        Err(BillingError::Order(OrderError::WrongStatus {
+            id,
+            from: Status::Paid,
+            to: Status::Paid
+        }))
+    );
+}
+
+#[test]
+fn orders_that_were_never_taken() {
+    let mut restaurant = Restaurant::default();
+    assert_eq!(
+        serve_order(&mut restaurant, 7),
+        Err(OrderError::UnknownOrder(7))
+    );
+    assert_eq!(
+        take_payment(&mut restaurant, 7, &Payment::default()),
+        Err(BillingError::Order(OrderError::UnknownOrder(7)))
+    );
+    assert_eq!(
+        take_order(&mut restaurant, Vec::new()),
+        Err(OrderError::Empty)
+    );
+    // nothing was taken, so the kitchen has nothing to do
+    assert_eq!(cook_order(&mut restaurant), None);
+}
+
+#[test]
+fn the_kitchen_cooks_in_the_order_orders_came_in() {
+    let mut restaurant = Restaurant::default();
+    let breakfast = Item::Breakfast(restaurant.breakfast("rye"));
+    let first = order_salad(&mut restaurant);
+    let second = take_order(&mut restaurant, vec![breakfast]).expect("breakfast is on the menu");
+    let third = order_salad(&mut restaurant);
+    assert_eq!((first, second, third), (1, 2, 3));
+    assert_eq!(
+        restaurant.kitchen_queue().collect::<Vec<_>>(),
+        vec![first, second, third]
+    );
+
+    assert_eq!(cook_order(&mut restaurant), Some(first));
+    // an order taken now still waits behind the ones already in the queue
+    let fourth = order_salad(&mut restaurant);
+    assert_eq!(cook_order(&mut restaurant), Some(second));
+    assert_eq!(cook_order(&mut restaurant), Some(third));
+    assert_eq!(cook_order(&mut restaurant), Some(fourth));
+    assert_eq!(cook_order(&mut restaurant), None);
+    assert_eq!(restaurant.kitchen_queue().count(), 0);
+}
+
+#[test]
+fn taking_payment_keeps_the_bill() {
+    let mut restaurant = Restaurant::default();
+    restaurant.billing_mut().tax = Rate::basis_points(825);
+    let breakfast = Item::Breakfast(restaurant.breakfast("wheat"));
+    let id = take_order(&mut restaurant, vec![breakfast, salad()]).expect("both are on the menu");
+    cook_order(&mut restaurant);
+    serve_order(&mut restaurant, id).expect("the order was cooked");
+    assert_eq!(restaurant.payment(id), None);
+
+    let payment = Payment {
+        tip: Rate::percent(20).expect("20% is a rate"),
+        split: Split::Evenly(2),
+        ..Payment::default()
+    };
+    let bill = take_payment(&mut restaurant, id, &payment).expect("the order was served");
+    // $12.50 + $7.25, 8.25% tax on $19.75 is $1.629..., and a 20% tip is $3.95
+    assert_eq!(bill.subtotal, 1975);
+    assert_eq!(bill.tax, 163);
+    assert_eq!(bill.tip, 395);
+    assert_eq!(bill.total, 1975 + 163 + 395);
+    // the items, tax and tip are each split on their own, and the odd cent of each goes to the first diner
+    assert_eq!(
+        bill.shares
+            .iter()
+            .map(|share| share.total)
+            .collect::<Vec<_>>(),
+        vec![988 + 82 + 198, 987 + 81 + 197]
+    );
+    assert_eq!(restaurant.payment(id), Some(&bill));
+    assert_eq!(restaurant.payments().count(), 1);
+}
+
+#[test]
+fn the_total_stops_at_the_most_a_bill_can_say() {
+    let mut restaurant = Restaurant::default();
+    restaurant
+        .menu_mut()
+        .add(Dish::Appetizer(Appetizer::Salad), Cents::MAX);
+    let id = take_order(&mut restaurant, vec![salad(), salad()]).expect("salad is on the menu");
+    let order = restaurant.order(id).expect("the order was taken");
+    assert_eq!(order.total(), Cents::MAX);
+}
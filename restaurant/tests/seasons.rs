@@ -0,0 +1,148 @@
+// the seasonal menu file: what a valid one gives back, what's wrong with an invalid one, and which season a day is in
+use restaurant::front_of_house::serving::take_order;
+use restaurant::seasons::SeasonError;
+use restaurant::{Appetizer, Dish, Item, MonthDay, OrderError, Restaurant, Season, SeasonalMenu};
+
+const SEASONS: &str = r#"
+[seasons.spring]
+starts = "03-01"
+fruit = "rhubarb"
+
+[seasons.summer]
+starts = "06-01"
+fruit = "cherries"
+
+[seasons.autumn]
+starts = "09-01"
+fruit = "plums"
+
+[seasons.winter]
+starts = "12-01"
+fruit = "clementines"
+"#;
+
+fn date(month: u32, day: u32) -> MonthDay {
+    MonthDay::new(month, day).expect("a real date")
+}
+
+#[test]
+fn a_valid_file() {
+    let text = format!(
+        "{SEASONS}\n[availability]\nsoup = [\"winter\"]\nSalad = [\"spring\", \"summer\"]\n"
+    );
+    let menu = SeasonalMenu::from_toml(&text).expect("it's valid");
+    assert_eq!(menu.starts(Season::Spring), date(3, 1));
+    assert_eq!(menu.starts(Season::Winter), date(12, 1));
+    assert_eq!(menu.fruit(Season::Autumn), "plums");
+    let soup = Dish::Appetizer(Appetizer::Soup);
+    let salad = Dish::Appetizer(Appetizer::Salad);
+    assert!(menu.is_available(soup, Season::Winter));
+    assert!(!menu.is_available(soup, Season::Summer));
+    assert!(menu.is_available(salad, Season::Summer));
+    assert!(!menu.is_available(salad, Season::Autumn));
+    // dishes that aren't listed can be made all year
+    assert!(Season::ALL
+        .iter()
+        .all(|&season| menu.is_available(Dish::Breakfast, season)));
+
+    // the file the restaurant ships with is valid too
+    assert_eq!(SeasonalMenu::standard().fruit(Season::Summer), "peaches");
+}
+
+#[test]
+fn every_season_has_to_be_there() {
+    let without_autumn = SEASONS.replace("[seasons.autumn]", "[seasons.not_autumn]");
+    // an unknown season name is caught by the TOML layout first
+    assert!(matches!(
+        SeasonalMenu::from_toml(&without_autumn),
+        Err(SeasonError::Toml(_))
+    ));
+    let end = SEASONS
+        .find("[seasons.winter]")
+        .expect("winter is in there");
+    assert!(matches!(
+        SeasonalMenu::from_toml(&SEASONS[..end]),
+        Err(SeasonError::MissingSeason(Season::Winter))
+    ));
+}
+
+#[test]
+fn two_seasons_cant_start_together() {
+    let text = SEASONS.replace("\"09-01\"", "\"06-01\"");
+    match SeasonalMenu::from_toml(&text) {
+        Err(SeasonError::SameStart(start)) => assert_eq!(start, date(6, 1)),
+        other => panic!("expected SameStart, got {other:?}"),
+    }
+}
+
+#[test]
+fn dates_have_to_be_real() {
+    for bad in ["02-30", "13-01", "00-10", "6/1", "June 1st", "06-"] {
+        let text = SEASONS.replace("\"06-01\"", &format!("\"{bad}\""));
+        match SeasonalMenu::from_toml(&text) {
+            Err(SeasonError::BadDate(date)) => assert_eq!(date, bad),
+            other => panic!("expected BadDate for {bad}, got {other:?}"),
+        }
+    }
+    // the 29th of February comes round often enough to count
+    let text = SEASONS.replace("\"03-01\"", "\"02-29\"");
+    assert_eq!(
+        SeasonalMenu::from_toml(&text)
+            .expect("it's valid")
+            .starts(Season::Spring),
+        date(2, 29)
+    );
+}
+
+#[test]
+fn availability_has_to_name_real_dishes() {
+    let text = format!("{SEASONS}\n[availability]\nsoup = [\"winter\"]\npie = [\"autumn\"]\n");
+    match SeasonalMenu::from_toml(&text) {
+        Err(SeasonError::UnknownDish(dish)) => assert_eq!(dish, "pie"),
+        other => panic!("expected UnknownDish, got {other:?}"),
+    }
+}
+
+#[test]
+fn winter_wraps_round_the_new_year() {
+    let menu = SeasonalMenu::from_toml(SEASONS).expect("it's valid");
+    for (month, day, season) in [
+        (11, 30, Season::Autumn),
+        (12, 1, Season::Winter),
+        (12, 31, Season::Winter),
+        // before the first start date of the year, it's still the winter that started in December
+        (1, 1, Season::Winter),
+        (2, 29, Season::Winter),
+        (3, 1, Season::Spring),
+        (5, 31, Season::Spring),
+        (6, 1, Season::Summer),
+        (9, 1, Season::Autumn),
+    ] {
+        assert_eq!(
+            menu.season_on(date(month, day)),
+            season,
+            "{month:02}-{day:02}"
+        );
+    }
+}
+
+#[test]
+fn out_of_season_dishes_cant_be_ordered() {
+    let mut restaurant = Restaurant::default();
+    restaurant.set_date(date(1, 15));
+    assert_eq!(restaurant.season(), Season::Winter);
+    // the standard menu has no salad in winter
+    assert_eq!(
+        take_order(&mut restaurant, vec![Item::Appetizer(Appetizer::Salad)]),
+        Err(OrderError::OutOfSeason {
+            dish: Dish::Appetizer(Appetizer::Salad),
+            season: Season::Winter
+        })
+    );
+    take_order(&mut restaurant, vec![Item::Appetizer(Appetizer::Soup)])
+        .expect("soup is a winter dish");
+
+    restaurant.set_date(date(7, 1));
+    take_order(&mut restaurant, vec![Item::Appetizer(Appetizer::Salad)])
+        .expect("salad is back in summer");
+}
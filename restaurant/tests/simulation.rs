@@ -0,0 +1,127 @@
+// simulated services: the same seed plays out the same way, the numbers in the report add up, and configs that can't
+// be played out are turned down
+use restaurant::simulation::MAX_ARRIVALS_PER_HOUR;
+use restaurant::{simulate, SimConfig, SimError};
+
+#[test]
+fn the_same_seed_gives_the_same_service() {
+    for seed in [0, 1, 42] {
+        let config = SimConfig {
+            seed,
+            ..SimConfig::default()
+        };
+        let first = simulate(&config).expect("the default config makes sense");
+        let second = simulate(&config).expect("the default config makes sense");
+        assert_eq!(first, second);
+        assert_eq!(first.to_string(), second.to_string());
+        assert!(first.arrived > 0);
+    }
+}
+
+#[test]
+fn the_report_adds_up() {
+    let quiet = SimConfig::default();
+    // parties too big for even the tables pushed together, and more of them than there's room for, so some walk out
+    let busy = SimConfig {
+        tables: vec![2, 2, 4],
+        party_size: (1, 12),
+        arrivals_per_hour: 40.0,
+        cooks: 1,
+        ..SimConfig::default()
+    };
+    let (mut walked_out, mut turned_away) = (0, 0);
+    for config in [quiet, busy] {
+        for seed in 0..20 {
+            let config = SimConfig {
+                seed,
+                ..config.clone()
+            };
+            let report = simulate(&config).expect("the config makes sense");
+            assert_eq!(
+                report.revenue,
+                report.revenue_per_hour.iter().sum::<u64>(),
+                "seed {seed}"
+            );
+            assert!(
+                (0.0..=1.0).contains(&report.kitchen_utilisation),
+                "seed {seed}: {}",
+                report.kitchen_utilisation
+            );
+            // anyone still waiting when the last party leaves is in none of them
+            assert!(
+                report.seated + report.walked_out + report.turned_away <= report.arrived,
+                "seed {seed}: {report:?}"
+            );
+            walked_out += report.walked_out;
+            turned_away += report.turned_away;
+        }
+    }
+    assert!(walked_out > 0 && turned_away > 0);
+}
+
+#[test]
+fn the_kitchen_and_dining_room_need_someone_and_somewhere() {
+    let config = SimConfig {
+        cooks: 0,
+        ..SimConfig::default()
+    };
+    assert_eq!(simulate(&config), Err(SimError::NoCooks));
+    let config = SimConfig {
+        tables: Vec::new(),
+        ..SimConfig::default()
+    };
+    assert_eq!(simulate(&config), Err(SimError::NoTables));
+    let config = SimConfig {
+        tables: vec![4, 0, 2],
+        ..SimConfig::default()
+    };
+    assert_eq!(simulate(&config), Err(SimError::EmptyTable(1)));
+}
+
+#[test]
+fn a_party_needs_someone_in_it() {
+    let config = SimConfig {
+        party_size: (0, 4),
+        ..SimConfig::default()
+    };
+    assert_eq!(simulate(&config), Err(SimError::EmptyParty));
+}
+
+#[test]
+fn arrival_rates_have_to_be_possible() {
+    for rate in [
+        f64::INFINITY,
+        f64::NEG_INFINITY,
+        f64::NAN,
+        -1.0,
+        MAX_ARRIVALS_PER_HOUR + 1.0,
+        1e300,
+    ] {
+        let config = SimConfig {
+            arrivals_per_hour: rate,
+            ..SimConfig::default()
+        };
+        assert!(
+            matches!(simulate(&config), Err(SimError::ArrivalRate(_))),
+            "{rate} was accepted"
+        );
+    }
+
+    // nobody coming at all is fine, and so is someone every minute
+    let quiet = SimConfig {
+        arrivals_per_hour: 0.0,
+        ..SimConfig::default()
+    };
+    assert_eq!(simulate(&quiet).map(|report| report.arrived), Ok(0));
+    let busy = SimConfig {
+        arrivals_per_hour: MAX_ARRIVALS_PER_HOUR,
+        ..SimConfig::default()
+    };
+    let report = simulate(&busy).expect("a party a minute is possible");
+    // the doors are open for four hours and parties come at least a minute apart
+    assert!(
+        report.arrived <= 4 * 60,
+        "{} parties arrived",
+        report.arrived
+    );
+}
@@ -0,0 +1,161 @@
+// saving to a JSON lines file: what comes back, how big the file gets, saves cut short, old versions and which files
+// it refuses
+use restaurant::back_of_house::cook_order;
+use restaurant::front_of_house::serving::{serve_order, take_order, take_payment};
+use restaurant::{
+    Appetizer, Item, JsonLinesStorage, MemoryStorage, Occupant, Payment, Restaurant, Storage,
+    StorageError, Table,
+};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+// a file of its own for each test, so they can run at the same time
+fn state_file(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("restaurant-{}-{name}.jsonl", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+// take, cook, serve and pay for a salad, saving after every step like the API does
+fn busy_evening(restaurant: &mut Restaurant, storage: &mut dyn Storage, orders: usize) {
+    restaurant.inventory_mut().restock("lettuce", 1000);
+    for _ in 0..orders {
+        let id = take_order(restaurant, vec![Item::Appetizer(Appetizer::Salad)])
+            .expect("salad is on the summer menu");
+        restaurant.save(storage).expect("the state can be saved");
+        cook_order(restaurant);
+        restaurant.save(storage).expect("the state can be saved");
+        serve_order(restaurant, id).expect("the order was cooked");
+        restaurant.save(storage).expect("the state can be saved");
+        take_payment(restaurant, id, &Payment::default()).expect("the order was served");
+        restaurant.save(storage).expect("the state can be saved");
+    }
+}
+
+#[test]
+fn the_file_holds_the_same_as_memory() {
+    let path = state_file("same");
+    let mut file = JsonLinesStorage::open(&path).expect("the file can be created");
+    let mut memory = MemoryStorage::new();
+    busy_evening(&mut Restaurant::default(), &mut file, 5);
+    busy_evening(&mut Restaurant::default(), &mut memory, 5);
+    assert_eq!(file.load().unwrap(), memory.load().unwrap());
+
+    // and it's all still there after opening the file again
+    let reopened = JsonLinesStorage::open(&path).expect("the file was written");
+    assert_eq!(reopened.load().unwrap(), memory.load().unwrap());
+    let mut restored = Restaurant::default();
+    restored.restore(&reopened).expect("the file was written");
+    assert_eq!(restored.payments().count(), 5);
+    assert_eq!(restored.inventory().stock("lettuce"), Some(995));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn the_file_grows_with_the_restaurant_not_with_every_save() {
+    let path = state_file("growth");
+    let mut storage = JsonLinesStorage::open(&path).expect("the file can be created");
+    let mut restaurant = Restaurant::default();
+    busy_evening(&mut restaurant, &mut storage, 200);
+    let lines = fs::read_to_string(&path).unwrap().lines().count();
+    // an order and a payment for each salad, plus the menu, seating, audit trail, stock ledger and header, with no
+    // more than as many replaced records again and a little slack
+    let needed = 2 * 200 + 4;
+    assert!(lines <= 1 + 2 * needed + 64, "{lines} lines");
+
+    // saving when nothing has changed writes nothing
+    let size = fs::metadata(&path).unwrap().len();
+    restaurant.save(&mut storage).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().len(), size);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_save_cut_short_is_left_out() {
+    let path = state_file("cut-short");
+    let mut storage = JsonLinesStorage::open(&path).expect("the file can be created");
+    busy_evening(&mut Restaurant::default(), &mut storage, 2);
+    let before = storage.load().unwrap();
+    // the power goes out halfway through writing an order
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(br#"{"order":{"id":3,"lines":["#).unwrap();
+    drop(file);
+
+    let mut reopened = JsonLinesStorage::open(&path).expect("the half a record is skipped");
+    assert_eq!(reopened.load().unwrap(), before);
+    assert!(fs::read_to_string(&path).unwrap().ends_with('\n'));
+
+    // and the next save isn't stuck to the end of the half a record
+    let mut restaurant = Restaurant::default();
+    restaurant.restore(&reopened).expect("the file was written");
+    busy_evening(&mut restaurant, &mut reopened, 1);
+    let reopened = JsonLinesStorage::open(&path).expect("the file was written");
+    let mut restored = Restaurant::default();
+    restored.restore(&reopened).expect("the file was written");
+    assert_eq!(restored.payments().count(), 3);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn a_version_1_file_is_brought_forward() {
+    let path = state_file("version-1");
+    // version 1 kept who was at a table as a [party, since] pair
+    let tables =
+        r#"[{"id":1,"capacity":4,"occupant":[7,600]},{"id":2,"capacity":2,"occupant":null}]"#;
+    fs::write(
+        &path,
+        format!("{{\"version\":1}}\n{{\"seating\":{{\"tables\":{tables},\"waitlist\":[]}}}}\n"),
+    )
+    .unwrap();
+
+    let storage = JsonLinesStorage::open(&path).expect("version 1 can be migrated");
+    let expected = vec![
+        Table {
+            id: 1,
+            capacity: 4,
+            occupant: Some(Occupant {
+                party: 7,
+                since: 600,
+            }),
+        },
+        Table {
+            id: 2,
+            capacity: 2,
+            occupant: None,
+        },
+    ];
+    assert_eq!(
+        storage.load().unwrap().seating,
+        Some((expected.clone(), Vec::new()))
+    );
+    // the file was rewritten, so whatever is saved next goes after a header with the current version
+    let contents = fs::read_to_string(&path).unwrap();
+    assert_eq!(contents.lines().next(), Some(r#"{"version":2}"#));
+
+    let mut restaurant = Restaurant::default();
+    restaurant.restore(&storage).expect("the file was migrated");
+    assert_eq!(restaurant.tables(), expected.as_slice());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn only_known_versions_are_read() {
+    for (header, version) in [(r#"{"version":0}"#, 0), (r#"{"version":3}"#, 3)] {
+        let path = state_file(&format!("version-{version}"));
+        fs::write(&path, format!("{header}\n")).unwrap();
+        match JsonLinesStorage::open(&path) {
+            Err(StorageError::UnsupportedVersion(found)) => assert_eq!(found, version),
+            other => panic!("version {version} was opened: {other:?}"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    let path = state_file("no-version");
+    fs::write(&path, "{}\n").unwrap();
+    assert!(matches!(
+        JsonLinesStorage::open(&path),
+        Err(StorageError::MissingVersion)
+    ));
+    fs::remove_file(&path).unwrap();
+}